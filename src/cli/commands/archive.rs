@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::core::repo::Intention;
+
+#[derive(clap::Args)]
+pub struct ArchiveArgs {
+    /// Repo name or path
+    pub repo: String,
+}
+
+/// Set or clear the archived intention on a repo.
+pub fn run(
+    args: ArchiveArgs,
+    archive: bool,
+    root: Option<&Path>,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+
+    let mut repo = super::status::resolve_repo(&index, &args.repo, false)?;
+
+    let is_archived = repo.intention == Some(Intention::Archived);
+    if archive && !is_archived {
+        repo.intention = Some(Intention::Archived);
+        index.set_intention(repo.id, repo.intention)?;
+    } else if !archive && is_archived {
+        repo.intention = None;
+        index.set_intention(repo.id, None)?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &repo)?;
+            println!();
+        }
+        OutputFormat::Paths => {
+            println!("{}", repo.path.display());
+        }
+        OutputFormat::PathsNull => {
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+        }
+        OutputFormat::Porcelain => {
+            println!("{}", crate::cli::output::porcelain_line(&repo));
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(
+                std::slice::from_ref(&repo),
+                format,
+                &mut std::io::stdout(),
+            )?;
+        }
+        OutputFormat::Human => {
+            if archive {
+                println!(
+                    "  {} {} hidden from default listings",
+                    "archived:".green().bold(),
+                    repo.name,
+                );
+            } else {
+                println!(
+                    "  {} {} restored to default listings",
+                    "unarchived:".green().bold(),
+                    repo.name,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
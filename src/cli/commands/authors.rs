@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::config;
+use kissa::core::git_ops;
+use kissa::core::permissions::{self, OperationClass};
+
+#[derive(clap::Args)]
+pub struct AuthorsArgs {
+    /// Repo name or path
+    pub repo: String,
+
+    /// Look at only this many of the newest commits
+    #[arg(long, value_name = "N", default_value_t = 5000)]
+    pub limit: usize,
+}
+
+/// List the distinct author emails in a repo's recent history with their
+/// commit counts, to spot personal addresses in work repos and vice versa.
+pub fn run(args: AuthorsArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let cfg = config::load_config()?;
+    let index = crate::cli::open_index(root)?;
+    let repo = super::status::resolve_repo(&index, &args.repo, false)?;
+    permissions::check_permission(OperationClass::Read, &repo.path, &cfg, false)?;
+
+    let authors = git_ops::author_emails(&repo.path, args.limit)?;
+
+    match format {
+        OutputFormat::Json => {
+            let authors: Vec<_> = authors
+                .iter()
+                .map(|(email, commits)| serde_json::json!({ "email": email, "commits": commits }))
+                .collect();
+            let result = serde_json::json!({
+                "repo": repo.name,
+                "path": repo.path,
+                "limit": args.limit,
+                "authors": authors,
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Human => {
+            println!(
+                "  {} {} {}",
+                "authors:".green().bold(),
+                repo.name.bold(),
+                format!("(newest {} commits at most)", args.limit).dimmed(),
+            );
+            for (email, commits) in &authors {
+                println!("    {:>6} {}", commits, email);
+            }
+        }
+        _ => {
+            for (email, commits) in &authors {
+                println!("{}\t{}", commits, email);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 use owo_colors::OwoColorize;
@@ -7,6 +7,7 @@ use crate::cli::OutputFormat;
 use kissa::config;
 use kissa::core::classify;
 use kissa::core::index::Index;
+use kissa::core::repo::{Intention, Repo};
 
 #[derive(clap::Args)]
 pub struct ClassifyArgs {
@@ -17,11 +18,31 @@ pub struct ClassifyArgs {
     /// Analyze index and suggest classification rules
     #[arg(long)]
     pub suggest: bool,
+
+    /// Archive old, clean, fully pushed repos that have no intention set
+    #[arg(long)]
+    pub age_out: bool,
+
+    /// With --age-out, time since last commit, e.g. 6mo or 1y (default: defaults.age_out_days)
+    #[arg(long, value_name = "AGE", requires = "age_out", value_parser = crate::cli::parse_age)]
+    pub older_than: Option<chrono::Duration>,
+
+    /// With --age-out, list candidates without changing the index
+    #[arg(long, requires = "age_out")]
+    pub dry_run: bool,
+
+    /// Show which rules and heuristics decide a repo's classification
+    #[arg(long, value_name = "REPO", conflicts_with_all = ["reapply", "suggest", "age_out"])]
+    pub explain: Option<String>,
 }
 
-pub fn run(args: ClassifyArgs, format: OutputFormat) -> anyhow::Result<()> {
+pub fn run(args: ClassifyArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
     let cfg = config::load_config()?;
-    let index = Index::open(&config::index_path())?;
+    let index = crate::cli::open_index(root)?;
+
+    if let Some(ref repo) = args.explain {
+        return run_explain(&index, &cfg, repo, format);
+    }
 
     if args.suggest {
         return run_suggest(&index, format);
@@ -31,23 +52,42 @@ pub fn run(args: ClassifyArgs, format: OutputFormat) -> anyhow::Result<()> {
         return run_reapply(&index, &cfg, format);
     }
 
+    if args.age_out {
+        let days = args
+            .older_than
+            .map_or(cfg.defaults.age_out_days, |age| age.num_days() as u64);
+        return run_age_out(&index, days, args.dry_run, format);
+    }
+
     // Default: show classification summary
     run_summary(&index, format)
 }
 
-fn run_summary(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
-    let repos = index.all_repos()?;
-
-    let mut managed_counts: HashMap<String, usize> = HashMap::new();
-    let mut unclassified = 0;
-
-    for repo in &repos {
+/// Group tool-managed repos by their `managed_by` tool, in tool name order.
+/// Repos no tool manages are left out.
+pub(crate) fn group_by_manager<'a>(
+    repos: impl IntoIterator<Item = &'a Repo>,
+) -> BTreeMap<&'a str, Vec<&'a Repo>> {
+    let mut groups: BTreeMap<&str, Vec<&Repo>> = BTreeMap::new();
+    for repo in repos {
         if let Some(ref mb) = repo.managed_by {
-            *managed_counts.entry(mb.clone()).or_default() += 1;
-        } else if repo.ownership.is_none() && repo.intention.is_none() {
-            unclassified += 1;
+            groups.entry(mb.as_str()).or_default().push(repo);
         }
     }
+    groups
+}
+
+fn run_summary(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
+    let repos = index.all_repos()?;
+
+    let managed_counts: BTreeMap<&str, usize> = group_by_manager(&repos)
+        .into_iter()
+        .map(|(tool, members)| (tool, members.len()))
+        .collect();
+    let unclassified = repos
+        .iter()
+        .filter(|r| r.managed_by.is_none() && r.ownership.is_none() && r.intention.is_none())
+        .count();
 
     match format {
         OutputFormat::Json => {
@@ -76,11 +116,7 @@ fn run_summary(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
             }
 
             if unclassified > 0 {
-                println!(
-                    "  {} {} repos unclassified",
-                    "note:".yellow(),
-                    unclassified,
-                );
+                println!("  {} {} repos unclassified", "note:".yellow(), unclassified,);
                 println!(
                     "  {} run {} to see suggested rules",
                     "hint:".dimmed(),
@@ -102,30 +138,8 @@ fn run_reapply(
     let mut changed = 0;
 
     for mut repo in repos {
-        let old_managed = repo.managed_by.clone();
-        let old_ownership = repo.ownership.clone();
-        let old_intention = repo.intention.clone();
-        let old_category = repo.category;
-        let mut old_tags = repo.tags.clone();
-        old_tags.sort();
-
-        // Reset classification fields before re-applying
-        repo.managed_by = None;
-        repo.ownership = None;
-        repo.intention = None;
-        repo.category = None;
-        // Keep user tags but allow rule tags to be re-added
-        classify::classify_repo(&mut repo, cfg);
-
-        let mut new_tags = repo.tags.clone();
-        new_tags.sort();
-        if repo.managed_by != old_managed
-            || repo.ownership != old_ownership
-            || repo.intention != old_intention
-            || repo.category != old_category
-            || new_tags != old_tags
-        {
-            index.upsert_repo(&repo)?;
+        if classify::reclassify(&mut repo, cfg) {
+            index.set_classification(&repo)?;
             changed += 1;
         }
     }
@@ -148,6 +162,126 @@ fn run_reapply(
     Ok(())
 }
 
+/// Re-run classification on one repo (without saving) and print the trace.
+fn run_explain(
+    index: &Index,
+    cfg: &config::types::KissaConfig,
+    query: &str,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut repo = super::status::resolve_repo(index, query, false)?;
+
+    classify::reset_classification(&mut repo);
+    let trace = classify::explain_classification(&mut repo, cfg);
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "repo": repo.name,
+                "path": repo.path,
+                "trace": trace,
+                "result": {
+                    "managed_by": repo.managed_by,
+                    "ownership": repo.ownership,
+                    "intention": repo.intention,
+                    "category": repo.category,
+                    "tags": repo.tags,
+                },
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        _ => {
+            println!(
+                "  {} {} {}",
+                "explain:".green().bold(),
+                repo.name.bold(),
+                repo.path.display().dimmed(),
+            );
+            for step in &trace {
+                let mark = if step.matched {
+                    "✓".green().to_string()
+                } else {
+                    "·".dimmed().to_string()
+                };
+                println!("  {} {:<10} {}", mark, step.source, step.detail.dimmed());
+                for set in &step.set {
+                    println!("      {} {}", "set".green(), set);
+                }
+                for field in &step.skipped {
+                    println!(
+                        "      {} {} (already set by an earlier match)",
+                        "skip".yellow(),
+                        field,
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_age_out(
+    index: &Index,
+    older_than_days: u64,
+    dry_run: bool,
+    format: OutputFormat,
+) -> anyhow::Result<()> {
+    let now = chrono::Utc::now();
+    let candidates: Vec<_> = index
+        .all_repos()?
+        .into_iter()
+        .filter(|r| classify::is_age_out_candidate(r, older_than_days, now))
+        .collect();
+
+    if !dry_run {
+        for repo in &candidates {
+            index.set_intention(repo.id, Some(Intention::Archived))?;
+        }
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "older_than_days": older_than_days,
+                "dry_run": dry_run,
+                "archived": candidates.iter().map(|r| &r.path).collect::<Vec<_>>(),
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Paths | OutputFormat::Porcelain => {
+            for repo in &candidates {
+                println!("{}", repo.path.display());
+            }
+        }
+        OutputFormat::PathsNull => {
+            for repo in &candidates {
+                crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+            }
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(&candidates, format, &mut std::io::stdout())?;
+        }
+        OutputFormat::Human => {
+            for repo in &candidates {
+                println!("    {} {}", repo.name, repo.path.display().dimmed());
+            }
+            let verb = if dry_run { "would archive" } else { "archived" };
+            println!(
+                "  {} {} {} repos untouched for over {} days",
+                "age-out:".green().bold(),
+                verb,
+                candidates.len(),
+                older_than_days,
+            );
+        }
+    }
+
+    Ok(())
+}
+
 fn run_suggest(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
     let repos = index.all_repos()?;
 
@@ -171,7 +305,7 @@ fn run_suggest(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
         .filter(|(_, names)| names.len() >= 3)
         .map(|(path, names)| (path, names.len()))
         .collect();
-    suggestions.sort_by(|a, b| b.1.cmp(&a.1));
+    suggestions.sort_by_key(|s| std::cmp::Reverse(s.1));
 
     match format {
         OutputFormat::Json => {
@@ -203,13 +337,8 @@ fn run_suggest(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
                     let tilde_path = tilde_path(path);
                     println!("# {} repos under {}", count, tilde_path);
                     println!("[[classify]]");
-                    println!(
-                        "match = {{ path = \"{}/*\" }}",
-                        tilde_path,
-                    );
-                    println!(
-                        "set = {{ intention = \"dependency\", ownership = \"third-party\" }}"
-                    );
+                    println!("match = {{ path = \"{}/*\" }}", tilde_path,);
+                    println!("set = {{ intention = \"dependency\", ownership = \"third-party\" }}");
                     println!("managed_by = \"TODO\"");
                     println!();
                 }
@@ -226,10 +355,10 @@ fn run_suggest(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
 
 /// Replace home dir prefix with ~ for display.
 fn tilde_path(path: &Path) -> String {
-    if let Some(home) = dirs::home_dir() {
-        if let Ok(rest) = path.strip_prefix(&home) {
-            return format!("~/{}", rest.display());
-        }
+    if let Some(home) = dirs::home_dir()
+        && let Ok(rest) = path.strip_prefix(&home)
+    {
+        return format!("~/{}", rest.display());
     }
     path.display().to_string()
 }
@@ -1,12 +1,62 @@
 use crate::cli::OutputFormat;
 use kissa::config;
 
-pub fn run(format: OutputFormat) -> anyhow::Result<()> {
-    let cfg = config::load_config()?;
+#[derive(clap::Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: Option<ConfigCommand>,
+
+    /// Resolve run-time values too: scan roots as a scan would use them and
+    /// `scan.jobs = 0` as the actual worker count
+    #[arg(long)]
+    pub effective: bool,
+
+    /// With --format json, print one line instead of pretty-printing
+    #[arg(long)]
+    pub compact: bool,
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConfigCommand {
+    /// Print a JSON Schema for config.toml
+    Schema,
+    /// Rewrite the config file (config.toml, or config.json) with every option
+    /// filled in (keeps a .bak copy)
+    Normalize,
+}
+
+pub fn run(args: ConfigArgs, format: OutputFormat) -> anyhow::Result<()> {
+    match args.command {
+        Some(ConfigCommand::Schema) => {
+            serde_json::to_writer_pretty(std::io::stdout(), &config::json_schema())?;
+            println!();
+            return Ok(());
+        }
+        Some(ConfigCommand::Normalize) => {
+            let path = config::config_path();
+            let backup = config::normalize_config_file(&path)?;
+            println!("Normalized {}", path.display());
+            if let Some(backup) = backup {
+                println!("Backup saved to {}", backup.display());
+            }
+            return Ok(());
+        }
+        None => {}
+    }
+
+    // Always the merged config: every option, defaults included
+    let mut cfg = config::load_config()?;
+    if args.effective {
+        cfg = config::resolve_effective(cfg);
+    }
 
     match format {
         OutputFormat::Json => {
-            serde_json::to_writer_pretty(std::io::stdout(), &cfg)?;
+            if args.compact {
+                serde_json::to_writer(std::io::stdout(), &cfg)?;
+            } else {
+                serde_json::to_writer_pretty(std::io::stdout(), &cfg)?;
+            }
             println!();
         }
         _ => {
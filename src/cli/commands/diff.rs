@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::config;
+use kissa::core::git_ops;
+use kissa::core::permissions::{self, OperationClass};
+use kissa::core::repo::ChangeKind;
+
+#[derive(clap::Args)]
+pub struct DiffArgs {
+    /// Repo name or path
+    pub repo: String,
+
+    /// Compare the index (staged changes) to HEAD instead of the working tree
+    #[arg(long)]
+    pub staged: bool,
+}
+
+/// Summarize uncommitted changes file by file.
+pub fn run(args: DiffArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let cfg = config::load_config()?;
+    let index = crate::cli::open_index(root)?;
+    let repo = super::status::resolve_repo(&index, &args.repo, false)?;
+    permissions::check_permission(OperationClass::Read, &repo.path, &cfg, false)?;
+
+    let changes = git_ops::diff_summary(&repo.path, args.staged)?;
+    let count = |kind| changes.iter().filter(|c| c.kind == kind).count();
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "repo": repo.name,
+                "path": repo.path,
+                "staged": args.staged,
+                "files": changes,
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Paths => {
+            for change in &changes {
+                println!("{}", repo.path.join(&change.path).display());
+            }
+        }
+        OutputFormat::PathsNull => {
+            for change in &changes {
+                crate::cli::output::write_path_null(
+                    &mut std::io::stdout(),
+                    &repo.path.join(&change.path),
+                )?;
+            }
+        }
+        OutputFormat::Porcelain => {
+            for change in &changes {
+                println!("{}\t{}", change.kind.letter(), change.path.display());
+            }
+        }
+        OutputFormat::Csv => {
+            println!("{}", crate::cli::output::csv_line(&["kind", "path"]));
+            for change in &changes {
+                let fields = [
+                    change.kind.letter().to_string(),
+                    change.path.display().to_string(),
+                ];
+                println!("{}", crate::cli::output::csv_line(&fields));
+            }
+        }
+        OutputFormat::Human => {
+            let against = if args.staged {
+                "index vs HEAD"
+            } else {
+                "working tree vs HEAD"
+            };
+            if changes.is_empty() {
+                println!(
+                    "  {} {} clean ({})",
+                    "diff:".green().bold(),
+                    repo.name.bold(),
+                    against,
+                );
+                return Ok(());
+            }
+            println!(
+                "  {} {} ({}): {} modified, {} added, {} deleted",
+                "diff:".green().bold(),
+                repo.name.bold(),
+                against,
+                count(ChangeKind::Modified),
+                count(ChangeKind::Added),
+                count(ChangeKind::Deleted),
+            );
+            for change in &changes {
+                let letter = change.kind.letter().to_string();
+                let letter = match change.kind {
+                    ChangeKind::Added => letter.green().to_string(),
+                    ChangeKind::Deleted => letter.red().to_string(),
+                    _ => letter.yellow().to_string(),
+                };
+                println!("    {} {}", letter, change.path.display());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use kissa::config::{self, types::KissaConfig};
+use kissa::core::index::IndexSummary;
+use kissa::core::repo::Repo;
+
+/// Version of the `kissa dump` document. Bump it whenever a key anywhere in
+/// the document is renamed, removed or changes type; new keys don't count.
+const DUMP_VERSION: u32 = 1;
+
+/// Everything a frontend needs at startup, in one document.
+#[derive(Serialize)]
+struct Dump {
+    version: u32,
+    generated_at: DateTime<Utc>,
+    index_path: PathBuf,
+    /// Counts and freshness tiers, as the MCP `summary` tool reports them.
+    summary: IndexSummary,
+    config: KissaConfig,
+    /// Every indexed repo, including managed, archived and lost ones.
+    repos: Vec<Repo>,
+}
+
+/// Write a versioned JSON snapshot of the index, its summary and the
+/// effective config, so frontends can load state in one call instead of
+/// one per command. Always JSON; ignores `--format`.
+pub fn run(root: Option<&Path>) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+    let dump = Dump {
+        version: DUMP_VERSION,
+        generated_at: Utc::now(),
+        index_path: config::index_path(),
+        summary: index.summary()?,
+        config: config::load_config()?,
+        repos: index.all_repos()?,
+    };
+    serde_json::to_writer_pretty(std::io::stdout(), &dump)?;
+    println!();
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use super::list::FilterArgs;
+use crate::cli::{Outcome, OutputFormat, Verbosity};
+use kissa::config;
+use kissa::core::enrich::{self, ApiTarget};
+use kissa::core::permissions::{self, OperationClass};
+use kissa::error::KissaError;
+
+#[derive(clap::Args)]
+pub struct EnrichArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Why a repo wasn't enriched.
+#[derive(Default, Serialize)]
+struct Skipped {
+    /// No remote on github.com or a GitLab host.
+    no_platform: usize,
+    /// Its host has no token in `identity.api_tokens`.
+    no_token: usize,
+    /// The difficulty config doesn't allow network access there.
+    denied: usize,
+    /// Its host's rate limit ran out earlier in this run.
+    rate_limited: usize,
+}
+
+/// Fetch description, archived flag, stars and default branch from the
+/// GitHub or GitLab API for matching repos, one request per repo. Only
+/// hosts with a token in `identity.api_tokens` are contacted, and only
+/// where the difficulty config allows `fetch`; nothing else goes online.
+pub fn run(
+    args: EnrichArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    if matches!(format, OutputFormat::Csv) {
+        anyhow::bail!("csv not supported here; use --format json");
+    }
+    let cfg = config::load_config()?;
+    if cfg.identity.api_tokens.is_empty() {
+        anyhow::bail!(
+            "no API tokens configured; add one under [identity.api_tokens], \
+             e.g. \"github.com\" = \"$GITHUB_TOKEN\""
+        );
+    }
+    let index = crate::cli::open_index(root)?;
+    let repos = index.list_repos(&args.filter.into_filter())?;
+
+    let agent = enrich::agent();
+    let mut enriched = Vec::new();
+    let mut failed: Vec<(PathBuf, String)> = Vec::new();
+    let mut skipped = Skipped::default();
+    let mut exhausted: HashSet<String> = HashSet::new();
+    for repo in &repos {
+        let Some(target) = ApiTarget::of(repo, &cfg.scan.primary_remote) else {
+            skipped.no_platform += 1;
+            continue;
+        };
+        let Some(token) = enrich::api_token(&cfg.identity, &target.host) else {
+            skipped.no_token += 1;
+            continue;
+        };
+        if permissions::check_permission(OperationClass::Fetch, &repo.path, &cfg, false).is_err() {
+            skipped.denied += 1;
+            continue;
+        }
+        if exhausted.contains(&target.host) {
+            skipped.rate_limited += 1;
+            continue;
+        }
+
+        match enrich::fetch_upstream(&agent, &target, &token) {
+            Ok(info) => {
+                index.set_upstream(repo.id, &info)?;
+                enriched.push(repo.name.clone());
+            }
+            Err(KissaError::RateLimited { host }) => {
+                if !verbosity.is_quiet() {
+                    eprintln!(
+                        "  {} {} rate limit exhausted; skipping its other repos",
+                        "warn:".yellow(),
+                        host
+                    );
+                }
+                exhausted.insert(host);
+                skipped.rate_limited += 1;
+            }
+            Err(e) => {
+                if !verbosity.is_quiet() {
+                    eprintln!("  {} {}: {}", "warn:".yellow(), repo.path.display(), e);
+                }
+                failed.push((repo.path.clone(), e.to_string()));
+            }
+        }
+    }
+
+    if verbosity.is_quiet() {
+        return Ok(if enriched.is_empty() {
+            Outcome::NoMatch
+        } else {
+            Outcome::Success
+        });
+    }
+    match format {
+        OutputFormat::Json => {
+            let failed: Vec<_> = failed
+                .iter()
+                .map(|(path, error)| serde_json::json!({ "path": path, "error": error }))
+                .collect();
+            let result = serde_json::json!({
+                "enriched": enriched,
+                "skipped": skipped,
+                "failed": failed,
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        _ => {
+            println!(
+                "  {} {} of {} repos",
+                "enriched:".green().bold(),
+                enriched.len(),
+                repos.len(),
+            );
+            let reasons = [
+                (skipped.no_platform, "no GitHub/GitLab remote"),
+                (skipped.no_token, "no token for host"),
+                (skipped.denied, "difficulty below fetch"),
+                (skipped.rate_limited, "rate limited"),
+            ];
+            for (count, reason) in reasons.iter().filter(|(count, _)| *count > 0) {
+                println!("  {} {} ({})", "skipped:".dimmed(), count, reason);
+            }
+            if !failed.is_empty() {
+                println!("  {} {}", "failed:".red().bold(), failed.len());
+            }
+        }
+    }
+    Ok(if enriched.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+use std::process::Command;
+
+use owo_colors::OwoColorize;
+
+use super::list::FilterArgs;
+use crate::cli::{Outcome, OutputFormat, Verbosity, display};
+use kissa::core::scanner;
+
+#[derive(clap::Args)]
+pub struct ForeachArgs {
+    /// Pass each repo path as the last argument (as `xargs -0 <cmd>` would)
+    /// instead of running the command inside the repo
+    #[arg(short = '0', long)]
+    pub append_path: bool,
+
+    /// Stop at the first command that exits non-zero
+    #[arg(long)]
+    pub fail_fast: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Command to run for each repo, after `--`
+    #[arg(last = true, required = true, value_name = "CMD")]
+    pub command: Vec<String>,
+}
+
+/// Run a command once per matching repo, the in-process equivalent of
+/// `kissa list --format paths-null | xargs -0 ...`. The command is executed
+/// directly, never through a shell; wrap it in `sh -c` to get one.
+pub fn run(
+    args: ForeachArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    let index = crate::cli::open_index(root)?;
+    let repos = index.list_repos(&args.filter.into_filter())?;
+    let (program, rest) = args.command.split_first().expect("clap requires a command");
+
+    let mut failed = Vec::new();
+    for repo in &repos {
+        if !scanner::repo_path_exists(&repo.path) {
+            if !verbosity.is_quiet() {
+                eprintln!(
+                    "  {} {} (path missing)",
+                    "skipped:".yellow().bold(),
+                    repo.name
+                );
+            }
+            continue;
+        }
+        if format == OutputFormat::Human && !verbosity.is_quiet() {
+            println!("{}", display::render_repo_line(repo));
+        }
+
+        let mut cmd = Command::new(program);
+        cmd.args(rest);
+        if args.append_path {
+            cmd.arg(&repo.path);
+        } else {
+            cmd.current_dir(&repo.path);
+        }
+        let status = cmd
+            .status()
+            .map_err(|e| anyhow::anyhow!("failed to run {}: {}", program, e))?;
+
+        if !status.success() {
+            if !verbosity.is_quiet() {
+                eprintln!("  {} {} ({})", "failed:".red().bold(), repo.name, status);
+            }
+            failed.push(repo.name.as_str());
+            if args.fail_fast {
+                break;
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "{} failed in {} repo(s): {}",
+            program,
+            failed.len(),
+            failed.join(", ")
+        );
+    }
+    Ok(if repos.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+
+#[derive(clap::Args)]
+pub struct ForgetArgs {
+    /// Repo name or path
+    pub repo: String,
+
+    /// Don't ask for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Also keep future scans from indexing this path again (undo with `kissa unignore`)
+    #[arg(long)]
+    pub permanent: bool,
+}
+
+/// Remove a repo from the index. Files on disk are untouched, so a later
+/// scan finds it again unless `--permanent` adds its path to the ignore list.
+pub fn run(args: ForgetArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+    // Lost repos are the usual candidates for forgetting, so names reach them too
+    let repo = super::status::resolve_repo(&index, &args.repo, true)?;
+
+    if !crate::cli::safety::confirm("forget", &repo, args.yes)? {
+        eprintln!(
+            "  {} {} kept in index",
+            "aborted:".yellow().bold(),
+            repo.name
+        );
+        return Ok(());
+    }
+    index.forget_repo(repo.id)?;
+    if args.permanent {
+        index.ignore_path(&repo.path)?;
+    }
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &repo)?;
+            println!();
+        }
+        OutputFormat::Paths | OutputFormat::Porcelain => {
+            println!("{}", repo.path.display());
+        }
+        OutputFormat::PathsNull => {
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(
+                std::slice::from_ref(&repo),
+                format,
+                &mut std::io::stdout(),
+            )?;
+        }
+        OutputFormat::Human => {
+            println!(
+                "  {} {} removed from index ({})",
+                "forgotten:".green().bold(),
+                repo.name,
+                repo.path.display().dimmed(),
+            );
+            if args.permanent {
+                println!("  {} scans will skip this path", "ignored:".dimmed());
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,18 +1,33 @@
+use std::path::Path;
+
 use crate::cli::OutputFormat;
-use kissa::config;
-use kissa::core::index::Index;
+use crate::cli::output::csv_line;
 
-pub fn run(format: OutputFormat) -> anyhow::Result<()> {
-    let index = Index::open(&config::index_path())?;
+pub fn run(root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
 
     let summary = index.freshness_summary()?;
-    let total = summary.active + summary.recent + summary.stale + summary.dormant + summary.ancient;
+    let total = summary.total();
 
     match format {
         OutputFormat::Json => {
             serde_json::to_writer_pretty(std::io::stdout(), &summary)?;
             println!();
         }
+        OutputFormat::Csv => {
+            println!("{}", csv_line(&["tier", "repos"]));
+            let tiers = [
+                ("active", summary.active),
+                ("recent", summary.recent),
+                ("stale", summary.stale),
+                ("dormant", summary.dormant),
+                ("ancient", summary.ancient),
+                ("empty", summary.empty),
+            ];
+            for (tier, count) in tiers {
+                println!("{}", csv_line(&[tier, &count.to_string()]));
+            }
+        }
         _ => {
             println!("{}", crate::cli::display::render_freshness(&summary, total));
         }
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use crate::cli::OutputFormat;
+use kissa::config;
+use kissa::core::filter::RepoFilter;
+use kissa::core::graph;
+
+#[derive(clap::Args)]
+pub struct GraphArgs {
+    /// Include tool-managed and archived repos
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// Emit the clone/fork relationship graph as Graphviz DOT (pipe into `dot -Tsvg`).
+pub fn run(args: GraphArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+
+    let filter = if args.all {
+        RepoFilter::new()
+    } else {
+        RepoFilter::new().show_managed(false).show_archived(false)
+    };
+    let repos = index.list_repos(&filter)?;
+    let primary = config::load_config()?.scan.primary_remote;
+
+    match format {
+        OutputFormat::Json => {
+            let edges: Vec<_> = graph::infer_edges(&repos, &primary)
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "from": repos[e.from].path,
+                        "to": repos[e.to].path,
+                        "relation": e.relation.label(),
+                    })
+                })
+                .collect();
+            serde_json::to_writer_pretty(std::io::stdout(), &edges)?;
+            println!();
+        }
+        _ => print!("{}", graph::to_dot(&repos, &primary)),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use regex::RegexBuilder;
+
+use super::list::FilterArgs;
+use crate::cli::{Outcome, OutputFormat, Verbosity, output};
+use kissa::config;
+use kissa::core::permissions::{self, OperationClass};
+use kissa::core::repo::{LogMatch, Repo};
+use kissa::core::{git_ops, scanner};
+
+#[derive(clap::Args)]
+pub struct GrepLogArgs {
+    /// Regex matched against each commit's summary line
+    pub pattern: String,
+
+    /// Match case-insensitively
+    #[arg(short = 'i', long)]
+    pub ignore_case: bool,
+
+    /// Look at only this many of the newest commits per repo
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    pub commits: usize,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Find which repos have a recent commit whose summary matches a pattern,
+/// walking each matching repo's history on `scan.jobs` threads. Repos the
+/// difficulty config doesn't allow reading, and missing paths, are skipped.
+pub fn run(
+    args: GrepLogArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    let pattern = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|e| anyhow::anyhow!("invalid pattern: {}", e))?;
+    let cfg = config::load_config()?;
+    let index = crate::cli::open_index(root)?;
+    let repos: Vec<_> = index
+        .list_repos(&args.filter.into_filter())?
+        .into_iter()
+        .filter(|repo| scanner::repo_path_exists(&repo.path))
+        .filter(|repo| {
+            permissions::check_permission(OperationClass::Read, &repo.path, &cfg, false).is_ok()
+        })
+        .collect();
+
+    let results = scanner::map_parallel(&repos, cfg.scan.jobs, |repo| {
+        git_ops::grep_log(&repo.path, &pattern, args.commits)
+    });
+    let mut hits: Vec<(&Repo, Vec<LogMatch>)> = Vec::new();
+    for (repo, result) in repos.iter().zip(results) {
+        match result {
+            Ok(matches) if !matches.is_empty() => hits.push((repo, matches)),
+            Ok(_) => {}
+            Err(e) if !verbosity.is_quiet() => {
+                eprintln!("  {} {}: {}", "warn:".yellow(), repo.path.display(), e);
+            }
+            Err(_) => {}
+        }
+    }
+
+    if !verbosity.is_quiet() {
+        print_hits(&hits, format)?;
+    }
+    Ok(if hits.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
+
+fn print_hits(hits: &[(&Repo, Vec<LogMatch>)], format: OutputFormat) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let rows: Vec<_> = hits
+                .iter()
+                .map(|(repo, matches)| {
+                    serde_json::json!({ "repo": repo.name, "path": repo.path, "commits": matches })
+                })
+                .collect();
+            serde_json::to_writer_pretty(std::io::stdout(), &rows)?;
+            println!();
+        }
+        OutputFormat::Human => {
+            for (i, (repo, matches)) in hits.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!("  {} {}", repo.name.bold(), repo.path.display().dimmed());
+                for m in matches {
+                    println!(
+                        "    {} {} {}",
+                        (&m.sha[..8]).yellow(),
+                        m.summary,
+                        m.time.format("%Y-%m-%d").dimmed(),
+                    );
+                }
+            }
+        }
+        OutputFormat::Porcelain => {
+            for (repo, matches) in hits {
+                for m in matches {
+                    let fields = [
+                        &repo.name,
+                        &repo.path.display().to_string(),
+                        &m.sha,
+                        &m.summary,
+                    ];
+                    let line = fields.map(|f| output::porcelain_field(f));
+                    println!("{}", line.join("\t"));
+                }
+            }
+        }
+        OutputFormat::Csv => {
+            println!("{}", output::csv_line(&["repo", "path", "sha", "summary"]));
+            for (repo, matches) in hits {
+                for m in matches {
+                    let path = repo.path.display().to_string();
+                    println!(
+                        "{}",
+                        output::csv_line(&[&repo.name, &path, &m.sha, &m.summary])
+                    );
+                }
+            }
+        }
+        OutputFormat::Paths | OutputFormat::PathsNull => {
+            let repos: Vec<_> = hits.iter().map(|(repo, _)| (*repo).clone()).collect();
+            output::output_repos(&repos, format, &mut std::io::stdout())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use crate::cli::{Outcome, OutputFormat};
+
+/// Print cached status for the indexed repo containing the current
+/// directory. Built for shell prompts: one indexed lookup per ancestor, no
+/// git access, and exit 1 with no output outside any indexed repo.
+pub fn run(root: Option<&Path>, format: OutputFormat) -> anyhow::Result<Outcome> {
+    let cwd = std::env::current_dir()?;
+    let index = crate::cli::open_index(root)?;
+
+    for dir in cwd.ancestors() {
+        // Past the scoping root, every further ancestor is outside it too
+        if index.root().is_some_and(|root| !dir.starts_with(root)) {
+            break;
+        }
+        let Some(repo) = index.get_repo_by_path(dir)? else {
+            continue;
+        };
+        match format {
+            OutputFormat::Json => {
+                serde_json::to_writer(std::io::stdout(), &repo)?;
+                println!();
+            }
+            OutputFormat::Paths => println!("{}", repo.path.display()),
+            OutputFormat::PathsNull => {
+                crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?
+            }
+            OutputFormat::Porcelain => println!("{}", crate::cli::output::porcelain_line(&repo)),
+            OutputFormat::Csv => crate::cli::output::output_repos(
+                std::slice::from_ref(&repo),
+                format,
+                &mut std::io::stdout(),
+            )?,
+            OutputFormat::Human => println!("{}", crate::cli::display::render_prompt(&repo)),
+        }
+        return Ok(Outcome::Success);
+    }
+    Ok(Outcome::NoMatch)
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use crate::cli::output::csv_line;
+
+#[derive(clap::Args)]
+pub struct HistoryArgs {
+    /// Repo name or path
+    pub repo: String,
+}
+
+/// Show every URL each of a repo's remotes has had since it was indexed,
+/// to audit org renames and host migrations. Read from the index only.
+pub fn run(args: HistoryArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+    let repo = super::status::resolve_repo(&index, &args.repo, true)?;
+    let records = index.remote_url_history(repo.id)?;
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "repo": repo.name,
+                "path": repo.path,
+                "remotes": records,
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Human => {
+            println!("  {} {}", "history:".green().bold(), repo.name.bold());
+            if records.is_empty() {
+                println!("    {}", "no remotes recorded".dimmed());
+            }
+            let mut last_remote = None;
+            for record in &records {
+                if last_remote != Some(&record.remote) {
+                    println!("    {}", record.remote);
+                    last_remote = Some(&record.remote);
+                }
+                println!(
+                    "      {} {}",
+                    record.seen_at.format("%Y-%m-%d").dimmed(),
+                    record.url,
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            println!("{}", csv_line(&["remote", "url", "seen_at"]));
+            for record in &records {
+                let seen_at = record.seen_at.to_rfc3339();
+                println!("{}", csv_line(&[&record.remote, &record.url, &seen_at]));
+            }
+        }
+        OutputFormat::Paths | OutputFormat::PathsNull | OutputFormat::Porcelain => {
+            for record in &records {
+                let seen_at = record.seen_at.to_rfc3339();
+                let fields = [&record.remote, &record.url, &seen_at];
+                let line = fields.map(|f| crate::cli::output::porcelain_field(f));
+                println!("{}", line.join("\t"));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,51 +1,37 @@
 use std::path::Path;
 
 use crate::cli::OutputFormat;
-use kissa::config;
+use kissa::config::{self, types::ScanConfig};
 use kissa::core::git_ops;
 use kissa::core::index::Index;
-use kissa::core::repo::Freshness;
+use kissa::core::repo::{Repo, RepoState};
+use kissa::core::scanner::{self, PathProbe};
 
 #[derive(clap::Args)]
 pub struct InfoArgs {
     /// Repo name or path
     pub repo: String,
 
-    /// Refresh git vitals before displaying
-    #[arg(long)]
+    /// Refresh git vitals before displaying, regardless of age
+    #[arg(long, conflicts_with = "no_refresh")]
     pub refresh: bool,
-}
 
-pub fn run(args: InfoArgs, format: OutputFormat) -> anyhow::Result<()> {
-    let index = Index::open(&config::index_path())?;
+    /// Never refresh, even if vitals are older than scan.auto_verify_seconds
+    #[arg(long)]
+    pub no_refresh: bool,
 
-    let repo = if Path::new(&args.repo).is_absolute() {
-        index.get_repo_by_path(Path::new(&args.repo))?
-    } else {
-        index.get_repo_by_name(&args.repo)?
-    };
+    /// Let the name match a lost repo, to see its last-known state
+    #[arg(long)]
+    pub include_lost: bool,
+}
 
-    let Some(mut repo) = repo else {
-        anyhow::bail!("repo not found: {}", args.repo);
-    };
+pub fn run(args: InfoArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
 
-    // Optionally refresh vitals from disk
-    if args.refresh {
-        if let Ok(vitals) = git_ops::extract_vitals(&repo.path) {
-            repo.dirty = vitals.dirty;
-            repo.staged = vitals.staged;
-            repo.untracked = vitals.untracked;
-            repo.ahead = vitals.ahead;
-            repo.behind = vitals.behind;
-            repo.last_commit = vitals.last_commit;
-            repo.current_branch = vitals.current_branch;
-            repo.branch_count = vitals.branch_count;
-            repo.stale_branch_count = vitals.stale_branch_count;
-            repo.freshness = Freshness::from_commit_time(vitals.last_commit);
-            repo.last_verified = Some(chrono::Utc::now());
-            index.upsert_repo(&repo)?;
-        }
-    }
+    let mut repo = super::status::resolve_repo(&index, &args.repo, args.include_lost)?;
+
+    let cfg = config::load_config()?;
+    refresh_if_stale(&index, &mut repo, &cfg.scan, args.refresh, args.no_refresh)?;
 
     match format {
         OutputFormat::Json => {
@@ -59,3 +45,47 @@ pub fn run(args: InfoArgs, format: OutputFormat) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Re-extract vitals from disk when forced, when the cached copy is older
+/// than `scan.auto_verify_seconds`, or when its working-tree status is stale.
+/// A refresh always reads the working tree, whatever `scan.compute_status`
+/// says. The refreshed repo is written back to the index.
+pub(crate) fn refresh_if_stale(
+    index: &Index,
+    repo: &mut Repo,
+    scan: &ScanConfig,
+    force: bool,
+    disabled: bool,
+) -> anyhow::Result<()> {
+    if disabled {
+        return Ok(());
+    }
+    if !force
+        && !repo.status_is_stale()
+        && !repo.verify_is_stale(scan.auto_verify_seconds, chrono::Utc::now())
+    {
+        return Ok(());
+    }
+    let scan = ScanConfig {
+        compute_status: true,
+        ..scan.clone()
+    };
+    // Don't hang on a dead mount: a path that doesn't answer keeps its cached vitals
+    let timeout = std::time::Duration::from_millis(scan.boundaries.stat_timeout_ms);
+    if scanner::probe_repo_path(&repo.path, timeout) == PathProbe::TimedOut {
+        if repo.state != RepoState::Timeout {
+            repo.state = RepoState::Timeout;
+            index.mark_timeout(repo.id)?;
+        }
+        return Ok(());
+    }
+    // A repo that can't be opened keeps its cached vitals; `list --verify` handles lost paths
+    if let Ok(vitals) = git_ops::extract_vitals(&repo.path, &scan) {
+        repo.apply_vitals(vitals);
+        if repo.state == RepoState::Timeout {
+            repo.state = RepoState::Active;
+        }
+        index.upsert_vitals(repo)?;
+    }
+    Ok(())
+}
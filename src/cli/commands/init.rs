@@ -0,0 +1,40 @@
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::config;
+use kissa::core::index::Index;
+
+/// Create the index at the selected location, migrating it if it already
+/// exists. With `--local` this is `./.kissa/index.db`, the start of a
+/// per-project catalogue that later `--local` commands find by walking up.
+pub fn run(format: OutputFormat) -> anyhow::Result<()> {
+    let path = config::index_path();
+    let created = !path.exists();
+    Index::open(&path)?;
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({ "path": path, "created": created });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Paths | OutputFormat::Porcelain | OutputFormat::Csv => {
+            println!("{}", path.display())
+        }
+        OutputFormat::PathsNull => {
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &path)?;
+        }
+        OutputFormat::Human => {
+            let label = if created { "initialized:" } else { "exists:" };
+            println!("  {} {}", label.green().bold(), path.display());
+            if created && let Some(root) = config::project_root() {
+                println!(
+                    "  {} kissa --local scan",
+                    format!("next, from anywhere under {}:", root.display()).dimmed(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,11 +1,19 @@
-use crate::cli::OutputFormat;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Utc};
+
+use crate::cli::output::GroupBy;
+use crate::cli::{Outcome, OutputFormat, Verbosity};
 use kissa::config;
 use kissa::core::filter::RepoFilter;
-use kissa::core::index::Index;
+use kissa::core::index::{Index, MultiIndex};
 use kissa::core::repo::{Freshness, RepoState};
+use kissa::core::scanner::{self, PathProbe};
+use kissa::core::sort::{self, SortKey};
 
-#[derive(clap::Args)]
-pub struct ListArgs {
+/// Filter flags shared by `list` and batch `status`.
+#[derive(clap::Args, Default, PartialEq)]
+pub struct FilterArgs {
     /// Show only dirty repos
     #[arg(long)]
     pub dirty: bool,
@@ -22,10 +30,66 @@ pub struct ListArgs {
     #[arg(long)]
     pub orphan: bool,
 
+    /// Show only repos with a remote of this name (e.g. upstream)
+    #[arg(long, value_name = "REMOTE")]
+    pub has_remote_named: Option<String>,
+
+    /// Show only repos without a remote of this name
+    #[arg(long, value_name = "REMOTE")]
+    pub missing_remote: Option<String>,
+
+    /// Show only repos whose remote default branch has no local branch
+    #[arg(long)]
+    pub branch_drift: bool,
+
+    /// Show only repos with stale branches (no commits for 90+ days)
+    #[arg(long)]
+    pub has_stale_branches: bool,
+
+    /// Show only repos whose HEAD commit is signed
+    #[arg(long, conflicts_with = "unsigned")]
+    pub signed: bool,
+
+    /// Show only repos whose HEAD commit is unsigned
+    #[arg(long)]
+    pub unsigned: bool,
+
+    /// Show only repos checked out on a branch other than their default
+    #[arg(long)]
+    pub off_default: bool,
+
+    /// Show only repos with a detached HEAD (no branch checked out; includes bare repos)
+    #[arg(long)]
+    pub detached: bool,
+
+    /// Show only empty repos (no commits yet)
+    #[arg(long)]
+    pub empty: bool,
+
+    /// Show only repos inside another repo's working tree (not submodules)
+    #[arg(long)]
+    pub nested: bool,
+
+    /// Show only repos whose dirty/ahead/last commit moved since the last full scan
+    #[arg(long)]
+    pub changed: bool,
+
+    /// Show only repos not fetched within AGE, e.g. 7d or 3mo (including never fetched)
+    #[arg(long, value_name = "AGE", value_parser = crate::cli::parse_age)]
+    pub not_fetched_since: Option<Duration>,
+
     /// Show only lost repos (path missing)
     #[arg(long)]
     pub lost: bool,
 
+    /// Show only repos whose path timed out when last checked (slow or unavailable mounts)
+    #[arg(long, conflicts_with = "lost")]
+    pub timeout: bool,
+
+    /// Include lost repos, which are hidden by default
+    #[arg(long)]
+    pub include_lost: bool,
+
     /// Filter by remote org/owner
     #[arg(long)]
     pub org: Option<String>,
@@ -38,7 +102,7 @@ pub struct ListArgs {
     #[arg(long, value_name = "PATH")]
     pub path_prefix: Option<String>,
 
-    /// Filter by ownership (personal, work, work:label, community, third-party, local)
+    /// Filter by ownership (personal, work, work:label, work:glob*, community, third-party, local)
     #[arg(long)]
     pub ownership: Option<String>,
 
@@ -50,18 +114,26 @@ pub struct ListArgs {
     #[arg(long)]
     pub category: Option<String>,
 
-    /// Filter by tags (comma-separated, all must match)
+    /// Filter by tags (comma-separated, ALL must match)
     #[arg(long, value_delimiter = ',')]
     pub tags: Option<Vec<String>>,
 
+    /// Filter by tags (comma-separated, ANY one may match); combines with --tags
+    #[arg(long, value_delimiter = ',')]
+    pub tags_any: Option<Vec<String>>,
+
     /// Filter by name (substring match)
     #[arg(long)]
     pub name: Option<String>,
 
-    /// Show all repos including tool-managed ones
+    /// Show all repos including tool-managed and archived ones
     #[arg(long)]
     pub all: bool,
 
+    /// Show only archived repos
+    #[arg(long)]
+    pub archived: bool,
+
     /// Show only tool-managed repos
     #[arg(long)]
     pub managed: bool,
@@ -71,55 +143,288 @@ pub struct ListArgs {
     pub managed_by: Option<String>,
 }
 
-pub fn run(args: ListArgs, format: OutputFormat) -> anyhow::Result<()> {
-    let index = Index::open(&config::index_path())?;
+#[derive(clap::Args)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Print only the number of matching repos
+    #[arg(long)]
+    pub count: bool,
+
+    /// Sort by field (name, path, last-commit, freshness, ahead, activity)
+    #[arg(long, value_name = "FIELD")]
+    pub sort: Option<String>,
+
+    /// Reverse the sort order
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// Show at most N repos
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Show the N most recently committed repos (--sort last-commit --reverse --limit N)
+    #[arg(long, value_name = "N", conflicts_with_all = ["oldest", "sort", "limit"])]
+    pub newest: Option<usize>,
+
+    /// Show the N least recently committed repos (--sort last-commit --limit N)
+    #[arg(long, value_name = "N", conflicts_with_all = ["sort", "limit"])]
+    pub oldest: Option<usize>,
+
+    /// Check each listed path still exists and mark missing ones LOST in the
+    /// output, or TIMEOUT when the check exceeds scan.boundaries.stat_timeout_ms
+    #[arg(long)]
+    pub verify: bool,
+
+    /// With --verify, also record missing and timed-out repos in the index
+    #[arg(long, requires = "verify")]
+    pub update: bool,
+
+    /// Also read repos from another index database (read-only, repeatable)
+    #[arg(long, value_name = "PATH")]
+    pub also_index: Vec<PathBuf>,
+
+    /// Output only these fields (comma-separated) with --format json, porcelain or csv
+    #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+    pub only: Option<Vec<String>>,
+
+    /// Group repos under headers with counts (JSON: an object of group -> repos)
+    #[arg(long, value_enum, value_name = "KEY", conflicts_with_all = ["only", "count"])]
+    pub group_by: Option<GroupBy>,
+
+    /// With --format json or porcelain, emit one row per remote with its repo's
+    /// name, path and freshness, plus the remote's host and org
+    #[arg(long, conflicts_with_all = ["only", "count", "group_by"])]
+    pub flatten_remotes: bool,
+}
+
+impl FilterArgs {
+    /// Build the index filter these flags describe. Managed and archived
+    /// repos are hidden unless asked for or `--all` is set; lost repos only
+    /// show with `--lost` or `--include-lost`.
+    pub(crate) fn into_filter(self) -> RepoFilter {
+        let freshness = self
+            .freshness
+            .as_deref()
+            .and_then(|s| serde_plain::from_str::<Freshness>(s).ok());
 
-    let freshness = args.freshness.as_deref().and_then(|s| {
-        serde_plain::from_str::<Freshness>(s).ok()
-    });
+        let state = if self.lost {
+            Some(RepoState::Lost)
+        } else if self.timeout {
+            Some(RepoState::Timeout)
+        } else {
+            None
+        };
+        let show_lost = (!self.lost && !self.include_lost).then_some(false);
 
-    let state = if args.lost {
-        Some(RepoState::Lost)
-    } else {
-        None
-    };
+        // Determine managed visibility:
+        // --managed-by X  → show only repos managed by X
+        // --managed       → show only managed repos
+        // --all           → show everything (no managed filter)
+        // (default)       → hide managed repos
+        let (show_managed, managed_by) = if self.managed_by.is_some() {
+            (None, self.managed_by)
+        } else if self.managed {
+            (Some(true), None)
+        } else if self.all {
+            (None, None)
+        } else {
+            (Some(false), None)
+        };
+
+        // Archived repos are hidden unless asked for explicitly or via --all
+        let show_archived = if self.archived {
+            Some(true)
+        } else if self.all {
+            None
+        } else {
+            Some(false)
+        };
+
+        let signed = match (self.signed, self.unsigned) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+
+        RepoFilter::new()
+            .maybe(self.dirty.then_some(true), RepoFilter::dirty)
+            .maybe(self.unpushed.then_some(true), RepoFilter::unpushed)
+            .maybe(self.orphan.then_some(true), RepoFilter::orphan)
+            .maybe(self.has_remote_named, RepoFilter::has_remote_named)
+            .maybe(self.missing_remote, RepoFilter::missing_remote)
+            .maybe(self.branch_drift.then_some(true), RepoFilter::branch_drift)
+            .maybe(
+                self.has_stale_branches.then_some(true),
+                RepoFilter::has_stale_branches,
+            )
+            .maybe(signed, RepoFilter::head_signed)
+            .maybe(
+                self.off_default.then_some(true),
+                RepoFilter::on_non_default_branch,
+            )
+            .maybe(self.detached.then_some(true), RepoFilter::detached)
+            .maybe(self.empty.then_some(true), RepoFilter::empty)
+            .maybe(self.nested.then_some(true), RepoFilter::nested)
+            .maybe(self.changed.then_some(true), RepoFilter::changed_since_scan)
+            .maybe(
+                self.not_fetched_since
+                    .and_then(|age| Utc::now().checked_sub_signed(age)),
+                RepoFilter::not_fetched_since,
+            )
+            .maybe(self.org, RepoFilter::org)
+            .maybe(freshness, RepoFilter::freshness)
+            .maybe(self.ownership, RepoFilter::ownership)
+            .maybe(self.intention, RepoFilter::intention)
+            .maybe(self.category, RepoFilter::category)
+            .maybe(self.tags, RepoFilter::tags)
+            .maybe(self.tags_any, RepoFilter::tags_any)
+            .maybe(self.path_prefix, RepoFilter::path_prefix)
+            .maybe(self.name, RepoFilter::name_contains)
+            .maybe(state, RepoFilter::state)
+            .maybe(managed_by, RepoFilter::managed_by)
+            .maybe(show_managed, RepoFilter::show_managed)
+            .maybe(show_archived, RepoFilter::show_archived)
+            .maybe(show_lost, RepoFilter::show_lost)
+    }
+}
+
+/// Under `-q` nothing is printed; the exit code alone says whether anything
+/// matched (0 = matches, 1 = none, 2 = error).
+pub fn run(
+    args: ListArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    // Validate before touching the index so typos fail fast
+    let only = args
+        .only
+        .as_deref()
+        .map(crate::cli::output::parse_fields)
+        .transpose()?;
+    if only.is_some()
+        && !matches!(
+            format,
+            OutputFormat::Json | OutputFormat::Porcelain | OutputFormat::Csv
+        )
+    {
+        anyhow::bail!("--only works with --format json, porcelain or csv");
+    }
+
+    let index = crate::cli::open_index(root)?;
+    let extras = args
+        .also_index
+        .iter()
+        .map(|p| {
+            Index::open_readonly(p)
+                .map(|extra| extra.with_root(index.root().map(Path::to_path_buf)))
+        })
+        .collect::<kissa::error::Result<Vec<_>>>()?;
+    let index = MultiIndex::new(index, extras);
+
+    let filter = args.filter.into_filter();
 
-    // Determine managed visibility:
-    // --managed-by X  → show only repos managed by X
-    // --managed       → show only managed repos
-    // --all           → show everything (no managed filter)
-    // (default)       → hide managed repos
-    let (show_managed, managed_by) = if args.managed_by.is_some() {
-        (None, args.managed_by)
-    } else if args.managed {
-        (Some(true), None)
-    } else if args.all {
-        (None, None)
+    if args.count {
+        let count = index.count_repos(&filter)?;
+        let outcome = if count == 0 {
+            Outcome::NoMatch
+        } else {
+            Outcome::Success
+        };
+        if verbosity.is_quiet() {
+            return Ok(outcome);
+        }
+        match format {
+            OutputFormat::Json => {
+                let result = serde_json::json!({ "count": count });
+                serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+                println!();
+            }
+            _ => println!("{}", count),
+        }
+        return Ok(outcome);
+    }
+
+    let mut repos = index.list_repos(&filter)?;
+
+    // --newest/--oldest are shortcuts over the generic sort + limit
+    let (sort, reverse, limit) = if let Some(n) = args.newest {
+        (Some(SortKey::LastCommit), true, Some(n))
+    } else if let Some(n) = args.oldest {
+        (Some(SortKey::LastCommit), false, Some(n))
     } else {
-        (Some(false), None)
+        let sort = match args.sort.as_deref() {
+            Some(s) => Some(
+                serde_plain::from_str::<SortKey>(s)
+                    .map_err(|_| anyhow::anyhow!("unknown sort field: {}", s))?,
+            ),
+            None => None,
+        };
+        (sort, args.reverse, args.limit)
     };
 
-    let filter = RepoFilter {
-        dirty: if args.dirty { Some(true) } else { None },
-        unpushed: if args.unpushed { Some(true) } else { None },
-        orphan: if args.orphan { Some(true) } else { None },
-        org: args.org,
-        freshness,
-        ownership: args.ownership,
-        intention: args.intention,
-        category: args.category,
-        tags: args.tags,
-        path_prefix: args.path_prefix,
-        has_remote: None,
-        name_contains: args.name,
-        state,
-        managed_by,
-        show_managed,
-    };
+    if let Some(key) = sort {
+        sort::sort_repos(&mut repos, key, reverse);
+    } else if reverse {
+        repos.reverse();
+    }
+    if let Some(n) = limit {
+        repos.truncate(n);
+    }
 
-    let repos = index.list_repos(&filter)?;
+    if args.verify {
+        let cfg = config::load_config()?;
+        let timeout = std::time::Duration::from_millis(cfg.scan.boundaries.stat_timeout_ms);
+        let probes = scanner::map_parallel(&repos, cfg.scan.jobs, |repo| {
+            (repo.state != RepoState::Lost).then(|| scanner::probe_repo_path(&repo.path, timeout))
+        });
+        for (repo, probe) in repos.iter_mut().zip(probes) {
+            let Some(probe) = probe else { continue };
+            let state = match probe {
+                PathProbe::Present if repo.state == RepoState::Timeout => RepoState::Active,
+                PathProbe::Present => continue,
+                PathProbe::Missing => RepoState::Lost,
+                PathProbe::TimedOut => RepoState::Timeout,
+            };
+            repo.state = state;
+            // Only the primary index is writable; extras stay untouched
+            if args.update
+                && let Some(indexed) = index.primary().get_repo_by_path(&repo.path)?
+            {
+                match state {
+                    RepoState::Lost => index.primary().mark_lost(indexed.id)?,
+                    RepoState::Timeout => index.primary().mark_timeout(indexed.id)?,
+                    RepoState::Active => index.primary().mark_active(indexed.id)?,
+                }
+            }
+        }
+    }
 
-    crate::cli::output::output_repos(&repos, format, &mut std::io::stdout())?;
+    if !verbosity.is_quiet()
+        && let Some(by) = args.group_by
+    {
+        let primary_remote = config::load_config()?.scan.primary_remote;
+        let groups = crate::cli::output::group_repos(&repos, by, &primary_remote);
+        crate::cli::output::output_grouped(&groups, format, &mut std::io::stdout())?;
+    } else if !verbosity.is_quiet() && args.flatten_remotes {
+        crate::cli::output::output_flattened(&repos, format, &mut std::io::stdout())?;
+    } else if !verbosity.is_quiet() {
+        match only {
+            Some(ref fields) => crate::cli::output::output_projected(
+                &repos,
+                fields,
+                format,
+                &mut std::io::stdout(),
+            )?,
+            None => crate::cli::output::output_repos(&repos, format, &mut std::io::stdout())?,
+        }
+    }
 
-    Ok(())
+    Ok(if repos.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
 }
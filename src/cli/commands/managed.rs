@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::{Outcome, OutputFormat, display, output};
+use kissa::core::index::FreshnessSummary;
+
+/// List tool-managed repos grouped by the tool that manages them, with each
+/// group's freshness breakdown. The detailed counterpart to the counts in
+/// `kissa classify`.
+pub fn run(root: Option<&Path>, format: OutputFormat) -> anyhow::Result<Outcome> {
+    let index = crate::cli::open_index(root)?;
+    let repos = index.all_repos()?;
+    let groups = super::classify::group_by_manager(&repos);
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &groups)?;
+            println!();
+        }
+        OutputFormat::Human => {
+            if groups.is_empty() {
+                println!("  No managed repos in index.");
+            }
+            for (i, (tool, members)) in groups.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                println!(
+                    "  {} {} {}",
+                    tool.bold(),
+                    format!("({} repos)", members.len()).dimmed(),
+                    display::render_freshness_inline(&FreshnessSummary::tally(
+                        members.iter().copied()
+                    )),
+                );
+                for repo in members {
+                    println!("  {}", display::render_repo_line(repo));
+                }
+            }
+        }
+        _ => {
+            let members: Vec<_> = groups.values().flatten().map(|r| (*r).clone()).collect();
+            output::output_repos(&members, format, &mut std::io::stdout().lock())?;
+        }
+    }
+
+    Ok(if groups.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
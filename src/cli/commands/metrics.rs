@@ -0,0 +1,18 @@
+use std::io::Write;
+use std::path::Path;
+
+use kissa::core::repo::RepoMetrics;
+
+/// Emit one NDJSON health record per indexed repo (see `RepoMetrics`),
+/// for scraping into a time-series database. Ignores `--format`.
+pub fn run(root: Option<&Path>) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+    let now = chrono::Utc::now();
+
+    let mut out = std::io::stdout().lock();
+    for repo in index.all_repos()? {
+        serde_json::to_writer(&mut out, &RepoMetrics::from_repo(&repo, now))?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
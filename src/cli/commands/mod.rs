@@ -1,7 +1,29 @@
+pub mod archive;
+pub mod authors;
 pub mod classify;
 pub mod config;
+pub mod diff;
+pub mod dump;
+pub mod enrich;
+pub mod foreach;
+pub mod forget;
 pub mod freshness;
+pub mod graph;
+pub mod grep_log;
+pub mod here;
+pub mod history;
 pub mod info;
+pub mod init;
 pub mod list;
+pub mod managed;
+pub mod metrics;
+pub mod note;
+pub mod prune;
+pub mod refresh_freshness;
 pub mod scan;
+pub mod search;
 pub mod status;
+pub mod tag;
+pub mod tui;
+pub mod unignore;
+pub mod values;
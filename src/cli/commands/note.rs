@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+
+#[derive(clap::Args)]
+pub struct NoteArgs {
+    /// Repo name or path
+    pub repo: String,
+
+    /// Note text (omit to clear the note)
+    pub text: Option<String>,
+}
+
+/// Set or clear the free-form note on a repo.
+pub fn run(args: NoteArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+
+    let mut repo = super::status::resolve_repo(&index, &args.repo, false)?;
+
+    let text = args.text.filter(|t| !t.trim().is_empty());
+    index.set_note(repo.id, text.as_deref())?;
+    repo.note = text;
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &repo)?;
+            println!();
+        }
+        OutputFormat::Paths => {
+            println!("{}", repo.path.display());
+        }
+        OutputFormat::PathsNull => {
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+        }
+        OutputFormat::Porcelain => {
+            println!("{}", crate::cli::output::porcelain_line(&repo));
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(
+                std::slice::from_ref(&repo),
+                format,
+                &mut std::io::stdout(),
+            )?;
+        }
+        OutputFormat::Human => match &repo.note {
+            Some(note) => println!("  {} {}: {}", "note:".green().bold(), repo.name, note),
+            None => println!("  {} {} note cleared", "note:".green().bold(), repo.name),
+        },
+    }
+
+    Ok(())
+}
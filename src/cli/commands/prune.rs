@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::core::filter::RepoFilter;
+
+#[derive(clap::Args)]
+pub struct PruneArgs {
+    /// Remove tool-managed repos (plugin managers, cargo checkouts, ...)
+    #[arg(long, required = true)]
+    pub managed: bool,
+
+    /// Show what would be removed without changing the index
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Don't ask for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Drop repos from the index by classification.
+pub fn run(args: PruneArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+
+    let managed = index.list_repos(&RepoFilter::new().show_managed(true))?;
+    if !args.dry_run && !crate::cli::safety::confirm_many("forget", &managed, args.yes)? {
+        eprintln!(
+            "  {} {} managed repos kept in index",
+            "aborted:".yellow().bold(),
+            managed.len()
+        );
+        return Ok(());
+    }
+    let removed = if args.dry_run {
+        0
+    } else {
+        index.forget_managed()?
+    };
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({
+                "matched": managed.len(),
+                "removed": removed,
+                "dry_run": args.dry_run,
+            });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Paths | OutputFormat::Porcelain => {
+            for repo in &managed {
+                println!("{}", repo.path.display());
+            }
+        }
+        OutputFormat::PathsNull => {
+            for repo in &managed {
+                crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+            }
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(&managed, format, &mut std::io::stdout())?;
+        }
+        OutputFormat::Human => {
+            if args.dry_run {
+                for repo in &managed {
+                    println!(
+                        "  {} {} ({})",
+                        repo.name,
+                        repo.path.display().dimmed(),
+                        repo.managed_by.as_deref().unwrap_or("?"),
+                    );
+                }
+                println!(
+                    "  {} {} managed repos would be removed",
+                    "dry run:".yellow().bold(),
+                    managed.len(),
+                );
+            } else {
+                println!(
+                    "  {} {} managed repos removed from index",
+                    "pruned:".green().bold(),
+                    removed,
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
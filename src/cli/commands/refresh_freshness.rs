@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+
+/// Re-tier every repo from its stored last commit time. Pure date math, no
+/// git access, so it is cheap enough to run from cron between full scans.
+pub fn run(root: Option<&Path>, format: OutputFormat) -> anyhow::Result<()> {
+    let index = crate::cli::open_index(root)?;
+    let changed = index.recompute_freshness()?;
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({ "changed": changed });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        _ => {
+            println!(
+                "  {} {} repos changed freshness tier",
+                "refreshed:".green().bold(),
+                changed,
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,23 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use indicatif::{ProgressBar, ProgressStyle};
 use owo_colors::OwoColorize;
+use serde::Serialize;
 
-use crate::cli::OutputFormat;
-use kissa::config;
-use kissa::core::classify;
-use kissa::core::git_ops;
+use crate::cli::output::porcelain_field;
+use crate::cli::{OutputFormat, Verbosity};
+use kissa::config::{
+    self,
+    types::{KissaConfig, ScanConfig},
+};
+use kissa::core::filter::RepoFilter;
 use kissa::core::index::Index;
-use kissa::core::repo::Repo;
-use kissa::core::scanner::{self, ScanEvent};
+use kissa::core::repo::{Repo, RepoState};
+use kissa::core::scanner::{self, IndexSkip, PathProbe, ScanEvent, ScanResult};
+use kissa::core::{classify, hook};
 
 #[derive(clap::Args)]
 pub struct ScanArgs {
@@ -17,81 +25,305 @@ pub struct ScanArgs {
     #[arg(long)]
     pub full: bool,
 
-    /// Override scan roots
-    #[arg(long)]
+    /// Override scan roots (default: scan.roots, or the project root with --local)
+    #[arg(long, conflicts_with = "from_stdin")]
     pub roots: Option<Vec<String>>,
+
+    /// Read candidate repo paths from stdin, one per line, instead of walking
+    /// roots (e.g. from `fd -H -t d -g .git`)
+    #[arg(long)]
+    pub from_stdin: bool,
+
+    /// With --from-stdin, paths are NUL-separated (`fd -0`, `find -print0`)
+    #[arg(short = '0', long = "null", requires = "from_stdin")]
+    pub null: bool,
+
+    /// Show errors recorded by the last scan instead of scanning
+    #[arg(long)]
+    pub show_errors: bool,
+
+    /// Don't index tool-managed repos, and drop any already indexed
+    #[arg(long)]
+    pub prune_managed: bool,
+
+    /// Don't ask before --prune-managed drops repos from the index
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Allow `/` as a scan root; /proc, /sys, /dev and /run are still skipped
+    #[arg(long, conflicts_with = "from_stdin")]
+    pub allow_huge_root: bool,
+
+    /// Parallel workers for reading repos (default: scan.jobs, 0 = all cores)
+    #[arg(long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Skip reading working trees (dirty/staged/untracked), the slowest part
+    /// on network mounts; repos keep their last known status, marked stale
+    #[arg(long)]
+    pub no_status: bool,
+
+    /// Only read repos git has written to within this age (e.g. 1d, 12h):
+    /// others already indexed keep their cached vitals. New repos are
+    /// always read
+    #[arg(long, value_name = "AGE", value_parser = crate::cli::parse_age)]
+    pub since: Option<chrono::Duration>,
+
+    /// Re-derive classification of already indexed repos from the current
+    /// rules, as `classify --reapply` does; tags set by hand are kept
+    #[arg(long)]
+    pub reclassify: bool,
 }
 
-pub fn run(args: ScanArgs, format: OutputFormat) -> anyhow::Result<()> {
-    let cfg = config::load_config()?;
+/// One repo written to the index by a scan, as reported in `--format json`.
+#[derive(Serialize)]
+struct TouchedRepo {
+    path: PathBuf,
+    name: String,
+    action: UpsertAction,
+    dirty: bool,
+    ahead: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum UpsertAction {
+    Inserted,
+    Updated,
+}
+
+pub fn run(args: ScanArgs, format: OutputFormat, verbosity: Verbosity) -> anyhow::Result<()> {
+    let mut cfg = config::load_config()?;
+    if args.no_status {
+        cfg.scan.compute_status = false;
+    }
     let index = Index::open(&config::index_path())?;
 
-    let roots: Vec<PathBuf> = if let Some(ref r) = args.roots {
+    if args.show_errors {
+        return show_errors(&index, format);
+    }
+
+    // Resolved once here, so the walk, the nesting bound and the recorded
+    // scan all agree on them
+    let roots = scanner::resolve_roots(&if args.from_stdin {
+        Vec::new()
+    } else if let Some(ref r) = args.roots {
         r.iter().map(PathBuf::from).collect()
     } else {
-        cfg.scan.roots.clone()
+        config::default_scan_roots(&cfg.scan)
+    });
+
+    let result = if args.from_stdin {
+        let mut input = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut input)?;
+        let separator = if args.null { b'\0' } else { b'\n' };
+        let candidates = input
+            .split(|&b| b == separator)
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .filter(|line| !line.is_empty())
+            .map(path_from_bytes);
+        let result = scanner::discover_paths(candidates);
+        if !verbosity.is_quiet() {
+            for (path, error) in &result.errors {
+                eprintln!("  {} {}: {}", "skipped:".yellow(), path.display(), error);
+            }
+        }
+        result
+    } else {
+        walk_roots(&roots, &cfg.scan, args.allow_huge_root, verbosity)?
     };
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
+    // Nesting is judged among the repos this scan found or the index already
+    // knows, below the scan roots (the configured ones for --from-stdin)
+    let mut known = index.indexed_paths()?;
+    known.extend(result.discovered.iter().map(|d| d.path.clone()));
+    let nest_roots = if args.from_stdin {
+        scanner::resolve_roots(&config::default_scan_roots(&cfg.scan))
+    } else {
+        roots.clone()
+    };
 
-    let pb_clone = pb.clone();
-    let progress: Option<Box<dyn Fn(ScanEvent) + Send>> = Some(Box::new(move |event| {
-        match event {
-            ScanEvent::RepoFound(p) => {
-                pb_clone.set_message(format!("found {}", p.display()));
-            }
-            ScanEvent::DirectoryEntered(p) => {
-                pb_clone.set_message(format!("scanning {}", p.display()));
-            }
-            ScanEvent::Skipped { .. } => {}
-            ScanEvent::Error { path, error } => {
-                pb_clone.set_message(format!("error: {} — {}", path.display(), error));
+    // With --since, indexed repos git hasn't touched lately aren't read at all
+    let since = args
+        .since
+        .map(|age| {
+            chrono::Utc::now()
+                .checked_sub_signed(age)
+                .ok_or_else(|| anyhow::anyhow!("--since reaches back past the earliest date"))
+        })
+        .transpose()?;
+    // Paths ignored with `forget --permanent` aren't read at all
+    let mut skipped_ignored = 0;
+    let mut wanted = Vec::new();
+    for discovered in &result.discovered {
+        if index.is_ignored(&discovered.path)? {
+            skipped_ignored += 1;
+        } else {
+            wanted.push(discovered.clone());
+        }
+    }
+
+    let mut unchanged = 0;
+    let to_read = match since {
+        Some(since) => {
+            let cutoff = SystemTime::from(since);
+            let mut to_read = Vec::new();
+            for discovered in wanted {
+                let cached = index.get_repo_by_path(&discovered.path)?;
+                if cached.is_some_and(|repo| repo.state == RepoState::Active)
+                    && !scanner::git_touched_since(&discovered.path, cutoff)
+                {
+                    unchanged += 1;
+                } else {
+                    to_read.push(discovered);
+                }
             }
+            to_read
         }
-        pb_clone.tick();
-    }));
+        None => wanted,
+    };
 
-    let result = scanner::full_scan(&roots, &cfg.scan, progress)?;
-    pb.finish_and_clear();
+    // Phase two: the discovery walk gave us the total, so show a real bar
+    let jobs = args.jobs.unwrap_or(cfg.scan.jobs);
+    let bar = if verbosity.is_quiet() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(to_read.len() as u64)
+    };
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{bar:30.green} {pos}/{len} {msg}")
+            .unwrap(),
+    );
+    bar.set_message("reading repos");
+    let extracted = scanner::extract_all(&to_read, jobs, &cfg.scan, |_| bar.inc(1));
+    bar.finish_and_clear();
 
-    // Extract vitals and upsert each discovered repo
+    // Upsert each discovered repo
     let mut upserted = 0;
-    for discovered in &result.discovered {
-        match git_ops::extract_vitals(&discovered.path) {
+    let mut touched = Vec::new();
+    let mut skipped_managed = 0;
+    let mut skipped_orgs = 0;
+    let mut nested = Vec::new();
+    let mut reclassified = 0;
+    let mut errors = result.errors.clone();
+    for (discovered, vitals) in to_read.iter().zip(extracted) {
+        match vitals {
             Ok(vitals) => {
                 let mut repo = Repo::from_vitals(vitals, discovered.path.clone());
+                if !discovered.is_bare {
+                    repo.nested_in = scanner::enclosing_repo(&repo.path, &known, &nest_roots);
+                }
                 classify::classify_repo(&mut repo, &cfg);
-                if index.upsert_repo(&repo).is_ok() {
+                match scanner::index_skip(&repo, &cfg.scan, args.prune_managed) {
+                    Some(IndexSkip::Managed) => {
+                        skipped_managed += 1;
+                        continue;
+                    }
+                    Some(IndexSkip::ExcludedOrg) => {
+                        skipped_orgs += 1;
+                        continue;
+                    }
+                    None => {}
+                }
+                let action = match index.get_repo_by_path(&repo.path)? {
+                    Some(cached) => {
+                        // The index keeps the cached status too; report that one
+                        if repo.status_checked.is_none() {
+                            repo.dirty = cached.dirty;
+                            repo.staged = cached.staged;
+                            repo.untracked = cached.untracked;
+                            repo.status_checked = cached.status_checked;
+                        }
+                        UpsertAction::Updated
+                    }
+                    None => UpsertAction::Inserted,
+                };
+                if let Ok(id) = index.upsert_vitals(&repo) {
+                    index.record_scan_snapshot(id)?;
                     upserted += 1;
+                    if matches!(action, UpsertAction::Inserted) {
+                        repo.id = id;
+                        run_classify_hook(&index, &cfg, &mut repo, verbosity)?;
+                    } else if args.reclassify
+                        && let Some(mut stored) = index.get_repo_by_path(&repo.path)?
+                        && classify::reclassify(&mut stored, &cfg)
+                    {
+                        index.set_classification(&stored)?;
+                        reclassified += 1;
+                    }
+                    if let Some(outer) = repo.nested_in.clone() {
+                        nested.push((repo.path.clone(), outer));
+                    }
+                    touched.push(TouchedRepo {
+                        path: repo.path,
+                        name: repo.name,
+                        action,
+                        dirty: repo.dirty,
+                        ahead: repo.ahead,
+                    });
                 }
             }
             Err(e) => {
-                eprintln!(
-                    "  {} could not read {}: {}",
-                    "warn:".yellow(),
-                    discovered.path.display(),
-                    e
-                );
+                if !verbosity.is_quiet() {
+                    eprintln!(
+                        "  {} could not read {}: {}",
+                        "warn:".yellow(),
+                        discovered.path.display(),
+                        e
+                    );
+                }
+                errors.push((discovered.path.clone(), e.to_string()));
             }
         }
     }
 
-    index.record_scan(&roots, upserted)?;
+    let timed_out = if args.from_stdin {
+        0
+    } else {
+        mark_unresponsive(&index, &roots, &result, &cfg.scan)?
+    };
+
+    // Also catches managed repos indexed by earlier scans or outside these roots
+    let removed_managed = if args.prune_managed {
+        let managed = index.list_repos(&RepoFilter::new().show_managed(true))?;
+        if crate::cli::safety::confirm_many("forget", &managed, args.yes)? {
+            index.forget_managed()?
+        } else {
+            eprintln!(
+                "  {} {} managed repos kept in index",
+                "aborted:".yellow().bold(),
+                managed.len()
+            );
+            0
+        }
+    } else {
+        0
+    };
+
+    index.record_scan(&roots, upserted, &errors)?;
 
     match format {
         OutputFormat::Json => {
             let summary = serde_json::json!({
                 "discovered": result.discovered.len(),
                 "upserted": upserted,
+                "unchanged": unchanged,
                 "skipped_excluded": result.skipped_excluded,
                 "skipped_mounts": result.skipped_mounts,
-                "errors": result.errors.len(),
+                "skipped_managed": skipped_managed,
+                "skipped_orgs": skipped_orgs,
+                "skipped_ignored": skipped_ignored,
+                "removed_managed": removed_managed,
+                "timed_out": timed_out,
+                "reclassified": reclassified,
+                "nested": nested
+                    .iter()
+                    .map(|(path, outer)| serde_json::json!({ "path": path, "nested_in": outer }))
+                    .collect::<Vec<_>>(),
+                "errors": errors.len(),
                 "duration_ms": result.duration.as_millis(),
+                "repos": touched,
             });
             serde_json::to_writer_pretty(std::io::stdout(), &summary)?;
             println!();
@@ -103,11 +335,15 @@ pub fn run(args: ScanArgs, format: OutputFormat) -> anyhow::Result<()> {
                 result.discovered.len(),
                 result.duration.as_secs_f64(),
             );
-            println!(
-                "  {} {} repos indexed",
-                "indexed:".bold(),
-                upserted,
-            );
+            println!("  {} {} repos indexed", "indexed:".bold(), upserted,);
+            if let Some(since) = since {
+                println!(
+                    "  {} {} repos untouched since {}, cached vitals kept",
+                    "unchanged:".dimmed(),
+                    unchanged,
+                    since.format("%Y-%m-%d %H:%M"),
+                );
+            }
             if result.skipped_excluded > 0 {
                 println!(
                     "  {} {} paths excluded",
@@ -115,13 +351,254 @@ pub fn run(args: ScanArgs, format: OutputFormat) -> anyhow::Result<()> {
                     result.skipped_excluded,
                 );
             }
-            if !result.errors.is_empty() {
+            if skipped_orgs > 0 {
+                println!(
+                    "  {} {} repos from excluded orgs",
+                    "skipped:".dimmed(),
+                    skipped_orgs,
+                );
+            }
+            if skipped_ignored > 0 {
+                println!(
+                    "  {} {} ignored repos (kissa unignore <path> to index again)",
+                    "skipped:".dimmed(),
+                    skipped_ignored,
+                );
+            }
+            if reclassified > 0 {
+                println!(
+                    "  {} {} repos picked up changed classify rules",
+                    "reclassified:".bold(),
+                    reclassified,
+                );
+            }
+            for (path, outer) in &nested {
                 println!(
-                    "  {} {} errors",
-                    "errors:".red(),
-                    result.errors.len(),
+                    "  {} {} is inside {} (not a submodule)",
+                    "nested:".yellow(),
+                    path.display(),
+                    outer.display(),
                 );
             }
+            if timed_out > 0 {
+                println!(
+                    "  {} {} indexed repos did not respond (kissa list --timeout)",
+                    "timeout:".yellow(),
+                    timed_out,
+                );
+            }
+            if args.prune_managed {
+                println!(
+                    "  {} {} managed repos skipped, {} removed from index",
+                    "pruned:".dimmed(),
+                    skipped_managed,
+                    removed_managed,
+                );
+            }
+            if !errors.is_empty() {
+                println!("  {} {} errors", "errors:".red(), errors.len(),);
+                println!(
+                    "  {} run {} to review them",
+                    "hint:".dimmed(),
+                    "kissa scan --show-errors".bold(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Probe indexed repos under the resolved `roots` that the walk didn't
+/// reach, within `boundaries.stat_timeout_ms`, and mark the ones whose path
+/// doesn't answer as `timeout`. Returns how many were marked.
+fn mark_unresponsive(
+    index: &Index,
+    roots: &[PathBuf],
+    result: &ScanResult,
+    scan: &ScanConfig,
+) -> anyhow::Result<usize> {
+    let discovered: HashSet<&Path> = result.discovered.iter().map(|d| d.path.as_path()).collect();
+    let mut unseen = Vec::new();
+    for root in roots {
+        unseen.extend(index.repos_under(root)?.into_iter().filter(|repo| {
+            repo.state != RepoState::Lost && !discovered.contains(repo.path.as_path())
+        }));
+    }
+    let timeout = Duration::from_millis(scan.boundaries.stat_timeout_ms);
+    let probes = scanner::map_parallel(&unseen, scan.jobs, |repo| {
+        scanner::probe_repo_path(&repo.path, timeout)
+    });
+    let mut marked = 0;
+    for (repo, probe) in unseen.iter().zip(probes) {
+        if probe == PathProbe::TimedOut {
+            index.mark_timeout(repo.id)?;
+            marked += 1;
+        }
+    }
+    Ok(marked)
+}
+
+/// Walk `roots` for repos behind a spinner, tracing directories under `-v`.
+fn walk_roots(
+    roots: &[PathBuf],
+    scan: &ScanConfig,
+    allow_huge_root: bool,
+    verbosity: Verbosity,
+) -> anyhow::Result<ScanResult> {
+    let pb = if verbosity.is_quiet() {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new_spinner()
+    };
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+
+    let pb_clone = pb.clone();
+    let progress: Option<Box<dyn Fn(ScanEvent) + Send>> = Some(Box::new(move |event| {
+        match event {
+            ScanEvent::RepoFound(p) => {
+                pb_clone.set_message(format!("found {}", p.display()));
+            }
+            ScanEvent::DirectoryEntered(p) => {
+                if verbosity.is_verbose() {
+                    pb_clone.suspend(|| eprintln!("  {} {}", "scanning:".dimmed(), p.display()));
+                }
+                pb_clone.set_message(format!("scanning {}", p.display()));
+            }
+            ScanEvent::Skipped { path, reason } => {
+                if verbosity.is_verbose() {
+                    pb_clone.suspend(|| {
+                        eprintln!(
+                            "  {} {} ({:?})",
+                            "skipped:".dimmed(),
+                            path.display(),
+                            reason
+                        )
+                    });
+                }
+            }
+            ScanEvent::Error { path, error } => {
+                pb_clone.set_message(format!("error: {} — {}", path.display(), error));
+            }
+            ScanEvent::Warning { path, message } => {
+                if !verbosity.is_quiet() {
+                    pb_clone.suspend(|| {
+                        eprintln!("  {} {}: {}", "warn:".yellow(), path.display(), message)
+                    });
+                }
+            }
+        }
+        pb_clone.tick();
+    }));
+
+    let result = scanner::full_scan(roots, scan, allow_huge_root, progress)?;
+    pb.finish_and_clear();
+    Ok(result)
+}
+
+/// A path as raw stdin bytes; on Unix any bytes, elsewhere UTF-8.
+pub(crate) fn path_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// Apply `classification.hook` to a freshly inserted repo. A failing or
+/// blocked hook is a warning, not a failed scan.
+fn run_classify_hook(
+    index: &Index,
+    cfg: &KissaConfig,
+    repo: &mut Repo,
+    verbosity: Verbosity,
+) -> anyhow::Result<()> {
+    match hook::classify_hook_tags(cfg, repo, false) {
+        Ok(Some(tags)) => {
+            let before = repo.tags.len();
+            for tag in tags {
+                if !repo.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                    repo.tags.push(tag);
+                }
+            }
+            if repo.tags.len() > before {
+                index.set_tags(repo.id, &repo.tags)?;
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            if !verbosity.is_quiet() {
+                eprintln!("  {} {}: {}", "warn:".yellow(), repo.path.display(), e);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn show_errors(index: &Index, format: OutputFormat) -> anyhow::Result<()> {
+    let errors = index.last_scan_errors()?;
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &errors)?;
+            println!();
+        }
+        OutputFormat::Paths => {
+            for err in &errors {
+                println!("{}", err.path.display());
+            }
+        }
+        OutputFormat::PathsNull => {
+            for err in &errors {
+                crate::cli::output::write_path_null(&mut std::io::stdout(), &err.path)?;
+            }
+        }
+        OutputFormat::Porcelain => {
+            for err in &errors {
+                println!(
+                    "{}\t{}",
+                    porcelain_field(&err.path.display().to_string()),
+                    porcelain_field(&err.message),
+                );
+            }
+        }
+        OutputFormat::Csv => {
+            println!("{}", crate::cli::output::csv_line(&["path", "message"]));
+            for err in &errors {
+                let path = err.path.display().to_string();
+                println!("{}", crate::cli::output::csv_line(&[&path, &err.message]));
+            }
+        }
+        OutputFormat::Human => {
+            let Some(ts) = index.last_scan_time()? else {
+                println!("  {} no scans recorded yet", "errors:".dimmed());
+                return Ok(());
+            };
+            if errors.is_empty() {
+                println!(
+                    "  {} last scan ({}) had no errors",
+                    "errors:".green().bold(),
+                    ts.format("%Y-%m-%d %H:%M"),
+                );
+            } else {
+                println!(
+                    "  {} {} from last scan ({})",
+                    "errors:".red().bold(),
+                    errors.len(),
+                    ts.format("%Y-%m-%d %H:%M"),
+                );
+                for err in &errors {
+                    println!("    {} {}", err.path.display(), err.message.dimmed());
+                }
+            }
         }
     }
 
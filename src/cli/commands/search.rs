@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use crate::cli::{Outcome, OutputFormat, Verbosity};
+
+#[derive(clap::Args)]
+pub struct SearchArgs {
+    /// Words to find in repo names, paths, tags and notes (all must match)
+    #[arg(required = true)]
+    pub query: Vec<String>,
+
+    /// Show at most N repos
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+}
+
+pub fn run(
+    args: SearchArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    let index = crate::cli::open_index(root)?;
+
+    let mut repos = index.search_fulltext(&args.query.join(" "))?;
+    if let Some(n) = args.limit {
+        repos.truncate(n);
+    }
+
+    if !verbosity.is_quiet() {
+        crate::cli::output::output_repos(&repos, format, &mut std::io::stdout())?;
+    }
+
+    Ok(if repos.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
@@ -1,28 +1,49 @@
 use std::path::Path;
 
-use crate::cli::OutputFormat;
+use super::list::FilterArgs;
+use crate::cli::{Outcome, OutputFormat};
 use kissa::config;
 use kissa::core::index::Index;
+use kissa::core::repo::Repo;
 
 #[derive(clap::Args)]
 pub struct StatusArgs {
-    /// Repo name or path
-    pub repo: String,
-}
+    /// Repo name or path; omit and pass filter flags (e.g. --all, --dirty) for every match
+    pub repo: Option<String>,
 
-pub fn run(args: StatusArgs, format: OutputFormat) -> anyhow::Result<()> {
-    let index = Index::open(&config::index_path())?;
+    /// Show cached vitals even if older than scan.auto_verify_seconds
+    #[arg(long)]
+    pub no_refresh: bool,
 
-    let repo = if Path::new(&args.repo).is_absolute() {
-        index.get_repo_by_path(Path::new(&args.repo))?
-    } else {
-        index.get_repo_by_name(&args.repo)?
-    };
+    /// Print the one-line summary `list` shows instead of the full block
+    #[arg(long)]
+    pub short: bool,
 
-    let Some(repo) = repo else {
-        anyhow::bail!("repo not found: {}", args.repo);
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+pub fn run(args: StatusArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<Outcome> {
+    let mut filter = args.filter;
+    // --include-lost also widens single-repo lookup, so it alone isn't a batch filter
+    let include_lost = std::mem::take(&mut filter.include_lost);
+    let filtered = filter != FilterArgs::default();
+    let query = match args.repo {
+        Some(_) if filtered => anyhow::bail!("pass either a repo or filter flags, not both"),
+        Some(query) => query,
+        None if filtered => {
+            filter.include_lost = include_lost;
+            return run_batch(filter, args.no_refresh, args.short, root, format);
+        }
+        None => anyhow::bail!("specify a repo, or filter flags such as --all or --dirty"),
     };
 
+    let index = crate::cli::open_index(root)?;
+    let mut repo = resolve_repo(&index, &query, include_lost)?;
+
+    let cfg = config::load_config()?;
+    super::info::refresh_if_stale(&index, &mut repo, &cfg.scan, false, args.no_refresh)?;
+
     match format {
         OutputFormat::Json => {
             serde_json::to_writer_pretty(std::io::stdout(), &repo)?;
@@ -32,12 +53,79 @@ pub fn run(args: StatusArgs, format: OutputFormat) -> anyhow::Result<()> {
             println!("{}", repo.path.display());
         }
         OutputFormat::PathsNull => {
-            print!("{}\0", repo.path.display());
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &repo.path)?;
+        }
+        OutputFormat::Porcelain => {
+            println!("{}", crate::cli::output::porcelain_line(&repo));
+        }
+        OutputFormat::Csv => {
+            crate::cli::output::output_repos(
+                std::slice::from_ref(&repo),
+                format,
+                &mut std::io::stdout(),
+            )?;
+        }
+        OutputFormat::Human if args.short => {
+            println!("{}", crate::cli::display::render_repo_line(&repo));
         }
         OutputFormat::Human => {
             println!("{}", crate::cli::display::render_status(&repo));
         }
     }
 
-    Ok(())
+    Ok(Outcome::Success)
+}
+
+/// Full status for every repo matching the filter, refreshing stale vitals as `status` does.
+fn run_batch(
+    filter: FilterArgs,
+    no_refresh: bool,
+    short: bool,
+    root: Option<&Path>,
+    format: OutputFormat,
+) -> anyhow::Result<Outcome> {
+    let index = crate::cli::open_index(root)?;
+    let mut repos = index.list_repos(&filter.into_filter())?;
+    let cfg = config::load_config()?;
+    for repo in &mut repos {
+        super::info::refresh_if_stale(&index, repo, &cfg.scan, false, no_refresh)?;
+    }
+
+    match format {
+        OutputFormat::Human if short => {
+            for repo in &repos {
+                println!("{}", crate::cli::display::render_repo_line(repo));
+            }
+        }
+        OutputFormat::Human => {
+            let blocks: Vec<String> = repos
+                .iter()
+                .map(crate::cli::display::render_status)
+                .collect();
+            let rule = format!("\n{}\n", crate::cli::display::rule());
+            if !blocks.is_empty() {
+                println!("{}", blocks.join(&rule));
+            }
+        }
+        _ => crate::cli::output::output_repos(&repos, format, &mut std::io::stdout())?,
+    }
+
+    Ok(if repos.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
+
+/// Look up a repo by absolute path, or else by name (exact, then prefix, then
+/// substring), settling ties per `display.name_collision`. Names only match
+/// lost repos when `include_lost` is set.
+pub(crate) fn resolve_repo(index: &Index, query: &str, include_lost: bool) -> anyhow::Result<Repo> {
+    let repo = if Path::new(query).is_absolute() {
+        index.get_repo_by_path(Path::new(query))?
+    } else {
+        let strategy = config::load_config()?.display.name_collision;
+        index.resolve_repo_name(query, include_lost, strategy)?
+    };
+    repo.ok_or_else(|| anyhow::anyhow!("repo not found: {}", query))
 }
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+
+use super::list::FilterArgs;
+use crate::cli::{Outcome, OutputFormat, Verbosity, output};
+
+#[derive(clap::Args)]
+pub struct TagArgs {
+    /// Tag to add to every matching repo
+    pub tag: String,
+
+    /// Remove the tag instead of adding it
+    #[arg(long)]
+    pub remove: bool,
+
+    /// Only repos whose paths are read from stdin, one per line (e.g. from
+    /// `kissa list --format paths`); the filter options still apply
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// With --stdin, paths are NUL-separated (`--format paths-null`)
+    #[arg(short = '0', long = "null", requires = "stdin")]
+    pub null: bool,
+
+    /// Change every listed repo when neither a filter nor --stdin narrows them
+    #[arg(short, long)]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Add a tag to (or remove it from) every repo matching the filter, in one
+/// transaction. Without a filter or `--stdin` that would be every repo
+/// `kissa list` shows, so it takes `--yes`.
+pub fn run(
+    args: TagArgs,
+    root: Option<&Path>,
+    format: OutputFormat,
+    verbosity: Verbosity,
+) -> anyhow::Result<Outcome> {
+    let tag = args.tag.trim();
+    if tag.is_empty() {
+        anyhow::bail!("tag must not be empty");
+    }
+    let index = crate::cli::open_index(root)?;
+    let filtered = args.filter != FilterArgs::default();
+    let mut repos = index.list_repos(&args.filter.into_filter())?;
+    if !filtered && !args.stdin && !args.yes {
+        anyhow::bail!(
+            "no filter given: this would {} '{}' {} all {} listed repos; \
+             narrow it with a filter or --stdin, or pass --yes",
+            if args.remove { "remove" } else { "add" },
+            tag,
+            if args.remove { "from" } else { "to" },
+            repos.len(),
+        );
+    }
+
+    if args.stdin {
+        let mut input = Vec::new();
+        std::io::stdin().lock().read_to_end(&mut input)?;
+        let separator = if args.null { b'\0' } else { b'\n' };
+        let mut wanted = HashSet::new();
+        for line in input.split(|&b| b == separator) {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            if line.is_empty() {
+                continue;
+            }
+            let path = super::scan::path_from_bytes(line);
+            match index.get_repo_by_path(&path)? {
+                Some(repo) => {
+                    wanted.insert(repo.id);
+                }
+                None if !verbosity.is_quiet() => {
+                    eprintln!(
+                        "  {} {} is not indexed",
+                        "skipped:".yellow(),
+                        path.display()
+                    );
+                }
+                None => {}
+            }
+        }
+        repos.retain(|repo| wanted.contains(&repo.id));
+    }
+
+    let ids: Vec<_> = repos.iter().map(|repo| repo.id).collect();
+    let changed = if args.remove {
+        index.remove_tag(&ids, tag)?
+    } else {
+        index.add_tag(&ids, tag)?
+    };
+
+    if !verbosity.is_quiet() {
+        match format {
+            OutputFormat::Json => {
+                let result = serde_json::json!({
+                    "tag": tag,
+                    "removed": args.remove,
+                    "matched": repos.len(),
+                    "changed": changed,
+                    "repos": repos.iter().map(|repo| &repo.path).collect::<Vec<_>>(),
+                });
+                serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+                println!();
+            }
+            OutputFormat::Human => {
+                let (verb, action, unchanged) = if args.remove {
+                    ("untagged:", "removed from", "didn't have it")
+                } else {
+                    ("tagged:", "added to", "already had it")
+                };
+                println!(
+                    "  {} {} {} {} repos",
+                    verb.green().bold(),
+                    format!("#{}", tag).bold(),
+                    action,
+                    changed,
+                );
+                if repos.len() > changed {
+                    println!(
+                        "  {} {} {}",
+                        "unchanged:".dimmed(),
+                        repos.len() - changed,
+                        unchanged
+                    );
+                }
+            }
+            _ => output::output_repos(&repos, format, &mut std::io::stdout())?,
+        }
+    }
+    Ok(if repos.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
@@ -0,0 +1,295 @@
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+
+use ansi_to_tui::IntoText;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::list::FilterArgs;
+use crate::cli::display;
+use kissa::core::repo::Repo;
+
+#[derive(clap::Args)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// Browser state: the repos loaded at startup and what the user has typed.
+struct Browser {
+    repos: Vec<Repo>,
+    query: String,
+    /// Indexes into `repos` matching `query`, in list order.
+    visible: Vec<usize>,
+    /// Selection and scroll position within `visible`.
+    list: ListState,
+    /// Shown in place of the key help until the next key press.
+    message: Option<String>,
+}
+
+impl Browser {
+    fn new(repos: Vec<Repo>) -> Self {
+        let mut browser = Browser {
+            repos,
+            query: String::new(),
+            visible: Vec::new(),
+            list: ListState::default(),
+            message: None,
+        };
+        browser.refilter();
+        browser
+    }
+
+    /// Keep repos whose name, path or tags contain every typed word.
+    fn refilter(&mut self) {
+        let words: Vec<String> = self
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        self.visible = self
+            .repos
+            .iter()
+            .enumerate()
+            .filter(|(_, repo)| {
+                let haystack = format!(
+                    "{} {} {}",
+                    repo.name,
+                    repo.path.display(),
+                    repo.tags.join(" ")
+                )
+                .to_lowercase();
+                words.iter().all(|w| haystack.contains(w.as_str()))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        self.list = ListState::default().with_selected(Some(0));
+    }
+
+    fn selected(&self) -> usize {
+        self.list.selected().unwrap_or(0)
+    }
+
+    fn current(&self) -> Option<&Repo> {
+        self.visible.get(self.selected()).map(|&i| &self.repos[i])
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.visible.len().saturating_sub(1);
+        self.list
+            .select(Some(self.selected().saturating_add_signed(delta).min(last)));
+    }
+
+    /// Draw the whole frame: filter line, repo list, status panel of the
+    /// selected repo, key help.
+    fn draw(&mut self, frame: &mut Frame) {
+        let [filter_area, list_area, status_area, help_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(50),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Line::from(vec![
+                Span::raw(" "),
+                "filter:".bold(),
+                Span::raw(format!(" {}", self.query)),
+                "▏".dim(),
+                format!(" {}/{} repos", self.visible.len(), self.repos.len()).dim(),
+            ]),
+            filter_area,
+        );
+
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&i| ListItem::new(ansi_text(&display::render_repo_line(&self.repos[i]))))
+            .collect();
+        let list = List::new(items).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let status = match self.current() {
+            Some(repo) => ansi_text(&display::render_status(repo)),
+            None => Text::from("  No repos match.".dim()),
+        };
+        frame.render_widget(
+            Paragraph::new(status).block(Block::new().borders(Borders::TOP).dim()),
+            status_area,
+        );
+
+        let help = match self.message {
+            Some(ref message) => Line::from(format!(" {}", message).red()),
+            None => Line::from(
+                " ↑↓ move · type to filter · enter open in $EDITOR · esc clear/quit".dim(),
+            ),
+        };
+        frame.render_widget(help, help_area);
+    }
+}
+
+/// Styled text from the colored strings `display` renders for the terminal.
+fn ansi_text(rendered: &str) -> Text<'static> {
+    rendered
+        .into_text()
+        .unwrap_or_else(|_| Text::raw(rendered.to_string()))
+}
+
+/// Browse the catalogue interactively: filter as you type, read the
+/// selected repo's status, and open it in `$EDITOR`. Read-only: nothing in
+/// the index changes.
+pub fn run(args: TuiArgs, root: Option<&Path>) -> anyhow::Result<()> {
+    if !std::io::stdout().is_terminal() {
+        anyhow::bail!("kissa tui needs an interactive terminal");
+    }
+    let index = crate::cli::open_index(root)?;
+    let mut browser = Browser::new(index.list_repos(&args.filter.into_filter())?);
+
+    // ratatui::init switches to the alternate screen in raw mode and puts the
+    // terminal back on panic; restore covers exit and errors
+    let mut terminal = ratatui::init();
+    let result = browse(&mut terminal, &mut browser);
+    ratatui::restore();
+    result
+}
+
+fn browse(terminal: &mut DefaultTerminal, browser: &mut Browser) -> anyhow::Result<()> {
+    loop {
+        terminal.draw(|frame| browser.draw(frame))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        browser.message = None;
+        match key.code {
+            KeyCode::Up => browser.move_by(-1),
+            KeyCode::Down => browser.move_by(1),
+            KeyCode::PageUp => browser.move_by(-10),
+            KeyCode::PageDown => browser.move_by(10),
+            KeyCode::Home => browser.list.select(Some(0)),
+            KeyCode::End => browser.move_by(isize::MAX),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                browser.query.push(c);
+                browser.refilter();
+            }
+            KeyCode::Backspace => {
+                browser.query.pop();
+                browser.refilter();
+            }
+            KeyCode::Esc if !browser.query.is_empty() => {
+                browser.query.clear();
+                browser.refilter();
+            }
+            KeyCode::Esc => break,
+            KeyCode::Enter => {
+                if let Some(repo) = browser.current() {
+                    let path = repo.path.clone();
+                    let opened = suspended(|| open_in_editor(&path))?;
+                    terminal.clear()?;
+                    browser.message = opened.err().map(|e| e.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Hand the terminal back to the shell's screen for the length of `work`,
+/// as a full-screen editor needs it.
+fn suspended<T>(work: impl FnOnce() -> T) -> std::io::Result<T> {
+    terminal::disable_raw_mode()?;
+    execute!(std::io::stdout(), LeaveAlternateScreen)?;
+    let result = work();
+    execute!(std::io::stdout(), EnterAlternateScreen)?;
+    terminal::enable_raw_mode()?;
+    Ok(result)
+}
+
+/// Run `$VISUAL`, else `$EDITOR`, else `vi` on the repo directory from
+/// inside it. The variable may carry arguments (`code -w`); no shell is
+/// involved.
+fn open_in_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".into());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .current_dir(path)
+        .status()
+        .map_err(|e| anyhow::anyhow!("failed to run {}: {}", program, e))?;
+    if !status.success() {
+        anyhow::bail!("{} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn browser() -> Browser {
+        let mut tagged = crate::common::repo("web-app", "/code/acme/web-app");
+        tagged.tags = vec!["frontend".into()];
+        Browser::new(vec![
+            crate::common::repo("api-gateway", "/code/acme/api-gateway"),
+            tagged,
+            crate::common::repo("dotfiles", "/home/me/dotfiles"),
+        ])
+    }
+
+    #[test]
+    fn refilter_matches_every_word_across_name_path_and_tags() {
+        let mut browser = browser();
+        assert_eq!(browser.visible, vec![0, 1, 2]);
+
+        browser.query = "ACME front".into();
+        browser.refilter();
+        assert_eq!(browser.visible, vec![1]);
+        assert_eq!(browser.current().map(|r| r.name.as_str()), Some("web-app"));
+
+        browser.query = "nothing-like-this".into();
+        browser.refilter();
+        assert!(browser.visible.is_empty());
+        assert!(browser.current().is_none());
+    }
+
+    #[test]
+    fn refilter_resets_the_selection() {
+        let mut browser = browser();
+        browser.move_by(2);
+        browser.query = "acme".into();
+        browser.refilter();
+        assert_eq!(browser.selected(), 0);
+    }
+
+    #[test]
+    fn move_by_clamps_to_the_visible_list() {
+        let mut browser = browser();
+        browser.move_by(1);
+        assert_eq!(browser.selected(), 1);
+        browser.move_by(isize::MAX);
+        assert_eq!(browser.selected(), 2);
+        browser.move_by(-10);
+        assert_eq!(browser.selected(), 0);
+
+        browser.query = "nothing-like-this".into();
+        browser.refilter();
+        browser.move_by(1);
+        assert_eq!(browser.selected(), 0);
+    }
+}
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use owo_colors::OwoColorize;
+
+use crate::cli::OutputFormat;
+use kissa::config;
+use kissa::core::index::Index;
+
+#[derive(clap::Args)]
+pub struct UnignoreArgs {
+    /// Path previously ignored with `kissa forget --permanent`
+    pub path: PathBuf,
+}
+
+/// Let scans index a path again after `kissa forget --permanent`. The repo
+/// reappears on the next scan that covers it.
+pub fn run(args: UnignoreArgs, format: OutputFormat) -> anyhow::Result<()> {
+    let index = Index::open(&config::index_path())?;
+    if !index.unignore_path(&args.path)? {
+        anyhow::bail!("not ignored: {}", args.path.display());
+    }
+
+    match format {
+        OutputFormat::Json => {
+            let result = serde_json::json!({ "path": args.path, "unignored": true });
+            serde_json::to_writer_pretty(std::io::stdout(), &result)?;
+            println!();
+        }
+        OutputFormat::Paths | OutputFormat::Porcelain | OutputFormat::Csv => {
+            println!("{}", args.path.display())
+        }
+        OutputFormat::PathsNull => {
+            crate::cli::output::write_path_null(&mut std::io::stdout(), &args.path)?;
+        }
+        OutputFormat::Human => {
+            println!(
+                "  {} {} will be indexed by the next scan",
+                "unignored:".green().bold(),
+                args.path.display(),
+            );
+        }
+    }
+
+    Ok(())
+}
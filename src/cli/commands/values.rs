@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use crate::cli::{Outcome, OutputFormat};
+
+#[derive(clap::Args)]
+pub struct ValuesArgs {
+    /// Which field's values to list
+    #[arg(value_enum)]
+    pub field: ValueField,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ValueField {
+    Tags,
+    Projects,
+    ManagedBy,
+    Orgs,
+}
+
+/// Print every distinct value of a field in the index, one per line: the
+/// choices for `--tags`, `--managed-by` and `--org`, plus project names,
+/// for shell completion and filter UIs.
+pub fn run(args: ValuesArgs, root: Option<&Path>, format: OutputFormat) -> anyhow::Result<Outcome> {
+    let index = crate::cli::open_index(root)?;
+    let values = match args.field {
+        ValueField::Tags => index.distinct_tags()?,
+        ValueField::Projects => index.distinct_projects()?,
+        ValueField::ManagedBy => index.distinct_managed_by()?,
+        ValueField::Orgs => index.distinct_orgs()?,
+    };
+
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(std::io::stdout(), &values)?;
+            println!();
+        }
+        _ => {
+            for value in &values {
+                println!("{}", value);
+            }
+        }
+    }
+
+    Ok(if values.is_empty() {
+        Outcome::NoMatch
+    } else {
+        Outcome::Success
+    })
+}
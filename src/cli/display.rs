@@ -1,37 +1,229 @@
-use owo_colors::{OwoColorize, Style};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use chrono::{DateTime, FixedOffset, Utc};
+use owo_colors::{OwoColorize, Style, XtermColors};
 
 use kissa::core::index::FreshnessSummary;
 use kissa::core::repo::{Freshness, Repo, RepoState};
+use kissa::core::time::humanize_age;
+
+/// Whether paths are wrapped in OSC 8 hyperlinks; set once by `init_hyperlinks`.
+static HYPERLINKS: AtomicBool = AtomicBool::new(false);
+
+/// `display.stale_branch_warn`; set once by `init_stale_branch_warn`.
+static STALE_BRANCH_WARN: AtomicU32 = AtomicU32::new(0);
+
+/// Whether names are colored by commit age; set once by `init_gradient`.
+static GRADIENT: AtomicBool = AtomicBool::new(false);
+
+/// Whether indicators use Nerd Font glyphs; set once by `init_nerd_fonts`.
+static NERD_FONTS: AtomicBool = AtomicBool::new(false);
+
+/// Oldest age the gradient distinguishes; anything older gets the final color.
+const GRADIENT_MAX_DAYS: f64 = 730.0;
+
+/// Set the stale-branch count at which list lines carry a warning (0 = never).
+pub fn init_stale_branch_warn(threshold: u32) {
+    STALE_BRANCH_WARN.store(threshold, Ordering::Relaxed);
+}
+
+/// Enable hyperlinks when configured, stdout is a terminal, and the terminal
+/// is one known to render OSC 8. Anything else keeps plain paths.
+pub fn init_hyperlinks(configured: bool) {
+    let enabled = configured
+        && std::io::stdout().is_terminal()
+        && terminal_supports_hyperlinks(|key| std::env::var(key).ok());
+    HYPERLINKS.store(enabled, Ordering::Relaxed);
+}
+
+/// Enable the commit-age gradient when configured and the terminal
+/// advertises 256 colors; otherwise names keep their tier colors.
+pub fn init_gradient(configured: bool) {
+    let enabled = configured && terminal_supports_256_colors(|key| std::env::var(key).ok());
+    GRADIENT.store(enabled, Ordering::Relaxed);
+}
+
+/// Use Nerd Font glyphs for indicators and bars when `display.nerd_fonts`
+/// is set; otherwise the plain characters in `Glyphs::PLAIN`.
+pub fn init_nerd_fonts(configured: bool) {
+    NERD_FONTS.store(configured, Ordering::Relaxed);
+}
+
+/// The characters list lines, status and the freshness chart draw with.
+/// Every glyph is one column wide, so swapping sets never shifts alignment.
+#[derive(Debug, PartialEq, Eq)]
+struct Glyphs {
+    dirty: &'static str,
+    staged: &'static str,
+    ahead: &'static str,
+    behind: &'static str,
+    managed: &'static str,
+    warning: &'static str,
+    bar: &'static str,
+}
+
+impl Glyphs {
+    const PLAIN: Glyphs = Glyphs {
+        dirty: "*",
+        staged: "+",
+        ahead: "↑",
+        behind: "↓",
+        managed: "⚙",
+        warning: "⚠",
+        bar: "█",
+    };
+
+    const NERD: Glyphs = Glyphs {
+        dirty: "\u{f044}",   // nf-fa-edit
+        staged: "\u{f067}",  // nf-fa-plus
+        ahead: "\u{f062}",   // nf-fa-arrow_up
+        behind: "\u{f063}",  // nf-fa-arrow_down
+        managed: "\u{f013}", // nf-fa-cog
+        warning: "\u{f071}", // nf-fa-warning
+        bar: "\u{ee04}",     // filled progress bar middle (Nerd Fonts 3)
+    };
+
+    fn select(nerd_fonts: bool) -> &'static Glyphs {
+        if nerd_fonts {
+            &Glyphs::NERD
+        } else {
+            &Glyphs::PLAIN
+        }
+    }
+}
+
+fn glyphs() -> &'static Glyphs {
+    Glyphs::select(NERD_FONTS.load(Ordering::Relaxed))
+}
+
+fn terminal_supports_256_colors(env: impl Fn(&str) -> Option<String>) -> bool {
+    env("COLORTERM").is_some_and(|v| matches!(v.as_str(), "truecolor" | "24bit"))
+        || env("TERM").is_some_and(|v| v.contains("256color"))
+}
+
+/// Best-effort OSC 8 detection from the environment the terminal exports.
+fn terminal_supports_hyperlinks(env: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(program) = env("TERM_PROGRAM")
+        && matches!(
+            program.as_str(),
+            "iTerm.app" | "WezTerm" | "vscode" | "ghostty"
+        )
+    {
+        return true;
+    }
+    if env("KITTY_WINDOW_ID").is_some()
+        || env("WT_SESSION").is_some()
+        || env("KONSOLE_VERSION").is_some()
+    {
+        return true;
+    }
+    // VTE-based terminals (GNOME Terminal, Tilix, ...) since 0.50
+    env("VTE_VERSION")
+        .and_then(|v| v.parse::<u32>().ok())
+        .is_some_and(|v| v >= 5000)
+}
+
+/// Render `text` as a link to `path` when hyperlinks are on, else unchanged.
+fn path_link(path: &Path, text: String) -> String {
+    if !HYPERLINKS.load(Ordering::Relaxed) {
+        return text;
+    }
+    osc8(&file_url(path), &text)
+}
+
+fn osc8(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// `file://` URL for an absolute path, percent-encoding anything outside
+/// the unreserved set so spaces and control bytes survive. Windows paths
+/// become `file:///C:/...`.
+fn file_url(path: &Path) -> String {
+    let mut url = String::from("file://");
+    let mut path = path
+        .to_string_lossy()
+        .replace(std::path::MAIN_SEPARATOR, "/");
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b':' | b'-' | b'_' | b'.' | b'~' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    url
+}
 
 /// Render a single repo as a one-line summary for list output.
 pub fn render_repo_line(repo: &Repo) -> String {
-    let style = freshness_style(repo.freshness);
+    let style = match repo.last_commit {
+        Some(ts) if GRADIENT.load(Ordering::Relaxed) => {
+            Style::new().color(XtermColors::from(age_color((Utc::now() - ts).num_days())))
+        }
+        _ => freshness_style(repo.freshness),
+    };
     let name = format!("{}", repo.name.style(style));
+    let glyphs = glyphs();
 
     let mut indicators = Vec::new();
     if repo.dirty {
-        indicators.push("*".red().to_string());
+        indicators.push(glyphs.dirty.red().to_string());
     }
     if repo.staged {
-        indicators.push("+".green().to_string());
+        indicators.push(glyphs.staged.green().to_string());
     }
     if repo.ahead > 0 {
-        indicators.push(format!("{}↑", repo.ahead).yellow().to_string());
+        indicators.push(
+            format!("{}{}", repo.ahead, glyphs.ahead)
+                .yellow()
+                .to_string(),
+        );
     }
     if repo.behind > 0 {
-        indicators.push(format!("{}↓", repo.behind).yellow().to_string());
+        indicators.push(
+            format!("{}{}", repo.behind, glyphs.behind)
+                .yellow()
+                .to_string(),
+        );
     }
     if let Some(ref mb) = repo.managed_by {
-        indicators.push(format!("⚙{}", mb).dimmed().to_string());
+        indicators.push(format!("{}{}", glyphs.managed, mb).dimmed().to_string());
+    }
+    if repo.warn_stale_branches(STALE_BRANCH_WARN.load(Ordering::Relaxed)) {
+        indicators.push(
+            format!(
+                " {}{} stale branches",
+                glyphs.warning, repo.stale_branch_count
+            )
+            .yellow()
+            .to_string(),
+        );
+    }
+    if repo.is_empty {
+        indicators.push(" (empty)".dimmed().to_string());
+    }
+    if repo.nested_in.is_some() || repo.contains_nested {
+        indicators.push(" (nested)".yellow().to_string());
     }
-    if repo.state == RepoState::Lost {
-        indicators.push("LOST".red().bold().to_string());
+    match repo.state {
+        RepoState::Lost => indicators.push("LOST".red().bold().to_string()),
+        RepoState::Timeout => indicators.push(" TIMEOUT".yellow().bold().to_string()),
+        RepoState::Active => {}
     }
 
-    let branch = repo
-        .current_branch
-        .as_deref()
-        .unwrap_or("(detached)");
+    let branch = repo.current_branch.as_deref().unwrap_or("(detached)");
+    // Left on a feature branch: stands out from the dimmed default
+    let branch_label = format!("[{}]", branch);
+    let branch_label = if repo.off_default_branch() {
+        branch_label.yellow().to_string()
+    } else {
+        branch_label.dimmed().to_string()
+    };
 
     let indicator_str = if indicators.is_empty() {
         String::new()
@@ -42,12 +234,44 @@ pub fn render_repo_line(repo: &Repo) -> String {
     format!(
         "  {} {} {}{}",
         name,
-        format!("[{}]", branch).dimmed(),
-        repo.path.display().to_string().dimmed(),
+        branch_label,
+        path_link(
+            &repo.path,
+            repo.path.display().to_string().dimmed().to_string()
+        ),
         indicator_str,
     )
 }
 
+/// Compact, uncolored status for shell prompts: `kissa:<name>` plus the
+/// list-line indicators, e.g. `kissa:api-gateway *2↑`.
+pub fn render_prompt(repo: &Repo) -> String {
+    let glyphs = glyphs();
+    let mut flags = String::new();
+    if repo.dirty {
+        flags.push_str(glyphs.dirty);
+    }
+    if repo.staged {
+        flags.push_str(glyphs.staged);
+    }
+    if repo.ahead > 0 {
+        flags.push_str(&format!("{}{}", repo.ahead, glyphs.ahead));
+    }
+    if repo.behind > 0 {
+        flags.push_str(&format!("{}{}", repo.behind, glyphs.behind));
+    }
+    if flags.is_empty() {
+        format!("kissa:{}", repo.name)
+    } else {
+        format!("kissa:{} {}", repo.name, flags)
+    }
+}
+
+/// Horizontal separator between multi-line blocks such as batch `status`.
+pub fn rule() -> String {
+    "─".repeat(40).dimmed().to_string()
+}
+
 /// Render detailed status for a single repo.
 pub fn render_status(repo: &Repo) -> String {
     let mut lines = Vec::new();
@@ -60,7 +284,7 @@ pub fn render_status(repo: &Repo) -> String {
     lines.push(format!(
         "  {} {}",
         "path:".dimmed(),
-        repo.path.display()
+        path_link(&repo.path, repo.path.display().to_string())
     ));
 
     if let Some(ref branch) = repo.current_branch {
@@ -71,12 +295,41 @@ pub fn render_status(repo: &Repo) -> String {
             repo.default_branch.as_deref().unwrap_or("?"),
         ));
     }
+    if repo.state == RepoState::Timeout {
+        lines.push(format!(
+            "  {} path did not respond in time (slow or unavailable mount?); showing cached vitals",
+            "warning:".yellow().bold(),
+        ));
+    }
+    if repo.default_branch_drift {
+        lines.push(format!(
+            "  {} remote default branch has no local branch (renamed upstream?)",
+            "warning:".yellow().bold(),
+        ));
+    }
+    if let Some(ref outer) = repo.nested_in {
+        lines.push(format!(
+            "  {} inside the working tree of {} (not a submodule)",
+            "warning:".yellow().bold(),
+            outer.display(),
+        ));
+    }
+    if repo.contains_nested {
+        lines.push(format!(
+            "  {} other repos sit in its working tree (not submodules)",
+            "warning:".yellow().bold(),
+        ));
+    }
 
+    // Counting stopped at scan.max_branches, so both are lower bounds
+    let more = if repo.branches_truncated { "+" } else { "" };
     lines.push(format!(
-        "  {} total: {}, stale: {}",
+        "  {} total: {}{}, stale: {}{}",
         "branches:".dimmed(),
         repo.branch_count,
+        more,
         repo.stale_branch_count,
+        more,
     ));
 
     // Working tree
@@ -93,14 +346,47 @@ pub fn render_status(repo: &Repo) -> String {
     if wt.is_empty() {
         wt.push("clean".green().to_string());
     }
-    lines.push(format!("  {} {}", "tree:".dimmed(), wt.join(", ")));
+    // Scans run with --no-status leave the working-tree flags as they were
+    let tree = match repo.status_checked {
+        None => "unknown (never read; refresh to check)"
+            .dimmed()
+            .to_string(),
+        Some(checked) if repo.status_is_stale() => format!(
+            "{} {}",
+            wt.join(", "),
+            format!("(stale, as of {})", checked.format("%Y-%m-%d %H:%M")).dimmed(),
+        ),
+        Some(_) => wt.join(", "),
+    };
+    lines.push(format!("  {} {}", "tree:".dimmed(), tree));
+
+    let changes = repo.scan_changes();
+    if !changes.is_empty() {
+        lines.push(format!(
+            "  {} {}",
+            "since scan:".dimmed(),
+            changes.join(", ").yellow(),
+        ));
+    }
+
+    if let Some(signed) = repo.head_signed {
+        let label = if signed {
+            "signed".green().to_string()
+        } else {
+            "unsigned".dimmed().to_string()
+        };
+        lines.push(format!("  {} {}", "head:".dimmed(), label));
+    }
 
     // Ahead/behind
     if repo.ahead > 0 || repo.behind > 0 {
+        let glyphs = glyphs();
         lines.push(format!(
-            "  {} ↑{} ↓{}",
+            "  {} {}{} {}{}",
             "tracking:".dimmed(),
+            glyphs.ahead,
             repo.ahead,
+            glyphs.behind,
             repo.behind,
         ));
     }
@@ -119,57 +405,102 @@ pub fn render_status(repo: &Repo) -> String {
         ));
     }
 
-    // Classification
-    if let Some(ref mb) = repo.managed_by {
+    // Platform metadata from `kissa enrich`
+    if let Some(ref upstream) = repo.upstream {
+        let mut facts = vec![format!("{} stars", upstream.stars)];
+        if upstream.archived {
+            facts.push("archived".yellow().to_string());
+        }
+        if let Some(ref branch) = upstream.default_branch {
+            facts.push(format!("default {}", branch));
+        }
         lines.push(format!(
-            "  {} {}",
-            "managed by:".dimmed(),
-            mb,
+            "  {} {} {}",
+            "upstream:".dimmed(),
+            facts.join(", "),
+            format!("(as of {})", upstream.fetched_at.format("%Y-%m-%d")).dimmed(),
         ));
+        if let Some(ref description) = upstream.description {
+            lines.push(format!("    {}", description.italic()));
+        }
+    }
+
+    // Classification
+    if let Some(ref mb) = repo.managed_by {
+        lines.push(format!("  {} {}", "managed by:".dimmed(), mb,));
     }
     if let Some(ref cat) = repo.category {
-        lines.push(format!(
-            "  {} {:?}",
-            "category:".dimmed(),
-            cat,
-        ));
+        let upstream = repo
+            .origin_org
+            .as_ref()
+            .map(|org| format!(" (of {})", org))
+            .unwrap_or_default();
+        lines.push(format!("  {} {:?}{}", "category:".dimmed(), cat, upstream,));
     }
     if let Some(ref own) = repo.ownership {
-        lines.push(format!(
-            "  {} {:?}",
-            "ownership:".dimmed(),
-            own,
-        ));
+        lines.push(format!("  {} {:?}", "ownership:".dimmed(), own,));
     }
     if let Some(ref intent) = repo.intention {
-        lines.push(format!(
-            "  {} {:?}",
-            "intention:".dimmed(),
-            intent,
-        ));
+        lines.push(format!("  {} {:?}", "intention:".dimmed(), intent,));
     }
 
     // Tags
     if !repo.tags.is_empty() {
-        lines.push(format!(
-            "  {} {}",
-            "tags:".dimmed(),
-            repo.tags.join(", "),
-        ));
+        lines.push(format!("  {} {}", "tags:".dimmed(), repo.tags.join(", "),));
+    }
+
+    if let Some(ref note) = repo.note {
+        lines.push(format!("  {} {}", "note:".dimmed(), note));
     }
 
     // Last commit
-    if let Some(dt) = repo.last_commit {
+    if repo.is_empty {
         lines.push(format!(
             "  {} {}",
             "last commit:".dimmed(),
-            dt.format("%Y-%m-%d %H:%M"),
+            "empty repo".italic()
+        ));
+    } else if let Some(dt) = repo.last_commit_local() {
+        lines.push(format!("  {} {}", "last commit:".dimmed(), dated_local(dt),));
+    }
+    if let Some(n) = repo.commits_last_30d {
+        lines.push(format!(
+            "  {} {} commits in 30 days",
+            "activity:".dimmed(),
+            n
         ));
     }
 
+    if !repo.remotes.is_empty() {
+        let fetched = match repo.last_fetched {
+            Some(dt) => dated(dt),
+            None => "never".dimmed().to_string(),
+        };
+        lines.push(format!("  {} {}", "last fetch:".dimmed(), fetched));
+    }
+
     lines.join("\n")
 }
 
+/// Timestamp plus relative age, like `2026-03-01 14:05 (3d ago)`.
+fn dated(dt: DateTime<Utc>) -> String {
+    format!(
+        "{} {}",
+        dt.format("%Y-%m-%d %H:%M"),
+        format!("({})", humanize_age(dt, Utc::now())).dimmed(),
+    )
+}
+
+/// Like `dated`, but on the committer's clock with their UTC offset, like
+/// `2026-03-01 09:05 -05:00 (3d ago)`.
+fn dated_local(dt: DateTime<FixedOffset>) -> String {
+    format!(
+        "{} {}",
+        dt.format("%Y-%m-%d %H:%M %:z"),
+        format!("({})", humanize_age(dt.to_utc(), Utc::now())).dimmed(),
+    )
+}
+
 /// Render the freshness bar chart.
 pub fn render_freshness(summary: &FreshnessSummary, total: usize) -> String {
     if total == 0 {
@@ -188,11 +519,16 @@ pub fn render_freshness(summary: &FreshnessSummary, total: usize) -> String {
         ("stale", summary.stale, Freshness::Stale),
         ("dormant", summary.dormant, Freshness::Dormant),
         ("ancient", summary.ancient, Freshness::Ancient),
+        ("empty", summary.empty, Freshness::Empty),
     ];
 
     let max_bar = 40;
 
-    for (label, count, freshness) in &tiers {
+    // Empty repos have no tier of their own; only show them when present
+    for (label, count, freshness) in tiers
+        .iter()
+        .filter(|(_, count, f)| *count > 0 || *f != Freshness::Empty)
+    {
         let pct = if total > 0 {
             (*count as f64 / total as f64 * 100.0) as usize
         } else {
@@ -203,7 +539,7 @@ pub fn render_freshness(summary: &FreshnessSummary, total: usize) -> String {
         } else {
             0
         };
-        let bar = "█".repeat(bar_len);
+        let bar = glyphs().bar.repeat(bar_len);
         let style = freshness_style(*freshness);
 
         lines.push(format!(
@@ -218,6 +554,27 @@ pub fn render_freshness(summary: &FreshnessSummary, total: usize) -> String {
     lines.join("\n")
 }
 
+/// One-line tier breakdown like `3 active · 9 ancient`, skipping empty tiers.
+pub fn render_freshness_inline(summary: &FreshnessSummary) -> String {
+    [
+        ("active", summary.active, Freshness::Active),
+        ("recent", summary.recent, Freshness::Recent),
+        ("stale", summary.stale, Freshness::Stale),
+        ("dormant", summary.dormant, Freshness::Dormant),
+        ("ancient", summary.ancient, Freshness::Ancient),
+        ("empty", summary.empty, Freshness::Empty),
+    ]
+    .iter()
+    .filter(|(_, count, _)| *count > 0)
+    .map(|(label, count, freshness)| {
+        format!("{} {}", count, label)
+            .style(freshness_style(*freshness))
+            .to_string()
+    })
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
 /// Get the terminal style for a freshness tier.
 pub fn freshness_style(f: Freshness) -> Style {
     match f {
@@ -226,5 +583,149 @@ pub fn freshness_style(f: Freshness) -> Style {
         Freshness::Stale => Style::new().yellow(),
         Freshness::Dormant => Style::new().red(),
         Freshness::Ancient => Style::new().dimmed(),
+        Freshness::Empty => Style::new().italic(),
+    }
+}
+
+/// xterm-256 color for a commit `days` old: green for today, through yellow
+/// and red, fading to grey at `GRADIENT_MAX_DAYS`. Age is taken on a log
+/// scale so the first weeks, where most activity is, get most of the range.
+pub fn age_color(days: i64) -> u8 {
+    // (position, [r, g, b]) with channels in 0.0..=1.0
+    const STOPS: [(f64, [f64; 3]); 4] = [
+        (0.0, [0.0, 1.0, 0.0]),
+        (0.5, [1.0, 1.0, 0.0]),
+        (0.8, [1.0, 0.2, 0.0]),
+        (1.0, [0.5, 0.5, 0.5]),
+    ];
+    let t = ((days.max(0) as f64).ln_1p() / GRADIENT_MAX_DAYS.ln_1p()).min(1.0);
+    let i = STOPS
+        .iter()
+        .rposition(|(at, _)| *at <= t)
+        .unwrap_or(0)
+        .min(STOPS.len() - 2);
+    let ((from_at, from), (to_at, to)) = (STOPS[i], STOPS[i + 1]);
+    let local = (t - from_at) / (to_at - from_at);
+    // Map each channel onto the 6x6x6 color cube that starts at index 16
+    let level = |c: usize| ((from[c] + (to[c] - from[c]) * local) * 5.0).round() as u8;
+    16 + 36 * level(0) + 6 * level(1) + level(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyph_sets_are_single_column() {
+        assert_eq!(Glyphs::select(false), &Glyphs::PLAIN);
+        assert_eq!(Glyphs::select(true), &Glyphs::NERD);
+        for set in [&Glyphs::PLAIN, &Glyphs::NERD] {
+            let all = [
+                set.dirty,
+                set.staged,
+                set.ahead,
+                set.behind,
+                set.managed,
+                set.warning,
+                set.bar,
+            ];
+            for glyph in all {
+                assert_eq!(console::measure_text_width(glyph), 1, "{:?}", glyph);
+            }
+        }
+    }
+
+    #[test]
+    fn file_url_percent_encodes() {
+        assert_eq!(
+            file_url(Path::new("/home/me/my repo#1")),
+            "file:///home/me/my%20repo%231"
+        );
+        assert_eq!(
+            osc8("file:///x", "x"),
+            "\x1b]8;;file:///x\x1b\\x\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn detects_hyperlink_terminals() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert!(terminal_supports_hyperlinks(env(&[(
+            "TERM_PROGRAM",
+            "WezTerm"
+        )])));
+        assert!(terminal_supports_hyperlinks(env(&[(
+            "KITTY_WINDOW_ID",
+            "1"
+        )])));
+        assert!(terminal_supports_hyperlinks(env(&[(
+            "VTE_VERSION",
+            "7200"
+        )])));
+        assert!(!terminal_supports_hyperlinks(env(&[(
+            "VTE_VERSION",
+            "4600"
+        )])));
+        assert!(!terminal_supports_hyperlinks(env(&[(
+            "TERM_PROGRAM",
+            "Apple_Terminal"
+        )])));
+        assert!(!terminal_supports_hyperlinks(env(&[])));
+    }
+
+    #[test]
+    fn detects_256_color_terminals() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert!(terminal_supports_256_colors(env(&[(
+            "TERM",
+            "xterm-256color"
+        )])));
+        assert!(terminal_supports_256_colors(env(&[(
+            "COLORTERM",
+            "truecolor"
+        )])));
+        assert!(!terminal_supports_256_colors(env(&[("TERM", "xterm")])));
+        assert!(!terminal_supports_256_colors(env(&[])));
+    }
+
+    #[test]
+    fn age_color_runs_green_to_grey() {
+        let rgb = |code: u8| ((code - 16) / 36, (code - 16) / 6 % 6, (code - 16) % 6);
+        assert_eq!(rgb(age_color(0)), (0, 5, 0));
+        assert_eq!(age_color(-3), age_color(0));
+        assert_eq!(rgb(age_color(730)), (3, 3, 3));
+        assert_eq!(age_color(5000), age_color(730));
+
+        // Red rises and green falls on the way from fresh to stale
+        let (r_week, g_week, _) = rgb(age_color(7));
+        let (r_quarter, g_quarter, _) = rgb(age_color(90));
+        assert!(r_week > 0 && r_quarter >= r_week);
+        assert!(g_quarter < g_week);
+    }
+
+    #[test]
+    fn prompt_is_compact() {
+        let mut repo = Repo::from_vitals(
+            crate::common::vitals("api-gateway"),
+            "/code/api-gateway".into(),
+        );
+        assert_eq!(render_prompt(&repo), "kissa:api-gateway");
+        repo.dirty = true;
+        repo.ahead = 2;
+        assert_eq!(render_prompt(&repo), "kissa:api-gateway *2↑");
     }
 }
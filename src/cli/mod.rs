@@ -1,8 +1,13 @@
 pub mod commands;
 pub mod display;
 pub mod output;
+pub mod safety;
+
+use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand};
+use kissa::config;
+use kissa::core::index::Index;
 
 #[derive(Parser)]
 #[command(name = "kissa", about = "Finally herd your repos.", version)]
@@ -19,26 +24,93 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub cat_mode: bool,
 
+    /// Scope read commands to repos under this directory
+    #[arg(long, global = true, value_name = "PATH")]
+    pub root: Option<PathBuf>,
+
+    /// Use the project index in the nearest .kissa/ at or above the current directory
+    #[arg(long, global = true, conflicts_with = "index")]
+    pub local: bool,
+
+    /// Use this index database instead of the global one
+    #[arg(long, global = true, value_name = "PATH")]
+    pub index: Option<PathBuf>,
+
+    /// No progress or warnings; listings print nothing and only set the exit code
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Trace each directory and skipped path while scanning
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once per process; boxing buys nothing
 pub enum Commands {
+    /// Create the index (with --local, a project index in ./.kissa/)
+    Init,
     /// Scan filesystem for git repositories
     Scan(commands::scan::ScanArgs),
     /// List catalogued repositories
     List(commands::list::ListArgs),
+    /// Full-text search over repo names, paths, tags and notes
+    Search(commands::search::SearchArgs),
     /// Show detailed status of a repository
     Status(commands::status::StatusArgs),
+    /// Print compact cached status of the repo containing the current directory
+    Here,
+    /// Summarize uncommitted changes in a repository
+    Diff(commands::diff::DiffArgs),
+    /// Show how a repository's remote URLs changed over time
+    History(commands::history::HistoryArgs),
+    /// Fetch description, stars and archived state from GitHub/GitLab (needs identity.api_tokens)
+    Enrich(commands::enrich::EnrichArgs),
     /// Show full info dump for a repository
     Info(commands::info::InfoArgs),
+    /// List author emails in a repository's recent history with commit counts
+    Authors(commands::authors::AuthorsArgs),
+    /// Find repos with recent commits whose summary matches a pattern
+    GrepLog(commands::grep_log::GrepLogArgs),
+    /// Browse repos interactively: filter as you type, open in $EDITOR
+    Tui(commands::tui::TuiArgs),
     /// Show freshness overview
     Freshness,
+    /// Recompute freshness tiers from stored commit times, without git
+    RefreshFreshness,
+    /// Emit one NDJSON health record per repo, for monitoring systems
+    Metrics,
+    /// Write the index, summary and config as one versioned JSON document
+    Dump,
+    /// List tool-managed repos grouped by the tool that manages them
+    Managed,
+    /// List the distinct tags, projects, managing tools or orgs in the index
+    Values(commands::values::ValuesArgs),
+    /// Run a command in every matching repository
+    Foreach(commands::foreach::ForeachArgs),
     /// Manage repo classification rules
     Classify(commands::classify::ClassifyArgs),
+    /// Hide a repository from default listings
+    Archive(commands::archive::ArchiveArgs),
+    /// Restore an archived repository to default listings
+    Unarchive(commands::archive::ArchiveArgs),
+    /// Export clone/fork relationships as a Graphviz DOT graph
+    Graph(commands::graph::GraphArgs),
+    /// Remove repos from the index by classification
+    Prune(commands::prune::PruneArgs),
+    /// Remove a repository from the index (files are untouched)
+    Forget(commands::forget::ForgetArgs),
+    /// Let scans index a path again after `forget --permanent`
+    Unignore(commands::unignore::UnignoreArgs),
+    /// Attach a free-form note to a repository
+    Note(commands::note::NoteArgs),
+    /// Add a tag to (or --remove it from) every repository matching a filter
+    Tag(commands::tag::TagArgs),
     /// Show current configuration
-    Config,
+    Config(commands::config::ConfigArgs),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
@@ -47,18 +119,212 @@ pub enum OutputFormat {
     Json,
     Paths,
     PathsNull,
+    /// Stable tab-separated fields for scripts (see `output::porcelain_line`)
+    Porcelain,
+    /// Comma-separated values with a header row, for spreadsheets (see `output::CSV_HEADER`)
+    Csv,
+}
+
+/// How much a command says besides its result: progress, warnings and
+/// tracing go to stderr and scale with this, the result itself doesn't
+/// (except under `-q` for listings, which then only set the exit code).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn is_quiet(self) -> bool {
+        self == Verbosity::Quiet
+    }
+
+    pub fn is_verbose(self) -> bool {
+        self == Verbosity::Verbose
+    }
+}
+
+impl Cli {
+    pub fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
+
+/// Exit code when a listing matched no repos (the grep convention).
+pub const EXIT_NO_MATCH: i32 = 1;
+/// Exit code when a command failed outright.
+pub const EXIT_ERROR: i32 = 2;
+
+/// How a command finished; `main` maps this to the process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    /// The command ran fine but nothing matched the filters.
+    NoMatch,
+}
+
+impl Outcome {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::Success => 0,
+            Outcome::NoMatch => EXIT_NO_MATCH,
+        }
+    }
+}
+
+/// Apply `--index`/`--local` before anything opens the index. `--local`
+/// finds the nearest project index, except under `init`, which creates
+/// one in the current directory.
+pub fn select_index(cli: &Cli) -> anyhow::Result<()> {
+    let location = if let Some(ref path) = cli.index {
+        config::IndexLocation::File(std::path::absolute(path)?)
+    } else if cli.local {
+        let cwd = std::env::current_dir()?;
+        let root = if matches!(cli.command, Some(Commands::Init)) {
+            cwd
+        } else {
+            config::find_project_root(&cwd).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no {}/index.db in {} or any parent (create one with `kissa init --local`)",
+                    config::LOCAL_INDEX_DIR,
+                    cwd.display()
+                )
+            })?
+        };
+        config::IndexLocation::Project(root)
+    } else {
+        return Ok(());
+    };
+    config::set_index_location(location);
+    Ok(())
+}
+
+/// Open the index, scoped to `--root` when given. Relative roots resolve
+/// against the current directory, so `--root .` means "this workspace".
+pub fn open_index(root: Option<&Path>) -> anyhow::Result<Index> {
+    let root = root
+        .map(|r| {
+            std::fs::canonicalize(r)
+                .map(kissa::core::scanner::strip_verbatim)
+                .map_err(|e| anyhow::anyhow!("invalid --root {}: {}", r.display(), e))
+        })
+        .transpose()?;
+    Ok(Index::open(&config::index_path())?.with_root(root))
+}
+
+/// Clap value parser for ages like `7d`, `3mo` or `1y` (see
+/// `core::time::parse_duration`). A bare number still means days. Ages
+/// reaching back past the earliest representable date are rejected, so
+/// callers can subtract them from now.
+pub(crate) fn parse_age(value: &str) -> Result<chrono::Duration, String> {
+    let age = match value.trim().parse::<i64>() {
+        Ok(days) if days >= 0 => chrono::Duration::try_days(days),
+        _ => kissa::core::time::parse_duration(value),
+    }
+    .ok_or_else(|| {
+        format!(
+            "invalid age '{}': use e.g. 45m, 12h, 7d, 2w, 3mo, 1y",
+            value
+        )
+    })?;
+    if chrono::Utc::now().checked_sub_signed(age).is_none() {
+        return Err(format!("age '{}' is out of range", value));
+    }
+    Ok(age)
 }
 
 /// Dispatch a CLI command.
-pub fn run(cli: Cli) -> anyhow::Result<()> {
-    match cli.command {
-        Some(Commands::Scan(args)) => commands::scan::run(args, cli.format),
-        Some(Commands::List(args)) => commands::list::run(args, cli.format),
-        Some(Commands::Status(args)) => commands::status::run(args, cli.format),
-        Some(Commands::Info(args)) => commands::info::run(args, cli.format),
-        Some(Commands::Freshness) => commands::freshness::run(cli.format),
-        Some(Commands::Classify(args)) => commands::classify::run(args, cli.format),
-        Some(Commands::Config) => commands::config::run(cli.format),
+pub fn run(cli: Cli) -> anyhow::Result<Outcome> {
+    // A broken config is reported by the command that needs it, not here
+    let verbosity = cli.verbosity();
+    let display_cfg = config::load_config()
+        .map(|cfg| cfg.display)
+        .unwrap_or_default();
+    display::init_hyperlinks(display_cfg.hyperlinks);
+    display::init_stale_branch_warn(display_cfg.stale_branch_warn);
+    display::init_gradient(display_cfg.gradient);
+    display::init_nerd_fonts(display_cfg.nerd_fonts);
+
+    let result = match cli.command {
+        Some(Commands::Init) => commands::init::run(cli.format),
+        Some(Commands::Scan(args)) => {
+            if cli.root.is_some() {
+                anyhow::bail!(
+                    "--root does not apply to kissa scan; use --roots to pick what to walk"
+                );
+            }
+            commands::scan::run(args, cli.format, verbosity)
+        }
+        Some(Commands::List(args)) => {
+            return commands::list::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Search(args)) => {
+            return commands::search::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Status(args)) => {
+            return commands::status::run(args, cli.root.as_deref(), cli.format);
+        }
+        Some(Commands::Here) => return commands::here::run(cli.root.as_deref(), cli.format),
+        Some(Commands::Diff(args)) => commands::diff::run(args, cli.root.as_deref(), cli.format),
+        Some(Commands::History(args)) => {
+            commands::history::run(args, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Enrich(args)) => {
+            return commands::enrich::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Info(args)) => commands::info::run(args, cli.root.as_deref(), cli.format),
+        Some(Commands::Authors(args)) => {
+            commands::authors::run(args, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::GrepLog(args)) => {
+            return commands::grep_log::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Tui(args)) => commands::tui::run(args, cli.root.as_deref()),
+        Some(Commands::Freshness) => commands::freshness::run(cli.root.as_deref(), cli.format),
+        Some(Commands::RefreshFreshness) => {
+            commands::refresh_freshness::run(cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Metrics) => commands::metrics::run(cli.root.as_deref()),
+        Some(Commands::Dump) => commands::dump::run(cli.root.as_deref()),
+        Some(Commands::Managed) => return commands::managed::run(cli.root.as_deref(), cli.format),
+        Some(Commands::Values(args)) => {
+            return commands::values::run(args, cli.root.as_deref(), cli.format);
+        }
+        Some(Commands::Foreach(args)) => {
+            return commands::foreach::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Classify(args)) => {
+            commands::classify::run(args, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Archive(args)) => {
+            commands::archive::run(args, true, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Unarchive(args)) => {
+            commands::archive::run(args, false, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Graph(args)) => commands::graph::run(args, cli.root.as_deref(), cli.format),
+        Some(Commands::Prune(args)) => commands::prune::run(args, cli.root.as_deref(), cli.format),
+        Some(Commands::Forget(args)) => {
+            commands::forget::run(args, cli.root.as_deref(), cli.format)
+        }
+        Some(Commands::Unignore(args)) => {
+            if cli.root.is_some() {
+                anyhow::bail!("--root does not apply to kissa unignore, which takes a path");
+            }
+            commands::unignore::run(args, cli.format)
+        }
+        Some(Commands::Note(args)) => commands::note::run(args, cli.root.as_deref(), cli.format),
+        Some(Commands::Tag(args)) => {
+            return commands::tag::run(args, cli.root.as_deref(), cli.format, verbosity);
+        }
+        Some(Commands::Config(args)) => commands::config::run(args, cli.format),
         None => {
             // No subcommand — print help
             use clap::CommandFactory;
@@ -66,5 +332,19 @@ pub fn run(cli: Cli) -> anyhow::Result<()> {
             println!();
             Ok(())
         }
+    };
+    result.map(|()| Outcome::Success)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_age_rejects_ages_before_the_earliest_date() {
+        assert_eq!(parse_age("30"), Ok(chrono::Duration::days(30)));
+        assert_eq!(parse_age("2w"), Ok(chrono::Duration::weeks(2)));
+        assert!(parse_age("99999999d").is_err());
+        assert!(parse_age("-3").is_err());
     }
 }
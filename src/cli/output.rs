@@ -1,7 +1,13 @@
+use std::cmp::Ordering;
 use std::io::Write;
+use std::path::Path;
+
+use owo_colors::OwoColorize;
+use serde::Serialize;
 
-use kissa::core::repo::Repo;
 use crate::cli::OutputFormat;
+use kissa::core::git_ops;
+use kissa::core::repo::{Freshness, Repo};
 
 /// Write repos in the requested output format.
 pub fn output_repos(
@@ -21,7 +27,18 @@ pub fn output_repos(
         }
         OutputFormat::PathsNull => {
             for repo in repos {
-                write!(writer, "{}\0", repo.path.display())?;
+                write_path_null(writer, &repo.path)?;
+            }
+        }
+        OutputFormat::Porcelain => {
+            for repo in repos {
+                writeln!(writer, "{}", porcelain_line(repo))?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+            for repo in repos {
+                writeln!(writer, "{}", csv_record(repo))?;
             }
         }
         OutputFormat::Human => {
@@ -32,3 +49,692 @@ pub fn output_repos(
     }
     Ok(())
 }
+
+/// Key for `list --group-by`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// Org of the primary remote
+    Org,
+    Ownership,
+    Freshness,
+    ManagedBy,
+    Project,
+}
+
+/// Group label for repos with no value for the key.
+const NO_GROUP: &str = "(none)";
+
+impl GroupBy {
+    fn key(self, repo: &Repo, primary_remote: &str) -> Option<String> {
+        match self {
+            GroupBy::Org => git_ops::primary_remote(&repo.remotes, primary_remote)
+                .and_then(|r| git_ops::parse_remote_org(&r.url))
+                .map(|info| info.org),
+            GroupBy::Ownership => repo.ownership.as_ref().map(|o| o.label()),
+            GroupBy::Freshness => Some(repo.freshness.label().to_string()),
+            GroupBy::ManagedBy => repo.managed_by.clone(),
+            GroupBy::Project => repo.project.clone(),
+        }
+    }
+}
+
+/// Partition repos by `by`, keeping their order within each group. Groups
+/// are sorted by name, freshness by tier; repos without a value come last
+/// under "(none)".
+pub fn group_repos<'a>(
+    repos: &'a [Repo],
+    by: GroupBy,
+    primary_remote: &str,
+) -> Vec<(String, Vec<&'a Repo>)> {
+    let mut groups: Vec<(Option<String>, u8, Vec<&Repo>)> = Vec::new();
+    for repo in repos {
+        let key = by.key(repo, primary_remote);
+        match groups.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, members)) => members.push(repo),
+            None => groups.push((key, repo.freshness.tier(), vec![repo])),
+        }
+    }
+    groups.sort_by(|(a, a_tier, _), (b, b_tier, _)| {
+        let tiers = if by == GroupBy::Freshness {
+            a_tier.cmp(b_tier)
+        } else {
+            Ordering::Equal
+        };
+        a.is_none()
+            .cmp(&b.is_none())
+            .then(tiers)
+            .then_with(|| a.cmp(b))
+    });
+    groups
+        .into_iter()
+        .map(|(key, _, members)| (key.unwrap_or_else(|| NO_GROUP.into()), members))
+        .collect()
+}
+
+/// Write grouped repos: headers with counts for humans, an object of
+/// group -> repos for JSON, and the repos in group order otherwise.
+pub fn output_grouped(
+    groups: &[(String, Vec<&Repo>)],
+    format: OutputFormat,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let object: serde_json::Map<String, serde_json::Value> = groups
+                .iter()
+                .map(|(key, members)| Ok((key.clone(), serde_json::to_value(members)?)))
+                .collect::<serde_json::Result<_>>()?;
+            serde_json::to_writer_pretty(&mut *writer, &object)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Human => {
+            for (i, (key, members)) in groups.iter().enumerate() {
+                if i > 0 {
+                    writeln!(writer)?;
+                }
+                writeln!(
+                    writer,
+                    "  {} {}",
+                    key.bold(),
+                    format!("({} repos)", members.len()).dimmed(),
+                )?;
+                for repo in members {
+                    writeln!(writer, "  {}", super::display::render_repo_line(repo))?;
+                }
+            }
+        }
+        _ => {
+            let repos: Vec<Repo> = groups
+                .iter()
+                .flat_map(|(_, members)| members.iter().map(|r| (*r).clone()))
+                .collect();
+            output_repos(&repos, format, writer)?;
+        }
+    }
+    Ok(())
+}
+
+/// One (repo, remote) pair for `list --flatten-remotes`, with the repo's
+/// identity copied onto each row.
+#[derive(Debug, Serialize)]
+pub struct RemoteRow<'a> {
+    pub repo: &'a str,
+    pub path: &'a Path,
+    pub freshness: Freshness,
+    pub remote: &'a str,
+    pub url: &'a str,
+    pub push_url: Option<&'a str>,
+    /// Host parsed from the URL; None for local paths and unparseable URLs.
+    pub host: Option<String>,
+    pub org: Option<String>,
+}
+
+/// One row per remote, in repo order. Repos without remotes yield no rows.
+pub fn flatten_remotes(repos: &[Repo]) -> Vec<RemoteRow<'_>> {
+    repos
+        .iter()
+        .flat_map(|repo| {
+            repo.remotes.iter().map(move |remote| {
+                let info = git_ops::parse_remote_org(&remote.url);
+                RemoteRow {
+                    repo: &repo.name,
+                    path: &repo.path,
+                    freshness: repo.freshness,
+                    remote: &remote.name,
+                    url: &remote.url,
+                    push_url: remote.push_url.as_deref(),
+                    host: info.as_ref().map(|i| i.platform.clone()),
+                    org: info.map(|i| i.org),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Write repos one row per remote for JSON and porcelain. Other formats
+/// are repo-centric and keep the usual nested output.
+///
+/// Porcelain columns: `repo \t path \t remote \t url \t host \t org`, with
+/// `-` for an unknown host or org.
+pub fn output_flattened(
+    repos: &[Repo],
+    format: OutputFormat,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let rows = flatten_remotes(repos);
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &rows)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Porcelain => {
+            for row in &rows {
+                let path = row.path.display().to_string();
+                let fields = [
+                    row.repo,
+                    &path,
+                    row.remote,
+                    row.url,
+                    row.host.as_deref().unwrap_or("-"),
+                    row.org.as_deref().unwrap_or("-"),
+                ];
+                let line = fields
+                    .iter()
+                    .map(|f| porcelain_field(f))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{}", line.join("\t"))?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(writer, "repo,path,remote,url,host,org")?;
+            for row in &rows {
+                let path = row.path.display().to_string();
+                let fields = [
+                    row.repo,
+                    &path,
+                    row.remote,
+                    row.url,
+                    row.host.as_deref().unwrap_or(""),
+                    row.org.as_deref().unwrap_or(""),
+                ];
+                writeln!(writer, "{}", csv_line(&fields))?;
+            }
+        }
+        _ => output_repos(repos, format, writer)?,
+    }
+    Ok(())
+}
+
+/// Write `path` NUL-terminated for `xargs -0`. The raw OS bytes go out
+/// unchanged, so spaces, newlines and non-UTF-8 names survive the round trip.
+pub fn write_path_null(writer: &mut dyn Write, path: &Path) -> std::io::Result<()> {
+    writer.write_all(path.as_os_str().as_encoded_bytes())?;
+    writer.write_all(b"\0")
+}
+
+/// Field names `--only` accepts: the top-level keys of a serialized `Repo`.
+pub const REPO_FIELDS: &[&str] = &[
+    "id",
+    "name",
+    "path",
+    "state",
+    "remotes",
+    "default_branch",
+    "current_branch",
+    "default_branch_drift",
+    "head_signed",
+    "last_fetched",
+    "commits_last_30d",
+    "scan_snapshot",
+    "upstream",
+    "branch_count",
+    "stale_branch_count",
+    "branches_truncated",
+    "dirty",
+    "staged",
+    "untracked",
+    "status_checked",
+    "ahead",
+    "behind",
+    "last_commit",
+    "last_commit_offset",
+    "is_empty",
+    "nested_in",
+    "contains_nested",
+    "last_verified",
+    "first_seen",
+    "freshness",
+    "category",
+    "origin_org",
+    "ownership",
+    "intention",
+    "managed_by",
+    "tags",
+    "project",
+    "role",
+    "note",
+];
+
+/// Validate `--only` field names, suggesting the closest match for typos.
+pub fn parse_fields(spec: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut fields = Vec::new();
+    for field in spec.iter().map(|f| f.trim()).filter(|f| !f.is_empty()) {
+        if !REPO_FIELDS.contains(&field) {
+            let hint = REPO_FIELDS
+                .iter()
+                .map(|known| (edit_distance(field, known), known))
+                .filter(|(d, _)| *d <= 2)
+                .min()
+                .map(|(_, known)| format!(" (did you mean '{}'?)", known))
+                .unwrap_or_default();
+            anyhow::bail!(
+                "unknown field '{}'{}; valid fields: {}",
+                field,
+                hint,
+                REPO_FIELDS.join(", ")
+            );
+        }
+        fields.push(field.to_string());
+    }
+    if fields.is_empty() {
+        anyhow::bail!("--only needs at least one field");
+    }
+    Ok(fields)
+}
+
+/// Write only the selected fields of each repo: JSON objects, or
+/// tab-separated values in the given order for porcelain.
+pub fn output_projected(
+    repos: &[Repo],
+    fields: &[String],
+    format: OutputFormat,
+    writer: &mut dyn Write,
+) -> anyhow::Result<()> {
+    let rows = repos
+        .iter()
+        .map(|repo| project(repo, fields))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *writer, &rows)?;
+            writeln!(writer)?;
+        }
+        OutputFormat::Porcelain => {
+            for row in &rows {
+                let line = fields
+                    .iter()
+                    .map(|f| porcelain_field(&porcelain_value(&row[f.as_str()])))
+                    .collect::<Vec<_>>()
+                    .join("\t");
+                writeln!(writer, "{}", line)?;
+            }
+        }
+        OutputFormat::Csv => {
+            writeln!(writer, "{}", csv_line(fields))?;
+            for row in &rows {
+                let values: Vec<String> =
+                    fields.iter().map(|f| csv_value(&row[f.as_str()])).collect();
+                writeln!(writer, "{}", csv_line(&values))?;
+            }
+        }
+        _ => anyhow::bail!("--only works with --format json, porcelain or csv"),
+    }
+    Ok(())
+}
+
+/// Pick `fields` out of a serialized repo.
+fn project(
+    repo: &Repo,
+    fields: &[String],
+) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+    let serde_json::Value::Object(mut all) = serde_json::to_value(repo)? else {
+        anyhow::bail!("repo did not serialize to an object");
+    };
+    Ok(fields
+        .iter()
+        .map(|f| (f.clone(), all.remove(f).unwrap_or(serde_json::Value::Null)))
+        .collect())
+}
+
+/// Porcelain spelling of a JSON value: booleans as `0`/`1`, null as `-`,
+/// lists comma-joined, nested objects as compact JSON.
+fn porcelain_value(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+    match value {
+        Value::Null => "-".into(),
+        Value::Bool(b) => u8::from(*b).to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(porcelain_value)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Levenshtein distance, for "did you mean" hints.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur.push((prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1));
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Render a repo as one porcelain line.
+///
+/// The field order is a stable contract for scripts and must not change;
+/// new fields may only be appended:
+///
+/// `name \t path \t freshness \t dirty \t ahead \t behind \t branch`
+///
+/// `dirty` is `0` or `1`, a detached or bare HEAD prints `-` as the branch,
+/// and tabs or newlines inside a field are replaced with spaces.
+pub fn porcelain_line(repo: &Repo) -> String {
+    let fields = [
+        repo.name.clone(),
+        repo.path.display().to_string(),
+        repo.freshness.label().to_string(),
+        u8::from(repo.dirty).to_string(),
+        repo.ahead.to_string(),
+        repo.behind.to_string(),
+        repo.current_branch.clone().unwrap_or_else(|| "-".into()),
+    ];
+    fields
+        .iter()
+        .map(|f| porcelain_field(f))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+/// Make a value safe to embed in a tab-separated porcelain line.
+pub fn porcelain_field(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Columns of `--format csv`. Like porcelain fields, they may only be
+/// appended to, never reordered.
+pub const CSV_HEADER: &[&str] = &[
+    "name",
+    "path",
+    "freshness",
+    "dirty",
+    "staged",
+    "ahead",
+    "behind",
+    "branch_count",
+    "last_commit",
+    "ownership",
+    "managed_by",
+    "remotes",
+    "tags",
+];
+
+/// Render a repo as one CSV row under `CSV_HEADER`. Booleans are
+/// `true`/`false`, missing values are empty, remotes (`name=url`) and tags
+/// are joined with `;`.
+pub fn csv_record(repo: &Repo) -> String {
+    let remotes: Vec<String> = repo
+        .remotes
+        .iter()
+        .map(|r| format!("{}={}", r.name, r.url))
+        .collect();
+    let fields = [
+        repo.name.clone(),
+        repo.path.display().to_string(),
+        repo.freshness.label().to_string(),
+        repo.dirty.to_string(),
+        repo.staged.to_string(),
+        repo.ahead.to_string(),
+        repo.behind.to_string(),
+        repo.branch_count.to_string(),
+        repo.last_commit.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        repo.ownership
+            .as_ref()
+            .map(|o| o.label())
+            .unwrap_or_default(),
+        repo.managed_by.clone().unwrap_or_default(),
+        remotes.join(";"),
+        repo.tags.join(";"),
+    ];
+    csv_line(&fields)
+}
+
+/// Join values into one CSV line, quoting as RFC 4180 requires.
+pub fn csv_line(fields: &[impl AsRef<str>]) -> String {
+    fields
+        .iter()
+        .map(|f| csv_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Quote a CSV value if it holds a comma, quote or line break, doubling
+/// any quotes inside.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// A projected JSON value as a CSV cell, following `csv_record`'s conventions.
+fn csv_value(value: &serde_json::Value) -> String {
+    use serde_json::Value;
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(csv_value).collect::<Vec<_>>().join(";"),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kissa::core::repo::Freshness;
+    use std::path::PathBuf;
+
+    fn make_repo() -> Repo {
+        Repo {
+            id: 1,
+            current_branch: None,
+            dirty: true,
+            ahead: 2,
+            behind: 1,
+            last_commit: None,
+            last_verified: None,
+            freshness: Freshness::Stale,
+            ..crate::common::repo("api\tgateway", "/code/api-gateway")
+        }
+    }
+
+    #[test]
+    fn repo_fields_match_serialized_keys() {
+        let value = serde_json::to_value(make_repo()).unwrap();
+        let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        let mut known: Vec<_> = REPO_FIELDS.iter().map(|f| f.to_string()).collect();
+        keys.sort();
+        known.sort();
+        assert_eq!(keys, known);
+    }
+
+    #[test]
+    fn only_projects_fields_in_order() {
+        let fields =
+            parse_fields(&["path".into(), "dirty".into(), "current_branch".into()]).unwrap();
+        let mut out = Vec::new();
+        output_projected(&[make_repo()], &fields, OutputFormat::Porcelain, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "/code/api-gateway\t1\t-\n");
+
+        let mut out = Vec::new();
+        output_projected(&[make_repo()], &fields, OutputFormat::Json, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json[0].as_object().unwrap().len(), 3);
+        assert_eq!(json[0]["dirty"], true);
+    }
+
+    #[test]
+    fn only_rejects_typos_with_suggestion() {
+        let err = parse_fields(&["name".into(), "pth".into()])
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("unknown field 'pth' (did you mean 'path'?)"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn groups_by_org_with_missing_last() {
+        use kissa::core::repo::Remote;
+        let with_remote = |name: &str, url: &str| {
+            let mut repo = make_repo();
+            repo.name = name.into();
+            repo.remotes = vec![Remote {
+                name: "origin".into(),
+                url: url.into(),
+                push_url: None,
+            }];
+            repo
+        };
+        let repos = vec![
+            with_remote("b", "git@github.com:vandelay/b.git"),
+            make_repo(),
+            with_remote("a", "git@github.com:initech/a.git"),
+            with_remote("c", "https://github.com/vandelay/c"),
+        ];
+
+        let groups = group_repos(&repos, GroupBy::Org, "origin");
+        let shape: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(k, m)| (k.as_str(), m.iter().map(|r| r.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            shape,
+            vec![
+                ("initech", vec!["a"]),
+                ("vandelay", vec!["b", "c"]),
+                ("(none)", vec!["api\tgateway"]),
+            ]
+        );
+
+        let mut out = Vec::new();
+        output_grouped(&groups, OutputFormat::Json, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["vandelay"].as_array().unwrap().len(), 2);
+        assert_eq!(json["vandelay"][1]["name"], "c");
+    }
+
+    #[test]
+    fn freshness_groups_follow_tier_order() {
+        let mut active = make_repo();
+        active.freshness = Freshness::Active;
+        let mut ancient = make_repo();
+        ancient.freshness = Freshness::Ancient;
+        let repos = vec![ancient, make_repo(), active];
+        let keys: Vec<String> = group_repos(&repos, GroupBy::Freshness, "origin")
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, vec!["active", "stale", "ancient"]);
+    }
+
+    #[test]
+    fn flatten_remotes_emits_one_row_per_remote() {
+        use kissa::core::repo::Remote;
+        let mut forked = make_repo();
+        forked.remotes = vec![
+            Remote {
+                name: "origin".into(),
+                url: "git@github.com:me/api.git".into(),
+                push_url: None,
+            },
+            Remote {
+                name: "upstream".into(),
+                url: "https://gitlab.com/group/sub/api".into(),
+                push_url: Some("no_push".into()),
+            },
+            Remote {
+                name: "local".into(),
+                url: "/srv/git/api.git".into(),
+                push_url: None,
+            },
+        ];
+        let repos = vec![forked, make_repo()];
+
+        let mut out = Vec::new();
+        output_flattened(&repos, OutputFormat::Json, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let rows = json.as_array().unwrap();
+        assert_eq!(rows.len(), 3); // the orphan contributes nothing
+        assert_eq!(rows[1]["repo"], "api\tgateway");
+        assert_eq!(rows[1]["remote"], "upstream");
+        assert_eq!(rows[1]["host"], "gitlab.com");
+        assert_eq!(rows[1]["org"], "group/sub");
+        assert_eq!(rows[1]["push_url"], "no_push");
+        assert_eq!(rows[1]["freshness"], "stale");
+
+        let mut out = Vec::new();
+        output_flattened(&repos, OutputFormat::Porcelain, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec![
+                "api gateway\t/code/api-gateway\torigin\tgit@github.com:me/api.git\tgithub.com\tme",
+                "api gateway\t/code/api-gateway\tupstream\thttps://gitlab.com/group/sub/api\tgitlab.com\tgroup/sub",
+                "api gateway\t/code/api-gateway\tlocal\t/srv/git/api.git\t-\t-",
+            ]
+        );
+    }
+
+    #[test]
+    fn paths_null_keeps_awkward_paths_intact() {
+        let mut spaced = make_repo();
+        spaced.path = PathBuf::from("/code/my repo");
+        let mut multiline = make_repo();
+        multiline.path = PathBuf::from("/code/odd\nname");
+        let mut out = Vec::new();
+        output_repos(&[spaced, multiline], OutputFormat::PathsNull, &mut out).unwrap();
+        assert_eq!(out, b"/code/my repo\0/code/odd\nname\0");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn paths_null_passes_non_utf8_bytes_through() {
+        use std::os::unix::ffi::OsStrExt;
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/code/caf\xe9"));
+        let mut out = Vec::new();
+        write_path_null(&mut out, path).unwrap();
+        assert_eq!(out, b"/code/caf\xe9\0");
+    }
+
+    #[test]
+    fn porcelain_field_order_is_stable() {
+        assert_eq!(
+            porcelain_line(&make_repo()),
+            "api gateway\t/code/api-gateway\tstale\t1\t2\t1\t-"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_only_when_needed() {
+        use kissa::core::repo::Remote;
+        let mut repo = make_repo();
+        repo.name = "api, \"v2\"".into();
+        repo.remotes = vec![
+            Remote {
+                name: "origin".into(),
+                url: "git@github.com:me/api.git".into(),
+                push_url: None,
+            },
+            Remote {
+                name: "up".into(),
+                url: "/srv/api.git".into(),
+                push_url: None,
+            },
+        ];
+        repo.tags = vec!["work".into(), "infra".into()];
+
+        let mut out = Vec::new();
+        output_repos(&[repo], OutputFormat::Csv, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "\"api, \"\"v2\"\"\",/code/api-gateway,stale,true,false,2,1,1,,,,\
+             origin=git@github.com:me/api.git;up=/srv/api.git,work;infra"
+        );
+        assert_eq!(csv_line(&["a\nb", "plain"]), "\"a\nb\",plain");
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{BufRead, IsTerminal, Write};
+
+use kissa::config;
+use kissa::core::repo::Repo;
+
+/// Gate a destructive operation on `repo` behind `safety.always_confirm_destructive`.
+///
+/// `--yes` (or the setting being off) confirms without asking. Otherwise the
+/// user is prompted on a terminal; with no terminal to ask on, the operation
+/// is refused rather than assumed. Returns whether to go ahead.
+pub fn confirm(operation: &str, repo: &Repo, yes: bool) -> anyhow::Result<bool> {
+    let cfg = config::load_config()?;
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    decide(
+        operation,
+        &repo.name,
+        yes || !cfg.safety.always_confirm_destructive,
+        interactive,
+        || {
+            eprint!(
+                "{} {} ({})? [y/N] ",
+                operation,
+                repo.name,
+                repo.path.display()
+            );
+            std::io::stderr().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)?;
+            Ok(answer)
+        },
+    )
+}
+
+/// [`confirm`] for an operation on many repos at once, such as pruning: the
+/// prompt gives the count and the first few names. Nothing to act on needs
+/// no confirmation.
+pub fn confirm_many(operation: &str, repos: &[Repo], yes: bool) -> anyhow::Result<bool> {
+    if repos.is_empty() {
+        return Ok(true);
+    }
+    let cfg = config::load_config()?;
+    let interactive = std::io::stdin().is_terminal() && std::io::stderr().is_terminal();
+    decide(
+        operation,
+        &format!("{} repos", repos.len()),
+        yes || !cfg.safety.always_confirm_destructive,
+        interactive,
+        || {
+            eprint!(
+                "{} {} repos ({})? [y/N] ",
+                operation,
+                repos.len(),
+                name_list(repos)
+            );
+            std::io::stderr().flush()?;
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)?;
+            Ok(answer)
+        },
+    )
+}
+
+/// The first few repo names, then how many more there are.
+fn name_list(repos: &[Repo]) -> String {
+    const SHOWN: usize = 5;
+    let names: Vec<&str> = repos.iter().take(SHOWN).map(|r| r.name.as_str()).collect();
+    let mut list = names.join(", ");
+    if repos.len() > SHOWN {
+        list.push_str(&format!(" and {} more", repos.len() - SHOWN));
+    }
+    list
+}
+
+fn decide(
+    operation: &str,
+    name: &str,
+    preconfirmed: bool,
+    interactive: bool,
+    ask: impl FnOnce() -> std::io::Result<String>,
+) -> anyhow::Result<bool> {
+    if preconfirmed {
+        return Ok(true);
+    }
+    if !interactive {
+        anyhow::bail!(
+            "refusing to {} {} without confirmation: not a terminal (pass --yes)",
+            operation,
+            name
+        );
+    }
+    let answer = ask()?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmation_rules() {
+        let never = || -> std::io::Result<String> { panic!("should not prompt") };
+        assert!(decide("forget", "r", true, false, never).unwrap());
+
+        let err = decide("forget", "r", false, false, never).unwrap_err();
+        assert!(err.to_string().contains("--yes"));
+
+        assert!(decide("forget", "r", false, true, || Ok("Y\n".into())).unwrap());
+        assert!(!decide("forget", "r", false, true, || Ok("\n".into())).unwrap());
+        assert!(!decide("forget", "r", false, true, || Ok("nope\n".into())).unwrap());
+    }
+
+    #[test]
+    fn name_list_caps_the_names_shown() {
+        let repos: Vec<Repo> = (1..=7)
+            .map(|i| crate::common::repo(&format!("r{}", i), format!("/code/r{}", i)))
+            .collect();
+        assert_eq!(name_list(&repos[..2]), "r1, r2");
+        assert_eq!(name_list(&repos), "r1, r2, r3, r4, r5 and 2 more");
+    }
+}
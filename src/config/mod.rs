@@ -1,25 +1,127 @@
 pub mod types;
 
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
+use crate::core::permissions::DifficultyLevel;
 use crate::error::{KissaError, Result};
-use types::KissaConfig;
+use types::{KissaConfig, ScanConfig};
+
+/// Directory under a project root that holds a project-local index.
+pub const LOCAL_INDEX_DIR: &str = ".kissa";
+
+/// Where this process keeps its index, when not in the global data dir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexLocation {
+    /// An explicit database file.
+    File(PathBuf),
+    /// `.kissa/index.db` under this project root.
+    Project(PathBuf),
+}
+
+static INDEX_LOCATION: OnceLock<IndexLocation> = OnceLock::new();
 
 /// Load config from XDG path, merging defaults.
 /// If no config file exists, returns sensible defaults (first-run experience).
 pub fn load_config() -> Result<KissaConfig> {
-    load_config_from(config_dir().join("config.toml"))
+    load_config_from(config_path())
+}
+
+/// Path of the user's config file: `config.toml`, or `config.json` when
+/// only that exists. New configs are written as TOML.
+pub fn config_path() -> PathBuf {
+    config_file_in(&config_dir())
+}
+
+/// Pick the config file in `dir`; TOML wins when both formats are present.
+fn config_file_in(dir: &Path) -> PathBuf {
+    let toml = dir.join("config.toml");
+    let json = dir.join("config.json");
+    if !toml.exists() && json.exists() {
+        json
+    } else {
+        toml
+    }
 }
 
-/// Load config from a specific path. Testable entry point.
+/// Whether `path` names a JSON config rather than TOML.
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Load config from a specific path, parsed as JSON for a `.json` file and
+/// as TOML otherwise. Testable entry point.
 pub fn load_config_from(path: impl AsRef<Path>) -> Result<KissaConfig> {
+    let mut config = load_config_raw(path.as_ref())?;
+    expand_config_paths(&mut config);
+    Ok(config)
+}
+
+/// Rewrite a config file in canonical form: every option present with its
+/// current or default value, consistently formatted. Paths are kept as
+/// written (`~` and `$VAR` are not expanded). An existing file is copied to
+/// `<name>.bak` first; the backup path is returned if one was made.
+pub fn normalize_config_file(path: impl AsRef<Path>) -> Result<Option<PathBuf>> {
     let path = path.as_ref();
+    let config = load_config_raw(path)?;
+    write_config_file(path, &config)
+}
+
+/// Add or replace a per-path difficulty override in a config file, rewriting
+/// it in canonical form like `normalize_config_file` (backup included).
+pub fn set_override_in_file(
+    path: impl AsRef<Path>,
+    pattern: &str,
+    level: DifficultyLevel,
+) -> Result<Option<PathBuf>> {
+    let path = path.as_ref();
+    let mut config = load_config_raw(path)?;
+    config.overrides.insert(pattern.to_string(), level);
+    write_config_file(path, &config)
+}
+
+/// Serialize `config` to `path` in the file's own format, copying any
+/// existing file to `<name>.bak` first. Returns the backup path if one was
+/// made.
+fn write_config_file(path: &Path, config: &KissaConfig) -> Result<Option<PathBuf>> {
+    let rendered = if is_json(path) {
+        serde_json::to_string_pretty(config)
+            .map(|json| json + "\n")
+            .map_err(|e| KissaError::Config(e.to_string()))?
+    } else {
+        toml::to_string_pretty(config).map_err(|e| KissaError::Config(e.to_string()))?
+    };
+
+    let backup = if path.exists() {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        let backup = path.with_file_name(name);
+        std::fs::copy(path, &backup).map_err(|e| {
+            KissaError::Config(format!("failed to back up {}: {}", path.display(), e))
+        })?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    let write = |e: std::io::Error| {
+        KissaError::Config(format!("failed to write {}: {}", path.display(), e))
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(write)?;
+    }
+    std::fs::write(path, rendered).map_err(write)?;
+    Ok(backup)
+}
+
+/// Parse a config file without expanding paths.
+fn load_config_raw(path: &Path) -> Result<KissaConfig> {
     match std::fs::read_to_string(path) {
-        Ok(contents) => {
-            let config: KissaConfig =
-                toml::from_str(&contents).map_err(|e| KissaError::Config(e.to_string()))?;
-            Ok(config)
+        Ok(contents) if is_json(path) => {
+            serde_json::from_str(&contents).map_err(|e| KissaError::Config(e.to_string()))
         }
+        Ok(contents) => toml::from_str(&contents).map_err(|e| KissaError::Config(e.to_string())),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             // First run — no config file yet, use defaults
             Ok(KissaConfig::default())
@@ -32,6 +134,74 @@ pub fn load_config_from(path: impl AsRef<Path>) -> Result<KissaConfig> {
     }
 }
 
+/// Expand `~`, `$VAR` and `${VAR}` in every configured filesystem path.
+fn expand_config_paths(config: &mut KissaConfig) {
+    let boundaries = &mut config.scan.boundaries;
+    for path in config
+        .scan
+        .roots
+        .iter_mut()
+        .chain(boundaries.allow_mounts.iter_mut())
+        .chain(boundaries.block_mounts.iter_mut())
+    {
+        *path = expand_path(path);
+    }
+}
+
+/// Expand a leading `~` and any `$VAR` / `${VAR}` references in a path.
+/// Unset variables are left as written so the path fails visibly rather than
+/// collapsing to something unintended like `/src`.
+pub fn expand_path(path: &Path) -> PathBuf {
+    let expanded = expand_vars(&path.to_string_lossy(), |name| std::env::var(name).ok());
+    match expanded.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', std::path::MAIN_SEPARATOR]) => {
+            match dirs::home_dir() {
+                Some(home) => PathBuf::from(format!("{}{}", home.display(), rest)),
+                None => PathBuf::from(expanded),
+            }
+        }
+        _ => PathBuf::from(expanded),
+    }
+}
+
+/// Substitute `$VAR` and `${VAR}` using `lookup`.
+fn expand_vars(input: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        let original = &rest[pos..pos + 1 + consumed];
+        match (name.is_empty(), lookup(name)) {
+            (false, Some(value)) => out.push_str(&value),
+            _ if consumed == 0 => out.push('$'),
+            _ => out.push_str(original),
+        }
+        rest = &rest[pos + 1 + consumed..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// JSON Schema describing `config.toml`, for editor validation and completion.
+pub fn json_schema() -> schemars::Schema {
+    schemars::schema_for!(KissaConfig)
+}
+
 /// Return XDG config dir (~/.config/kissa/)
 pub fn config_dir() -> PathBuf {
     dirs::config_dir()
@@ -46,15 +216,78 @@ pub fn data_dir() -> PathBuf {
         .join("kissa")
 }
 
-/// Return the index database path
+/// Point `index_path()` somewhere other than the global data dir for the
+/// rest of the process. Call once at startup; later calls are ignored.
+pub fn set_index_location(location: IndexLocation) {
+    let _ = INDEX_LOCATION.set(location);
+}
+
+/// Return the index database path: the location chosen at startup, or
+/// `index.db` in the XDG data dir.
 pub fn index_path() -> PathBuf {
-    data_dir().join("index.db")
+    match INDEX_LOCATION.get() {
+        Some(IndexLocation::File(path)) => path.clone(),
+        Some(IndexLocation::Project(root)) => local_index_path(root),
+        None => data_dir().join("index.db"),
+    }
+}
+
+/// The project root when running against a project-local index.
+pub fn project_root() -> Option<&'static Path> {
+    match INDEX_LOCATION.get() {
+        Some(IndexLocation::Project(root)) => Some(root),
+        _ => None,
+    }
+}
+
+/// Where the project-local index for `root` lives.
+pub fn local_index_path(root: &Path) -> PathBuf {
+    root.join(LOCAL_INDEX_DIR).join("index.db")
+}
+
+/// The nearest directory at or above `start` holding a project-local index.
+pub fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| local_index_path(dir).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Roots a scan walks when none are given: the project root for a
+/// project-local index, otherwise `scan.roots`.
+pub fn default_scan_roots(scan: &ScanConfig) -> Vec<PathBuf> {
+    match project_root() {
+        Some(root) => vec![root.to_path_buf()],
+        None => scan.roots.clone(),
+    }
+}
+
+/// Settle the values `config` still leaves to run time, as this process
+/// would use them: scan roots (the project root under `--local`) and
+/// `scan.jobs = 0` (the core count). Two machines whose effective configs
+/// match behave the same however their files are written.
+pub fn resolve_effective(mut config: KissaConfig) -> KissaConfig {
+    config.scan.roots = default_scan_roots(&config.scan);
+    config.scan.jobs = crate::core::scanner::effective_jobs(config.scan.jobs);
+    config
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::permissions::DifficultyLevel;
+    #[test]
+    fn effective_config_settles_runtime_defaults() {
+        let mut config = KissaConfig::default();
+        config.scan.roots = vec![PathBuf::from("/code")];
+        let effective = resolve_effective(config.clone());
+        assert!(effective.scan.jobs >= 1);
+        assert_eq!(effective.scan.roots, config.scan.roots);
+
+        config.scan.jobs = 3;
+        assert_eq!(resolve_effective(config).scan.jobs, 3);
+    }
+
     #[test]
     fn missing_file_returns_defaults() {
         let config = load_config_from("/nonexistent/path/config.toml").unwrap();
@@ -160,6 +393,59 @@ max_plan_size = 100
         assert_eq!(config.safety.max_plan_size, 100);
     }
 
+    #[test]
+    fn json_config_matches_equivalent_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("config.toml");
+        let json_path = dir.path().join("config.json");
+        std::fs::write(
+            &toml_path,
+            r#"
+[scan]
+roots = ["/opt/repos"]
+max_depth = 4
+
+[defaults.mcp]
+difficulty = "fetch"
+
+[overrides]
+"/opt/repos/*" = "readonly"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &json_path,
+            r#"{
+  "scan": { "roots": ["/opt/repos"], "max_depth": 4 },
+  "defaults": { "mcp": { "difficulty": "fetch" } },
+  "overrides": { "/opt/repos/*": "readonly" }
+}"#,
+        )
+        .unwrap();
+
+        let from_toml = serde_json::to_value(load_config_from(&toml_path).unwrap()).unwrap();
+        let from_json = serde_json::to_value(load_config_from(&json_path).unwrap()).unwrap();
+        assert_eq!(from_json, from_toml);
+
+        // Normalizing keeps the file JSON
+        normalize_config_file(&json_path).unwrap();
+        let rewritten = std::fs::read_to_string(&json_path).unwrap();
+        let reparsed: KissaConfig = serde_json::from_str(&rewritten).unwrap();
+        assert_eq!(reparsed.scan.max_depth, 4);
+    }
+
+    #[test]
+    fn config_file_prefers_toml_over_json() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(config_file_in(dir.path()), dir.path().join("config.toml"));
+
+        std::fs::write(dir.path().join("config.json"), "{}").unwrap();
+        assert_eq!(config_file_in(dir.path()), dir.path().join("config.json"));
+
+        std::fs::write(dir.path().join("config.toml"), "").unwrap();
+        assert_eq!(config_file_in(dir.path()), dir.path().join("config.toml"));
+    }
+
     #[test]
     fn invalid_toml_returns_error() {
         let dir = tempfile::tempdir().unwrap();
@@ -191,6 +477,143 @@ difficulty = "yolo"
         assert!(result.is_err());
     }
 
+    #[test]
+    fn json_schema_covers_nested_config() {
+        let schema = serde_json::to_value(json_schema()).unwrap();
+        let text = schema.to_string();
+        assert_eq!(schema["title"], "kissa config");
+        assert!(schema["properties"]["overrides"].is_object());
+        assert!(schema["properties"]["classify"].is_object());
+        // serde rename of match_criteria is reflected in the schema
+        assert!(text.contains("\"match\""));
+        assert!(!text.contains("remote_url_regex"));
+        assert!(text.contains("readonly"));
+    }
+
+    #[test]
+    fn expand_vars_handles_both_forms() {
+        let lookup = |name: &str| match name {
+            "GOPATH" => Some("/home/me/go".to_string()),
+            "HOME" => Some("/home/me".to_string()),
+            _ => None,
+        };
+        assert_eq!(expand_vars("$GOPATH/src", lookup), "/home/me/go/src");
+        assert_eq!(expand_vars("${HOME}/code", lookup), "/home/me/code");
+        assert_eq!(
+            expand_vars("${HOME}x/$GOPATH", lookup),
+            "/home/mex//home/me/go"
+        );
+        // Unknown, empty and malformed references are kept verbatim
+        assert_eq!(expand_vars("$NOPE/a", lookup), "$NOPE/a");
+        assert_eq!(expand_vars("${NOPE}/a", lookup), "${NOPE}/a");
+        assert_eq!(expand_vars("cost$/a", lookup), "cost$/a");
+        assert_eq!(expand_vars("${HOME", lookup), "${HOME");
+        assert_eq!(expand_vars("/plain/path", lookup), "/plain/path");
+    }
+
+    #[test]
+    fn expand_path_handles_tilde() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_path(Path::new("~/code")), home.join("code"));
+        assert_eq!(expand_path(Path::new("~")), home);
+        assert_eq!(expand_path(Path::new("/opt/~x")), PathBuf::from("/opt/~x"));
+        assert_eq!(
+            expand_path(Path::new("~other/x")),
+            PathBuf::from("~other/x")
+        );
+    }
+
+    #[test]
+    fn loaded_roots_and_mounts_are_expanded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+[scan]
+roots = ["~/code", "/abs"]
+
+[scan.boundaries]
+allow_mounts = ["~/mnt"]
+block_mounts = ["~/slow"]
+"#,
+        )
+        .unwrap();
+
+        let home = dirs::home_dir().unwrap();
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(
+            config.scan.roots,
+            vec![home.join("code"), PathBuf::from("/abs")]
+        );
+        assert_eq!(config.scan.boundaries.allow_mounts, vec![home.join("mnt")]);
+        assert_eq!(config.scan.boundaries.block_mounts, vec![home.join("slow")]);
+    }
+
+    #[test]
+    fn normalize_fills_defaults_and_keeps_unexpanded_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = "[scan]\nroots = [\"~/code\"]\n";
+        std::fs::write(&path, original).unwrap();
+
+        let backup = normalize_config_file(&path).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), original);
+
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        assert!(rewritten.contains("\"~/code\""));
+        assert!(rewritten.contains("[defaults]"));
+        let raw: KissaConfig = toml::from_str(&rewritten).unwrap();
+        assert_eq!(raw.scan.roots, vec![PathBuf::from("~/code")]);
+    }
+
+    #[test]
+    fn set_override_persists_with_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        // No file yet: written from defaults, nothing to back up
+        assert!(
+            set_override_in_file(&path, "/code/a", DifficultyLevel::Fetch)
+                .unwrap()
+                .is_none()
+        );
+
+        let backup = set_override_in_file(&path, "/code/b/*", DifficultyLevel::Force)
+            .unwrap()
+            .unwrap();
+        let before: KissaConfig =
+            toml::from_str(&std::fs::read_to_string(backup).unwrap()).unwrap();
+        assert_eq!(before.overrides.len(), 1);
+
+        let after = load_config_from(&path).unwrap();
+        assert_eq!(
+            after.overrides.get("/code/a"),
+            Some(&DifficultyLevel::Fetch)
+        );
+        assert_eq!(
+            after.overrides.get("/code/b/*"),
+            Some(&DifficultyLevel::Force)
+        );
+    }
+
+    #[test]
+    fn finds_nearest_project_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let outer = dir.path();
+        let inner = outer.join("a/b");
+        std::fs::create_dir_all(&inner).unwrap();
+        assert_eq!(find_project_root(&inner), None);
+
+        std::fs::create_dir(outer.join(LOCAL_INDEX_DIR)).unwrap();
+        std::fs::write(local_index_path(outer), "").unwrap();
+        assert_eq!(find_project_root(&inner).as_deref(), Some(outer));
+
+        std::fs::create_dir_all(outer.join("a").join(LOCAL_INDEX_DIR)).unwrap();
+        std::fs::write(local_index_path(&outer.join("a")), "").unwrap();
+        assert_eq!(find_project_root(&inner), Some(outer.join("a")));
+    }
+
     #[test]
     fn xdg_paths_are_sensible() {
         let cfg = config_dir();
@@ -1,48 +1,88 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::core::permissions::DifficultyLevel;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
+#[schemars(
+    title = "kissa config",
+    description = "Contents of ~/.config/kissa/config.toml"
+)]
 pub struct KissaConfig {
     pub scan: ScanConfig,
     pub identity: IdentityConfig,
     pub defaults: DefaultsConfig,
     pub display: DisplayConfig,
+    /// Per-path difficulty overrides, keyed by path or glob.
     #[serde(default)]
-    pub overrides: HashMap<String, DifficultyLevel>,
+    pub overrides: BTreeMap<String, DifficultyLevel>,
     pub safety: SafetyConfig,
+    /// Classification settings. A separate table because `classify` is the
+    /// rule array.
+    pub classification: ClassificationConfig,
+    /// Classification rules, applied in order (`[[classify]]`).
     #[serde(default)]
     pub classify: Vec<ClassifyRule>,
 }
 
-impl Default for KissaConfig {
-    fn default() -> Self {
-        Self {
-            scan: ScanConfig::default(),
-            identity: IdentityConfig::default(),
-            defaults: DefaultsConfig::default(),
-            display: DisplayConfig::default(),
-            overrides: HashMap::new(),
-            safety: SafetyConfig::default(),
-            classify: Vec::new(),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ScanConfig {
+    /// Directories to walk for repositories.
     pub roots: Vec<PathBuf>,
+    /// Directories to skip while walking: names or path fragments matched as
+    /// whole components (`node_modules`, `.cargo/registry`), or globs against
+    /// the path relative to the root (`*/target`, `**/vendor`).
     pub exclude: Vec<String>,
+    /// Remote orgs whose repos are never indexed, matched against the
+    /// primary remote like a classify rule's `org`.
+    pub exclude_orgs: Vec<String>,
     pub max_depth: usize,
+    /// When a root is itself a repo (it is always indexed), also walk its
+    /// working tree for repos nested inside; off indexes just the root.
+    pub walk_repo_roots: bool,
+    /// Cached vitals older than this are refreshed by `status` and `info`.
     pub auto_verify_seconds: u64,
+    /// Parallel workers for vitals extraction; 0 uses all available cores.
+    pub jobs: usize,
+    /// Skip NFS, SMB, SSHFS and other network-mounted subtrees unless listed
+    /// in `boundaries.allow_mounts`.
+    pub skip_network_fs: bool,
+    /// How untracked files count toward a repo's working-tree state.
+    pub untracked: UntrackedMode,
+    /// The canonical remote: it names the repo, its org decides ownership,
+    /// category and graph identity, its HEAD is checked for default-branch
+    /// drift, and a branch with no tracking branch measures ahead/behind
+    /// against its copy. Repos without it fall back to their first remote.
+    pub primary_remote: String,
+    /// Stop counting a repo's local branches after this many, flagging the
+    /// counts as truncated; 0 counts them all.
+    pub max_branches: u32,
+    /// Read each repo's working-tree status (dirty, staged, untracked).
+    /// It stats every tracked file, so turning it off makes scans of slow
+    /// mounts much faster; repos then keep their last known status.
+    pub compute_status: bool,
     pub boundaries: BoundaryConfig,
 }
 
+/// Untracked-file detection, after `git status --untracked-files`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UntrackedMode {
+    /// Report untracked files, but don't look inside untracked directories.
+    #[default]
+    Normal,
+    /// Ignore untracked files entirely; a repo is never flagged `untracked`.
+    No,
+    /// Report untracked files, recursing into untracked directories.
+    All,
+}
+
 impl Default for ScanConfig {
     fn default() -> Self {
         Self {
@@ -62,19 +102,29 @@ impl Default for ScanConfig {
                 "__pycache__".into(),
                 ".venv".into(),
             ],
+            exclude_orgs: Vec::new(),
             max_depth: 10,
+            walk_repo_roots: true,
             auto_verify_seconds: 300,
+            jobs: 0,
+            skip_network_fs: true,
+            untracked: UntrackedMode::default(),
+            primary_remote: "origin".into(),
+            max_branches: 500,
+            compute_status: true,
             boundaries: BoundaryConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct BoundaryConfig {
     pub cross_mounts: bool,
     pub allow_mounts: Vec<PathBuf>,
     pub block_mounts: Vec<PathBuf>,
+    /// How long a known repo path may take to answer before it's marked
+    /// `timeout` instead of being read (slow or hung mounts).
     pub stat_timeout_ms: u64,
 }
 
@@ -89,26 +139,35 @@ impl Default for BoundaryConfig {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct IdentityConfig {
     pub usernames: Vec<String>,
     pub work_orgs: Vec<WorkOrg>,
     pub community_orgs: Vec<String>,
+    /// Git hosts that are entirely work, mapped to the work label
+    /// (e.g. `"github.acme.internal" = "acme"`).
+    pub platform_aliases: BTreeMap<String, String>,
+    /// API tokens for `kissa enrich`, by host (`"github.com" = "$GITHUB_TOKEN"`).
+    /// A value starting with `$` names an environment variable holding the
+    /// token; hosts without one are never contacted.
+    pub api_tokens: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct WorkOrg {
     pub name: String,
     pub platform: String,
     pub label: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct DefaultsConfig {
     pub difficulty: DifficultyLevel,
     pub mcp: McpDefaultsConfig,
+    /// Days since last commit before `classify --age-out` archives a clean repo.
+    pub age_out_days: u64,
 }
 
 impl Default for DefaultsConfig {
@@ -116,11 +175,12 @@ impl Default for DefaultsConfig {
         Self {
             difficulty: DifficultyLevel::Commit,
             mcp: McpDefaultsConfig::default(),
+            age_out_days: 365,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct McpDefaultsConfig {
     pub difficulty: DifficultyLevel,
@@ -134,12 +194,27 @@ impl Default for McpDefaultsConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct DisplayConfig {
+    #[schemars(extend("enum" = ["auto", "always", "never"]))]
     pub color: String,
+    /// Draw indicators and freshness bars with Nerd Font glyphs instead of
+    /// plain characters; needs a patched font.
     pub nerd_fonts: bool,
     pub cat_mode: bool,
+    /// Make paths clickable (OSC 8) in terminals known to support it.
+    pub hyperlinks: bool,
+    /// Flag repos in listings once they have this many stale branches;
+    /// 0 turns the warning off.
+    pub stale_branch_warn: u32,
+    /// Color repo names on a continuous scale by commit age instead of by
+    /// freshness tier, on terminals with 256 colors.
+    pub gradient: bool,
+    /// Which repo a name picks when several match at the same level
+    /// (exact, prefix, substring), wherever a command or MCP tool takes a
+    /// repo name.
+    pub name_collision: NameCollision,
 }
 
 impl Default for DisplayConfig {
@@ -148,11 +223,63 @@ impl Default for DisplayConfig {
             color: "auto".into(),
             nerd_fonts: false,
             cat_mode: false,
+            hyperlinks: false,
+            stale_branch_warn: 3,
+            gradient: false,
+            name_collision: NameCollision::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NameCollision {
+    /// Take the first match in index order.
+    #[default]
+    First,
+    /// Fail and list the candidates, so the name has to be made unique.
+    Error,
+    /// Take the match with the most recent commit.
+    MostRecent,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ClassificationConfig {
+    /// How built-in heuristics (nvim plugin dirs, cargo checkouts, ...)
+    /// combine with `[[classify]]` rules.
+    pub heuristics: HeuristicsMode,
+    /// Executable run for each repo a CLI scan indexes for the first time.
+    /// It gets the repo as JSON on stdin and prints extra tags, one per
+    /// line. Runs only where the repo's difficulty is `commit` or above.
+    pub hook: Option<PathBuf>,
+    /// Seconds the hook may run before it is killed and counted as failed.
+    pub hook_timeout_secs: u64,
+}
+
+impl Default for ClassificationConfig {
+    fn default() -> Self {
+        Self {
+            heuristics: HeuristicsMode::default(),
+            hook: None,
+            hook_timeout_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HeuristicsMode {
+    /// Fill only fields that rules and identity left unset.
+    #[default]
+    Fallback,
+    /// Overwrite whatever rules set for the fields a heuristic covers.
+    Override,
+    /// Never apply built-in heuristics.
+    Off,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SafetyConfig {
     pub protected_branches: Vec<String>,
@@ -163,11 +290,7 @@ pub struct SafetyConfig {
 impl Default for SafetyConfig {
     fn default() -> Self {
         Self {
-            protected_branches: vec![
-                "main".into(),
-                "master".into(),
-                "production".into(),
-            ],
+            protected_branches: vec!["main".into(), "master".into(), "production".into()],
             always_confirm_destructive: true,
             max_plan_size: 50,
         }
@@ -175,7 +298,7 @@ impl Default for SafetyConfig {
 }
 
 /// A classification rule from config `[[classify]]` (ADR-106).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ClassifyRule {
     #[serde(rename = "match")]
     pub match_criteria: ClassifyMatch,
@@ -188,19 +311,42 @@ pub struct ClassifyRule {
 }
 
 /// Match criteria for a classification rule. All fields are AND-combined.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ClassifyMatch {
     pub path: Option<String>,
     pub org: Option<String>,
     pub name: Option<String>,
     pub has_remote: Option<bool>,
+    /// Regex matched against every remote URL; any match satisfies it.
+    pub remote_url: Option<String>,
+    /// Glob relative to the repo root, e.g. `Cargo.toml` or `.github/workflows`;
+    /// satisfied when at least one path in the working tree matches.
+    pub has_file: Option<String>,
+    /// Compiled `remote_url`, built once on first use. `None` inside means invalid.
+    #[serde(skip)]
+    pub(crate) remote_url_regex: OnceLock<Option<regex::Regex>>,
+}
+
+impl ClassifyMatch {
+    /// The compiled `remote_url` regex, or `None` if unset or invalid.
+    pub fn remote_url_regex(&self) -> Option<&regex::Regex> {
+        let pattern = self.remote_url.as_deref()?;
+        self.remote_url_regex
+            .get_or_init(|| regex::Regex::new(pattern).ok())
+            .as_ref()
+    }
 }
 
 /// Fields to set when a classification rule matches.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ClassifySet {
+    /// One of origin, clone, fork, mirror.
     pub category: Option<String>,
+    /// One of personal, work, community, thirdparty, local.
     pub ownership: Option<String>,
+    /// One of developing, contributing, reference, dependency, dotfiles,
+    /// infrastructure, experiment, archived.
     pub intention: Option<String>,
+    /// One of active, lost, timeout.
     pub state: Option<String>,
 }
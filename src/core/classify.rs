@@ -1,6 +1,12 @@
-use crate::config::types::{ClassifyRule, KissaConfig};
-use super::git_ops::parse_remote_org;
-use super::repo::{Ownership, Intention, Repo, RepoState};
+use std::collections::HashMap;
+use std::path::{Component, Path};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::git_ops::{self, parse_remote_org};
+use super::repo::{Category, Intention, Ownership, Remote, Repo, RepoState};
+use crate::config::types::{ClassifyRule, HeuristicsMode, IdentityConfig, KissaConfig};
 
 /// Built-in heuristic patterns for tool-managed repos.
 /// Each entry: (glob pattern, managed_by name).
@@ -8,33 +14,355 @@ const BUILTIN_HEURISTICS: &[(&str, &str)] = &[
     ("*/.local/share/nvim/lazy/*", "lazy.nvim"),
     ("*/.local/share/nvim/site/pack/*/start/*", "nvim-pack"),
     ("*/.vim/plugged/*", "vim-plug"),
-    ("*/.local/share/SuperCollider/downloaded-quarks/*", "SuperCollider"),
+    (
+        "*/.local/share/SuperCollider/downloaded-quarks/*",
+        "SuperCollider",
+    ),
     ("*/.cargo/git/checkouts/*", "cargo"),
     ("*/.local/share/FreeCAD/Mod/*", "FreeCAD"),
     ("*/.local/share/86Box/*", "86Box"),
 ];
 
+/// One evaluated step of a classification run, as reported by `explain`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStep {
+    /// Where the step came from: "rule 1", "identity" or "heuristics".
+    pub source: String,
+    /// The rule's match criteria, or what the step looked at.
+    pub detail: String,
+    pub matched: bool,
+    /// Fields this step set, as `field = value`.
+    pub set: Vec<String>,
+    /// Fields this step would have set, but an earlier match already had.
+    pub skipped: Vec<String>,
+}
+
+/// Answers `has_file` criteria for one repo, remembering each pattern's
+/// result so rules sharing a pattern touch the filesystem once.
+struct FileChecks<'a> {
+    root: &'a Path,
+    seen: HashMap<String, bool>,
+}
+
+impl<'a> FileChecks<'a> {
+    fn new(root: &'a Path) -> Self {
+        Self {
+            root,
+            seen: HashMap::new(),
+        }
+    }
+
+    fn has(&mut self, pattern: &str) -> bool {
+        if let Some(&hit) = self.seen.get(pattern) {
+            return hit;
+        }
+        let hit = file_exists(self.root, pattern);
+        self.seen.insert(pattern.to_string(), hit);
+        hit
+    }
+}
+
+/// Whether any path under `root` matches `pattern`. Literal patterns are a
+/// single stat; globs stop at the first match. Patterns that are absolute or
+/// climb out of the repo with `..` never match.
+fn file_exists(root: &Path, pattern: &str) -> bool {
+    let relative = Path::new(pattern);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|c| matches!(c, Component::ParentDir | Component::Prefix(_)))
+    {
+        return false;
+    }
+    if !pattern.contains(['*', '?', '[']) {
+        return root.join(relative).exists();
+    }
+    let full = format!(
+        "{}/{}",
+        glob::Pattern::escape(&root.to_string_lossy()),
+        pattern
+    );
+    match glob::glob(&full) {
+        Ok(mut paths) => paths.any(|p| p.is_ok()),
+        Err(_) => false,
+    }
+}
+
+/// Fields changed (and not changed) by applying one rule or heuristic.
+#[derive(Default)]
+struct Effect {
+    set: Vec<String>,
+    skipped: Vec<String>,
+}
+
+impl Effect {
+    fn into_step(self, source: impl Into<String>, detail: impl Into<String>) -> TraceStep {
+        TraceStep {
+            source: source.into(),
+            detail: detail.into(),
+            matched: true,
+            set: self.set,
+            skipped: self.skipped,
+        }
+    }
+}
+
 /// Apply classification rules and built-in heuristics to a repo.
 ///
 /// Evaluation order:
 /// 1. Config `[[classify]]` rules in order (first match per field wins)
-/// 2. Built-in heuristics as lowest-priority fallback
+/// 2. Ownership from `[identity]` (platform aliases, work orgs, usernames, community orgs)
+/// 3. Built-in heuristics, per `classification.heuristics`: a lowest-priority
+///    fallback by default, overriding rules, or off
+/// 4. Category (fork, clone, mirror) from the shape of the remotes
 ///
 /// Tags are always appended, never first-match gated.
 pub fn classify_repo(repo: &mut Repo, config: &KissaConfig) {
+    classify_traced(repo, config, None);
+}
+
+/// Like `classify_repo`, but also returns every rule evaluated, whether it
+/// matched and which fields it set, followed by the identity and heuristic phases.
+pub fn explain_classification(repo: &mut Repo, config: &KissaConfig) -> Vec<TraceStep> {
+    let mut trace = Vec::new();
+    classify_traced(repo, config, Some(&mut trace));
+    trace
+}
+
+fn classify_traced(repo: &mut Repo, config: &KissaConfig, mut trace: Option<&mut Vec<TraceStep>>) {
     // Phase 1: config rules
-    for rule in &config.classify {
-        if rule_matches(rule, repo) {
-            apply_rule(rule, repo);
+    let root = repo.path.clone();
+    let mut files = FileChecks::new(&root);
+    for (i, rule) in config.classify.iter().enumerate() {
+        let matched = rule_matches(rule, repo, &mut files);
+        let effect = if matched {
+            apply_rule(rule, repo)
+        } else {
+            Effect::default()
+        };
+        if let Some(trace) = trace.as_deref_mut() {
+            let mut step = effect.into_step(format!("rule {}", i + 1), describe_match(rule));
+            step.matched = matched;
+            trace.push(step);
+        }
+    }
+
+    // Phase 2: identity-based ownership (only if no rule set it)
+    if repo.ownership.is_none() {
+        let inferred = infer_ownership(repo, &config.identity, &config.scan.primary_remote);
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(match &inferred {
+                Some((ownership, reason)) => Effect {
+                    set: vec![format!("ownership = {}", ownership.label())],
+                    skipped: Vec::new(),
+                }
+                .into_step("identity", reason.clone()),
+                None => TraceStep {
+                    source: "identity".into(),
+                    detail: "no username, org or platform alias matched the primary remote".into(),
+                    matched: false,
+                    set: Vec::new(),
+                    skipped: Vec::new(),
+                },
+            });
+        }
+        repo.ownership = inferred.map(|(ownership, _)| ownership);
+    } else if let Some(trace) = trace.as_deref_mut() {
+        trace.push(TraceStep {
+            source: "identity".into(),
+            detail: "not consulted: ownership already set".into(),
+            matched: false,
+            set: Vec::new(),
+            skipped: Vec::new(),
+        });
+    }
+
+    // Phase 3: built-in heuristics (fill None fields unless overriding)
+    let mode = config.classification.heuristics;
+    let fired = apply_heuristics(repo, mode);
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.push(match fired {
+            Some((pattern, effect)) => effect.into_step("heuristics", pattern),
+            None => TraceStep {
+                source: "heuristics".into(),
+                detail: if mode == HeuristicsMode::Off {
+                    "not consulted: classification.heuristics = off".into()
+                } else if mode == HeuristicsMode::Fallback && repo.managed_by.is_some() {
+                    "not consulted: managed_by already set".into()
+                } else {
+                    "no built-in pattern matched the path".into()
+                },
+                matched: false,
+                set: Vec::new(),
+                skipped: Vec::new(),
+            },
+        });
+    }
+
+    // Phase 4: category from remotes (only if no rule set it)
+    if repo.category.is_none() {
+        let inferred = infer_category(&repo.remotes, &config.scan.primary_remote);
+        if let Some(trace) = trace {
+            trace.push(match &inferred {
+                Some((category, origin_org, reason)) => {
+                    let mut set = vec![format!("category = {}", category_label(*category))];
+                    if let Some(org) = origin_org {
+                        set.push(format!("origin_org = {}", org));
+                    }
+                    Effect {
+                        set,
+                        skipped: Vec::new(),
+                    }
+                    .into_step("remotes", reason.clone())
+                }
+                None => TraceStep {
+                    source: "remotes".into(),
+                    detail: "remotes don't indicate a fork, clone or mirror".into(),
+                    matched: false,
+                    set: Vec::new(),
+                    skipped: Vec::new(),
+                },
+            });
+        }
+        if let Some((category, origin_org, _)) = inferred {
+            repo.category = Some(category);
+            repo.origin_org = origin_org;
+        }
+    }
+}
+
+/// Infer a category from a repo's remotes, with the upstream org for forks
+/// and a reason for `explain`:
+/// - a push URL pointing at a different repo than its fetch URL → mirror
+/// - another remote with the same repo name under a different org than
+///   the primary remote (`scan.primary_remote`) → fork of that org (a remote
+///   named `upstream` wins)
+/// - exactly one remote → clone
+fn infer_category(
+    remotes: &[Remote],
+    primary_remote: &str,
+) -> Option<(Category, Option<String>, String)> {
+    if let Some(remote) = remotes.iter().find(|r| {
+        r.push_url
+            .as_deref()
+            .is_some_and(|push| !same_repo(push, &r.url))
+    }) {
+        return Some((
+            Category::Mirror,
+            None,
+            format!(
+                "remote {} pushes somewhere other than it fetches",
+                remote.name
+            ),
+        ));
+    }
+
+    let origin = git_ops::primary_remote(remotes, primary_remote)?;
+    let origin_info = parse_remote_org(&origin.url);
+    if let Some(ref origin_info) = origin_info {
+        let mut candidates: Vec<_> = remotes
+            .iter()
+            .filter(|r| r.name != origin.name)
+            .filter_map(|r| parse_remote_org(&r.url).map(|info| (r, info)))
+            .filter(|(_, info)| {
+                info.repo_name.eq_ignore_ascii_case(&origin_info.repo_name)
+                    && !info.org.eq_ignore_ascii_case(&origin_info.org)
+            })
+            .collect();
+        candidates.sort_by_key(|(r, _)| r.name != "upstream");
+        if let Some((upstream, info)) = candidates.into_iter().next() {
+            return Some((
+                Category::Fork,
+                Some(info.org.clone()),
+                format!(
+                    "{} is {}/{}, {} is {}/{}",
+                    origin.name,
+                    origin_info.org,
+                    origin_info.repo_name,
+                    upstream.name,
+                    info.org,
+                    info.repo_name,
+                ),
+            ));
+        }
+    }
+
+    (remotes.len() == 1).then(|| {
+        (
+            Category::Clone,
+            None,
+            format!("single remote {}", origin.name),
+        )
+    })
+}
+
+/// Whether two remote URLs name the same repo, so an `https` fetch URL and
+/// an `ssh` push URL for one repo don't look like a mirror.
+fn same_repo(a: &str, b: &str) -> bool {
+    match (parse_remote_org(a), parse_remote_org(b)) {
+        (Some(a), Some(b)) => {
+            a.platform.eq_ignore_ascii_case(&b.platform)
+                && a.org.eq_ignore_ascii_case(&b.org)
+                && a.repo_name.eq_ignore_ascii_case(&b.repo_name)
         }
+        _ => a == b,
     }
+}
 
-    // Phase 2: built-in heuristics (only fill None fields)
-    apply_heuristics(repo);
+/// Reset the fields `classify_repo` derives, so rules can be re-applied from
+/// scratch. Archiving is a user decision and tags may be hand-added, so both
+/// survive.
+pub fn reset_classification(repo: &mut Repo) {
+    repo.managed_by = None;
+    repo.ownership = None;
+    if repo.intention != Some(Intention::Archived) {
+        repo.intention = None;
+    }
+    repo.category = None;
+    repo.origin_org = None;
+}
+
+/// Re-derive `repo`'s classification from the current rules, as after a
+/// rules change. Returns whether anything changed.
+pub fn reclassify(repo: &mut Repo, config: &KissaConfig) -> bool {
+    let before = repo.clone();
+    // Keep user tags but allow rule tags to be re-added
+    reset_classification(repo);
+    classify_repo(repo, config);
+    !before.diff(repo).is_empty()
+}
+
+/// Render a rule's match criteria like `path=~/src/* org=initech`.
+fn describe_match(rule: &ClassifyRule) -> String {
+    let m = &rule.match_criteria;
+    let mut parts = Vec::new();
+    if let Some(ref path) = m.path {
+        parts.push(format!("path={}", path));
+    }
+    if let Some(ref org) = m.org {
+        parts.push(format!("org={}", org));
+    }
+    if let Some(ref name) = m.name {
+        parts.push(format!("name={}", name));
+    }
+    if let Some(has_remote) = m.has_remote {
+        parts.push(format!("has_remote={}", has_remote));
+    }
+    if let Some(ref url) = m.remote_url {
+        parts.push(format!("remote_url={}", url));
+    }
+    if let Some(ref file) = m.has_file {
+        parts.push(format!("has_file={}", file));
+    }
+    if parts.is_empty() {
+        "(matches everything)".into()
+    } else {
+        parts.join(" ")
+    }
 }
 
 /// Check if all match criteria in a rule are satisfied (AND-combined).
-fn rule_matches(rule: &ClassifyRule, repo: &Repo) -> bool {
+fn rule_matches(rule: &ClassifyRule, repo: &Repo, files: &mut FileChecks) -> bool {
     let m = &rule.match_criteria;
 
     if let Some(ref pattern) = m.path {
@@ -52,8 +380,7 @@ fn rule_matches(rule: &ClassifyRule, repo: &Repo) -> bool {
 
     if let Some(ref org_filter) = m.org {
         let matches_org = repo.remotes.iter().any(|remote| {
-            parse_remote_org(&remote.url)
-                .is_some_and(|info| info.org.eq_ignore_ascii_case(org_filter))
+            parse_remote_org(&remote.url).is_some_and(|info| info.org_matches(org_filter))
         });
         if !matches_org {
             return false;
@@ -72,79 +399,211 @@ fn rule_matches(rule: &ClassifyRule, repo: &Repo) -> bool {
         }
     }
 
-    if let Some(has_remote) = m.has_remote {
-        if repo.remotes.is_empty() == has_remote {
+    if let Some(has_remote) = m.has_remote
+        && repo.remotes.is_empty() == has_remote
+    {
+        return false;
+    }
+
+    if m.remote_url.is_some() {
+        // An invalid pattern disables the rule rather than matching everything
+        let Some(re) = m.remote_url_regex() else {
+            return false;
+        };
+        if !repo.remotes.iter().any(|r| re.is_match(&r.url)) {
             return false;
         }
     }
 
+    // Checked last: the only criterion that touches the filesystem
+    if let Some(ref pattern) = m.has_file
+        && !files.has(pattern)
+    {
+        return false;
+    }
+
     true
 }
 
 /// Apply a matching rule's fields to a repo.
 /// First-match-per-field: only sets fields that are currently None.
 /// Tags are always appended.
-fn apply_rule(rule: &ClassifyRule, repo: &mut Repo) {
-    if repo.managed_by.is_none() {
-        if let Some(ref mb) = rule.managed_by {
+fn apply_rule(rule: &ClassifyRule, repo: &mut Repo) -> Effect {
+    let mut effect = Effect::default();
+
+    if let Some(ref mb) = rule.managed_by {
+        if repo.managed_by.is_none() {
             repo.managed_by = Some(mb.clone());
+            effect.set.push(format!("managed_by = {}", mb));
+        } else {
+            effect.skipped.push("managed_by".into());
         }
     }
 
     if let Some(ref ownership_str) = rule.set.ownership {
         if repo.ownership.is_none() {
             repo.ownership = parse_ownership(ownership_str);
+            effect.set.push(format!("ownership = {}", ownership_str));
+        } else {
+            effect.skipped.push("ownership".into());
         }
     }
 
     if let Some(ref intention_str) = rule.set.intention {
         if repo.intention.is_none() {
             repo.intention = serde_plain::from_str(intention_str).ok();
+            effect.set.push(format!("intention = {}", intention_str));
+        } else {
+            effect.skipped.push("intention".into());
         }
     }
 
     if let Some(ref category_str) = rule.set.category {
         if repo.category.is_none() {
             repo.category = serde_plain::from_str(category_str).ok();
+            effect.set.push(format!("category = {}", category_str));
+        } else {
+            effect.skipped.push("category".into());
         }
     }
 
-    if let Some(ref state_str) = rule.set.state {
-        if let Ok(state) = serde_plain::from_str::<RepoState>(state_str) {
-            repo.state = state;
-        }
+    if let Some(ref state_str) = rule.set.state
+        && let Ok(state) = serde_plain::from_str::<RepoState>(state_str)
+    {
+        repo.state = state;
+        effect.set.push(format!("state = {}", state_str));
     }
 
     // Tags: always appended, deduplicated
     for tag in &rule.tags {
         if !repo.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
             repo.tags.push(tag.clone());
+            effect.set.push(format!("tag += {}", tag));
         }
     }
+
+    effect
 }
 
-/// Apply built-in heuristics as lowest-priority fallback.
-fn apply_heuristics(repo: &mut Repo) {
-    if repo.managed_by.is_some() {
-        return;
+/// Infer ownership from the primary remote (`scan.primary_remote`, else the
+/// first) using the identity config, along with which identity entry matched.
+/// Returns None when nothing matches.
+fn infer_ownership(
+    repo: &Repo,
+    identity: &IdentityConfig,
+    primary_remote: &str,
+) -> Option<(Ownership, String)> {
+    let remote = git_ops::primary_remote(&repo.remotes, primary_remote)?;
+    let info = parse_remote_org(&remote.url)?;
+    let host = info.platform.to_lowercase();
+
+    // A whole host aliased to work wins over any org-level match
+    if let Some((alias, label)) = identity
+        .platform_aliases
+        .iter()
+        .find(|(alias, _)| alias.to_lowercase() == host)
+    {
+        return Some((
+            Ownership::Work {
+                label: label.clone(),
+            },
+            format!("platform alias {}", alias),
+        ));
+    }
+
+    if let Some(org) = identity
+        .work_orgs
+        .iter()
+        .find(|o| platform_matches(&host, &o.platform) && info.org_matches(&o.name))
+    {
+        return Some((
+            Ownership::Work {
+                label: org.label.clone(),
+            },
+            format!("work org {}/{}", org.platform, org.name),
+        ));
+    }
+
+    if let Some(user) = identity
+        .usernames
+        .iter()
+        .find(|u| u.eq_ignore_ascii_case(info.owner()))
+    {
+        return Some((Ownership::Personal, format!("username {}", user)));
+    }
+
+    if let Some(org) = identity.community_orgs.iter().find(|o| info.org_matches(o)) {
+        return Some((Ownership::Community, format!("community org {}", org)));
     }
 
-    let path_str = repo.path.to_string_lossy();
+    None
+}
+
+/// Whether a remote host matches a configured platform, which may be the full
+/// host (`github.com`) or its first label (`github`).
+fn platform_matches(host: &str, platform: &str) -> bool {
+    let platform = platform.to_lowercase();
+    host == platform || host.split('.').next() == Some(platform.as_str())
+}
+
+/// Apply built-in heuristics. As a fallback they only fill None fields; in
+/// override mode they replace what rules set, except an archived intention.
+/// Returns the pattern that fired and what it set, if any.
+fn apply_heuristics(repo: &mut Repo, mode: HeuristicsMode) -> Option<(&'static str, Effect)> {
+    let force = match mode {
+        HeuristicsMode::Off => return None,
+        HeuristicsMode::Fallback if repo.managed_by.is_some() => return None,
+        HeuristicsMode::Fallback => false,
+        HeuristicsMode::Override => true,
+    };
+
+    let path_str = repo.path.to_string_lossy().into_owned();
     for &(pattern, manager) in BUILTIN_HEURISTICS {
         let expanded = expand_tilde(pattern);
-        if let Ok(p) = glob::Pattern::new(&expanded) {
-            if p.matches(&path_str) {
-                repo.managed_by = Some(manager.to_string());
-                if repo.ownership.is_none() {
-                    repo.ownership = Some(Ownership::ThirdParty);
-                }
-                if repo.intention.is_none() {
-                    repo.intention = Some(Intention::Dependency);
-                }
-                return;
+        if let Ok(p) = glob::Pattern::new(&expanded)
+            && p.matches(&path_str)
+        {
+            let mut effect = Effect::default();
+            repo.managed_by = Some(manager.to_string());
+            effect.set.push(format!("managed_by = {}", manager));
+            if force || repo.ownership.is_none() {
+                repo.ownership = Some(Ownership::ThirdParty);
+                effect.set.push("ownership = third-party".into());
+            } else {
+                effect.skipped.push("ownership".into());
+            }
+            let archived = repo.intention == Some(Intention::Archived);
+            if (force && !archived) || repo.intention.is_none() {
+                repo.intention = Some(Intention::Dependency);
+                effect.set.push("intention = dependency".into());
+            } else {
+                effect.skipped.push("intention".into());
             }
+            return Some((pattern, effect));
         }
     }
+    None
+}
+
+/// Whether a repo is a safe candidate for automatic archiving: untouched for
+/// more than `older_than_days`, nothing uncommitted or unpushed, and no
+/// intention chosen yet. Repos without any commit count as old, except
+/// empty ones: an unborn HEAD means the repo was just created.
+pub fn is_age_out_candidate(repo: &Repo, older_than_days: u64, now: DateTime<Utc>) -> bool {
+    if repo.is_empty {
+        return false;
+    }
+    let old_enough = match repo.last_commit {
+        Some(ts) => (now - ts).num_days() > older_than_days as i64,
+        None => true,
+    };
+    old_enough
+        && repo.state == RepoState::Active
+        && repo.intention.is_none()
+        && !repo.dirty
+        && !repo.staged
+        && !repo.untracked
+        && repo.ahead == 0
 }
 
 /// Parse an ownership string like "personal", "work:acme", "third-party".
@@ -164,12 +623,19 @@ fn parse_ownership(s: &str) -> Option<Ownership> {
     }
 }
 
+/// The config spelling of a category value.
+fn category_label(category: Category) -> String {
+    serde_plain::to_string(&category).unwrap_or_default()
+}
+
 /// Expand `~` prefix to home directory.
 fn expand_tilde(pattern: &str) -> String {
-    if let Some(rest) = pattern.strip_prefix("~/") {
-        if let Some(home) = dirs::home_dir() {
-            return format!("{}/{}", home.display(), rest);
-        }
+    if let Some(rest) = pattern
+        .strip_prefix("~/")
+        .or_else(|| pattern.strip_prefix(&format!("~{}", std::path::MAIN_SEPARATOR)))
+        && let Some(home) = dirs::home_dir()
+    {
+        return format!("{}{}{}", home.display(), std::path::MAIN_SEPARATOR, rest);
     }
     pattern.to_string()
 }
@@ -179,40 +645,16 @@ mod tests {
     use super::*;
     use crate::config::types::*;
     use crate::core::repo::*;
-    use chrono::Utc;
     use std::path::PathBuf;
 
     fn make_repo(name: &str, path: &str) -> Repo {
         Repo {
-            id: 0,
-            name: name.to_string(),
-            path: PathBuf::from(path),
-            state: RepoState::Active,
             remotes: vec![Remote {
                 name: "origin".into(),
                 url: "git@github.com:someuser/somerepo.git".into(),
                 push_url: None,
             }],
-            default_branch: Some("main".into()),
-            current_branch: Some("main".into()),
-            branch_count: 1,
-            stale_branch_count: 0,
-            dirty: false,
-            staged: false,
-            untracked: false,
-            ahead: 0,
-            behind: 0,
-            last_commit: Some(Utc::now()),
-            last_verified: Some(Utc::now()),
-            first_seen: Utc::now(),
-            freshness: Freshness::Active,
-            category: None,
-            ownership: None,
-            intention: None,
-            managed_by: None,
-            tags: vec![],
-            project: None,
-            role: None,
+            ..Repo::test_default(name, path)
         }
     }
 
@@ -402,9 +844,49 @@ mod tests {
         assert!(repo.tags.contains(&"nvim".to_string()));
     }
 
+    #[test]
+    fn heuristics_mode_override_and_off() {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/home/testuser"));
+        let path = home.join(".local/share/nvim/lazy/my-plugin");
+
+        let mut config = empty_config();
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch {
+                path: Some(format!("{}/.local/share/nvim/lazy/*", home.display())),
+                ..Default::default()
+            },
+            set: ClassifySet {
+                ownership: Some("personal".into()),
+                ..Default::default()
+            },
+            managed_by: Some("custom-manager".into()),
+            tags: vec![],
+        });
+
+        config.classification.heuristics = HeuristicsMode::Override;
+        let mut repo = make_repo("my-plugin", path.to_str().unwrap());
+        classify_repo(&mut repo, &config);
+        assert_eq!(repo.managed_by, Some("lazy.nvim".into()));
+        assert_eq!(repo.ownership, Some(Ownership::ThirdParty));
+
+        // Override never undoes archiving
+        let mut archived = make_repo("my-plugin", path.to_str().unwrap());
+        archived.intention = Some(Intention::Archived);
+        classify_repo(&mut archived, &config);
+        assert_eq!(archived.intention, Some(Intention::Archived));
+
+        config.classify.clear();
+        config.classification.heuristics = HeuristicsMode::Off;
+        let mut repo = make_repo("my-plugin", path.to_str().unwrap());
+        classify_repo(&mut repo, &config);
+        assert!(repo.managed_by.is_none());
+        assert!(repo.intention.is_none());
+    }
+
     #[test]
     fn no_match_leaves_fields_none() {
         let mut repo = make_repo("random-repo", "/tmp/random-repo");
+        repo.remotes.clear(); // a lone remote would still infer Clone
         classify_repo(&mut repo, &empty_config());
 
         assert!(repo.managed_by.is_none());
@@ -451,6 +933,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remote_url_regex_matches_nested_groups() {
+        let mut config = empty_config();
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch {
+                remote_url: Some(r"gitlab\.com[:/]team/subteam/".into()),
+                ..Default::default()
+            },
+            set: ClassifySet {
+                ownership: Some("work:team".into()),
+                ..Default::default()
+            },
+            managed_by: None,
+            tags: vec![],
+        });
+
+        let mut repo = make_repo("svc", "/code/svc");
+        repo.remotes[0].url = "git@gitlab.com:team/subteam/svc.git".into();
+        classify_repo(&mut repo, &config);
+        assert_eq!(
+            repo.ownership,
+            Some(Ownership::Work {
+                label: "team".into()
+            })
+        );
+
+        let mut other = make_repo("svc", "/code/other");
+        other.remotes[0].url = "git@gitlab.com:team/elsewhere/svc.git".into();
+        classify_repo(&mut other, &config);
+        assert!(other.ownership.is_none());
+    }
+
+    #[test]
+    fn has_file_checks_working_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::create_dir_all(dir.path().join(".github/workflows")).unwrap();
+        std::fs::write(dir.path().join(".github/workflows/ci.yml"), "").unwrap();
+
+        let mut config = empty_config();
+        for (pattern, tag) in [
+            ("Cargo.toml", "rust"),
+            (".github/workflows", "ci"),
+            (".github/workflows/*.yml", "actions"),
+            ("package.json", "node"),
+            ("../*", "escape"),
+            ("Cargo.toml", "crate"),
+        ] {
+            config.classify.push(ClassifyRule {
+                match_criteria: ClassifyMatch {
+                    has_file: Some(pattern.into()),
+                    ..Default::default()
+                },
+                set: ClassifySet::default(),
+                managed_by: None,
+                tags: vec![tag.into()],
+            });
+        }
+
+        let mut repo = make_repo("proj", dir.path().to_str().unwrap());
+        classify_repo(&mut repo, &config);
+        assert_eq!(repo.tags, vec!["rust", "ci", "actions", "crate"]);
+
+        let mut files = FileChecks::new(dir.path());
+        assert!(files.has("Cargo.toml"));
+        std::fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        assert!(files.has("Cargo.toml"), "result is cached per repo");
+        assert!(!FileChecks::new(dir.path()).has("Cargo.toml"));
+    }
+
+    #[test]
+    fn invalid_remote_url_regex_skips_rule() {
+        let mut config = empty_config();
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch {
+                remote_url: Some("(unclosed".into()),
+                ..Default::default()
+            },
+            set: ClassifySet::default(),
+            managed_by: None,
+            tags: vec!["never".into()],
+        });
+
+        let mut repo = make_repo("svc", "/code/svc");
+        classify_repo(&mut repo, &config);
+        assert!(repo.tags.is_empty());
+    }
+
     #[test]
     fn work_ownership_parsing() {
         assert_eq!(
@@ -466,4 +1036,259 @@ mod tests {
         assert_eq!(parse_ownership("local"), Some(Ownership::Local));
         assert_eq!(parse_ownership("nonsense"), None);
     }
+
+    #[test]
+    fn age_out_requires_old_clean_pushed_unclassified() {
+        let now = Utc::now();
+        let mut repo = make_repo("old", "/code/old");
+        repo.last_commit = Some(now - chrono::Duration::days(400));
+        assert!(is_age_out_candidate(&repo, 365, now));
+        assert!(!is_age_out_candidate(&repo, 500, now));
+
+        let mut dirty = repo.clone();
+        dirty.dirty = true;
+        assert!(!is_age_out_candidate(&dirty, 365, now));
+
+        let mut unpushed = repo.clone();
+        unpushed.ahead = 1;
+        assert!(!is_age_out_candidate(&unpushed, 365, now));
+
+        let mut chosen = repo.clone();
+        chosen.intention = Some(Intention::Reference);
+        assert!(!is_age_out_candidate(&chosen, 365, now));
+
+        let mut never_committed = make_repo("empty", "/code/empty");
+        never_committed.last_commit = None;
+        assert!(is_age_out_candidate(&never_committed, 365, now));
+
+        never_committed.is_empty = true;
+        assert!(!is_age_out_candidate(&never_committed, 365, now));
+    }
+
+    fn with_origin(url: &str) -> Repo {
+        let mut repo = make_repo("r", "/code/r");
+        repo.remotes[0].url = url.into();
+        repo
+    }
+
+    #[test]
+    fn platform_alias_classifies_host_as_work() {
+        let mut config = empty_config();
+        config
+            .identity
+            .platform_aliases
+            .insert("github.acme.internal".into(), "acme".into());
+
+        let mut repo = with_origin("git@github.acme.internal:platform/api.git");
+        classify_repo(&mut repo, &config);
+        assert_eq!(
+            repo.ownership,
+            Some(Ownership::Work {
+                label: "acme".into()
+            })
+        );
+
+        let mut public = with_origin("git@github.com:platform/api.git");
+        classify_repo(&mut public, &config);
+        assert!(public.ownership.is_none());
+    }
+
+    #[test]
+    fn work_org_respects_platform() {
+        let mut config = empty_config();
+        config.identity.work_orgs.push(WorkOrg {
+            name: "initech".into(),
+            platform: "gitlab.com".into(),
+            label: "initech".into(),
+        });
+        config.identity.usernames.push("me".into());
+        config.identity.community_orgs.push("rust-lang".into());
+
+        let mut work = with_origin("https://gitlab.com/initech/billing.git");
+        classify_repo(&mut work, &config);
+        assert_eq!(
+            work.ownership,
+            Some(Ownership::Work {
+                label: "initech".into()
+            })
+        );
+
+        // Same org name on another host is not work
+        let mut other = with_origin("https://github.com/initech/billing.git");
+        classify_repo(&mut other, &config);
+        assert!(other.ownership.is_none());
+
+        let mut mine = with_origin("git@github.com:Me/dotfiles.git");
+        classify_repo(&mut mine, &config);
+        assert_eq!(mine.ownership, Some(Ownership::Personal));
+
+        let mut oss = with_origin("https://github.com/rust-lang/cargo.git");
+        classify_repo(&mut oss, &config);
+        assert_eq!(oss.ownership, Some(Ownership::Community));
+    }
+
+    #[test]
+    fn rule_ownership_beats_identity() {
+        let mut config = empty_config();
+        config.identity.usernames.push("someuser".into());
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch {
+                name: Some("r".into()),
+                ..Default::default()
+            },
+            set: ClassifySet {
+                ownership: Some("community".into()),
+                ..Default::default()
+            },
+            managed_by: None,
+            tags: vec![],
+        });
+        let mut repo = make_repo("r", "/code/r");
+        classify_repo(&mut repo, &config);
+        assert_eq!(repo.ownership, Some(Ownership::Community));
+    }
+
+    #[test]
+    fn explain_traces_rules_identity_and_heuristics() {
+        let mut config = empty_config();
+        config.identity.usernames = vec!["someuser".into()];
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch {
+                path: Some("/elsewhere/*".into()),
+                ..Default::default()
+            },
+            set: ClassifySet::default(),
+            managed_by: Some("never".into()),
+            tags: vec![],
+        });
+        for intention in ["developing", "reference"] {
+            config.classify.push(ClassifyRule {
+                match_criteria: ClassifyMatch {
+                    path: Some("/code/*".into()),
+                    ..Default::default()
+                },
+                set: ClassifySet {
+                    intention: Some(intention.into()),
+                    ..Default::default()
+                },
+                managed_by: None,
+                tags: vec![],
+            });
+        }
+
+        let mut repo = make_repo("myrepo", "/code/myrepo");
+        let trace = explain_classification(&mut repo, &config);
+        let sources: Vec<_> = trace.iter().map(|s| s.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "rule 1",
+                "rule 2",
+                "rule 3",
+                "identity",
+                "heuristics",
+                "remotes"
+            ]
+        );
+
+        assert!(!trace[0].matched);
+        assert_eq!(trace[0].detail, "path=/elsewhere/*");
+        assert_eq!(trace[1].set, ["intention = developing"]);
+        assert!(trace[2].matched);
+        assert_eq!(trace[2].skipped, ["intention"]);
+        assert_eq!(trace[3].set, ["ownership = personal"]);
+        assert_eq!(trace[3].detail, "username someuser");
+        assert!(!trace[4].matched);
+        assert_eq!(trace[5].set, ["category = clone"]);
+
+        // Tracing doesn't change the outcome
+        let mut plain = make_repo("myrepo", "/code/myrepo");
+        classify_repo(&mut plain, &config);
+        assert_eq!(plain.intention, repo.intention);
+        assert_eq!(plain.ownership, repo.ownership);
+    }
+
+    #[test]
+    fn category_inferred_from_remotes() {
+        let remote = |name: &str, url: &str, push_url: Option<&str>| Remote {
+            name: name.into(),
+            url: url.into(),
+            push_url: push_url.map(String::from),
+        };
+
+        let mut fork = make_repo("tool", "/code/tool");
+        fork.remotes = vec![
+            remote("origin", "git@github.com:someuser/tool.git", None),
+            remote("upstream", "https://github.com/acme/tool.git", None),
+        ];
+        classify_repo(&mut fork, &empty_config());
+        assert_eq!(fork.category, Some(Category::Fork));
+        assert_eq!(fork.origin_org.as_deref(), Some("acme"));
+
+        // Same repo over ssh for pushing is not a mirror
+        let mut clone = make_repo("tool", "/code/tool");
+        clone.remotes = vec![remote(
+            "origin",
+            "https://github.com/acme/tool.git",
+            Some("git@github.com:acme/tool.git"),
+        )];
+        classify_repo(&mut clone, &empty_config());
+        assert_eq!(clone.category, Some(Category::Clone));
+        assert!(clone.origin_org.is_none());
+
+        let mut mirror = make_repo("tool", "/code/tool");
+        mirror.remotes = vec![remote(
+            "origin",
+            "https://github.com/acme/tool.git",
+            Some("git@gitlab.com:backup/tool.git"),
+        )];
+        classify_repo(&mut mirror, &empty_config());
+        assert_eq!(mirror.category, Some(Category::Mirror));
+
+        // Two unrelated remotes say nothing; a rule's category is never overridden
+        let mut other = make_repo("tool", "/code/tool");
+        other.remotes = vec![
+            remote("origin", "git@github.com:someuser/tool.git", None),
+            remote("deploy", "git@heroku.com:tool-prod.git", None),
+        ];
+        classify_repo(&mut other, &empty_config());
+        assert!(other.category.is_none());
+
+        let mut config = empty_config();
+        config.classify.push(ClassifyRule {
+            match_criteria: ClassifyMatch::default(),
+            set: ClassifySet {
+                category: Some("origin".into()),
+                ..Default::default()
+            },
+            managed_by: None,
+            tags: vec![],
+        });
+        classify_repo(&mut fork, &config);
+        assert_eq!(fork.category, Some(Category::Fork));
+        let mut fresh = make_repo("tool", "/code/tool");
+        fresh.remotes = fork.remotes.clone();
+        classify_repo(&mut fresh, &config);
+        assert_eq!(fresh.category, Some(Category::Origin));
+        assert!(fresh.origin_org.is_none());
+    }
+
+    #[test]
+    fn fork_compares_against_primary_remote() {
+        let remote = |name: &str, url: &str| Remote {
+            name: name.into(),
+            url: url.into(),
+            push_url: None,
+        };
+        let mut fork = make_repo("tool", "/code/tool");
+        fork.remotes = vec![
+            remote("upstream", "https://github.com/acme/tool.git"),
+            remote("gh", "git@github.com:someuser/tool.git"),
+        ];
+        let mut config = empty_config();
+        config.scan.primary_remote = "gh".into();
+        classify_repo(&mut fork, &config);
+        assert_eq!(fork.category, Some(Category::Fork));
+        assert_eq!(fork.origin_org.as_deref(), Some("acme"));
+    }
 }
@@ -0,0 +1,296 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use serde_json::Value;
+
+use super::git_ops;
+use super::repo::{Repo, UpstreamInfo};
+use crate::config::types::IdentityConfig;
+use crate::error::{KissaError, Result};
+
+/// Longest rate-limit wait worth sitting through; a later reset gives up
+/// with `RateLimited` instead.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Retries of one request after rate-limit waits.
+const MAX_RETRIES: u32 = 3;
+
+/// Hosting platforms whose API `kissa enrich` can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    GitHub,
+    GitLab,
+}
+
+impl Platform {
+    /// The platform serving `host`: github.com, gitlab.com, or a self-hosted
+    /// GitLab whose host name has a `gitlab` label.
+    pub fn for_host(host: &str) -> Option<Platform> {
+        let host = host.to_ascii_lowercase();
+        if host == "github.com" {
+            Some(Platform::GitHub)
+        } else if host.split('.').any(|label| label == "gitlab") {
+            Some(Platform::GitLab)
+        } else {
+            None
+        }
+    }
+}
+
+/// The API project behind a repo's primary remote.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiTarget {
+    pub platform: Platform,
+    pub host: String,
+    /// `org/repo`, or the full `group/subgroup/repo` path on GitLab.
+    pub project: String,
+}
+
+impl ApiTarget {
+    /// Where the platform API for `repo` lives, from its `primary_remote`
+    /// (else first) remote; None for remotes on unrecognized hosts.
+    pub fn of(repo: &Repo, primary_remote: &str) -> Option<ApiTarget> {
+        let remote = git_ops::primary_remote(&repo.remotes, primary_remote)?;
+        let info = git_ops::parse_remote_org(&remote.url)?;
+        Some(ApiTarget {
+            platform: Platform::for_host(&info.platform)?,
+            project: format!("{}/{}", info.org, info.repo_name),
+            host: info.platform,
+        })
+    }
+
+    fn url(&self) -> String {
+        match self.platform {
+            Platform::GitHub => format!("https://api.github.com/repos/{}", self.project),
+            Platform::GitLab => format!(
+                "https://{}/api/v4/projects/{}",
+                self.host,
+                self.project.replace('/', "%2F")
+            ),
+        }
+    }
+}
+
+/// The token configured for `host` in `identity.api_tokens`, resolving a
+/// `$NAME` value from the environment. None when unset or empty.
+pub fn api_token(identity: &IdentityConfig, host: &str) -> Option<String> {
+    let value = identity
+        .api_tokens
+        .iter()
+        .find(|(h, _)| h.eq_ignore_ascii_case(host))
+        .map(|(_, v)| v.trim())?;
+    let token = match value.strip_prefix('$') {
+        Some(var) => std::env::var(var).ok()?,
+        None => value.to_string(),
+    };
+    (!token.is_empty()).then_some(token)
+}
+
+/// A blocking HTTP client for platform APIs, shared across requests.
+pub fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("kissa/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// Ask the platform API about one project. Rate-limited responses are
+/// retried after the wait the platform asks for, unless that is longer
+/// than `MAX_BACKOFF`, which fails with `RateLimited`.
+pub fn fetch_upstream(
+    agent: &ureq::Agent,
+    target: &ApiTarget,
+    token: &str,
+) -> Result<UpstreamInfo> {
+    let failed = |message: String| KissaError::Api {
+        host: target.host.clone(),
+        message,
+    };
+    let url = target.url();
+    let mut attempt = 0;
+    loop {
+        let request = match target.platform {
+            Platform::GitHub => agent
+                .get(&url)
+                .set("Accept", "application/vnd.github+json")
+                .set("Authorization", &format!("Bearer {}", token)),
+            Platform::GitLab => agent.get(&url).set("PRIVATE-TOKEN", token),
+        };
+        match request.call() {
+            Ok(response) => {
+                let body: Value = response.into_json().map_err(|e| failed(e.to_string()))?;
+                return parse_upstream(target.platform, &body)
+                    .ok_or_else(|| failed("unexpected response shape".into()));
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                if !is_rate_limited(status, response.header("x-ratelimit-remaining")) {
+                    return Err(failed(format!("{} for {}", status, target.project)));
+                }
+                let wait = rate_limit_wait(
+                    response.header("retry-after"),
+                    response.header("x-ratelimit-reset"),
+                    Utc::now().timestamp(),
+                    attempt,
+                );
+                match wait {
+                    Some(wait) if attempt < MAX_RETRIES => std::thread::sleep(wait),
+                    _ => {
+                        return Err(KissaError::RateLimited {
+                            host: target.host.clone(),
+                        });
+                    }
+                }
+                attempt += 1;
+            }
+            Err(e) => return Err(failed(e.to_string())),
+        }
+    }
+}
+
+/// 429 everywhere; GitHub also answers 403 once the hourly quota is spent.
+fn is_rate_limited(status: u16, remaining: Option<&str>) -> bool {
+    status == 429 || (status == 403 && remaining == Some("0"))
+}
+
+/// How long to wait before retrying: `Retry-After` seconds, else until the
+/// `x-ratelimit-reset` epoch, else exponential backoff from one second.
+/// None when that is longer than `MAX_BACKOFF`.
+fn rate_limit_wait(
+    retry_after: Option<&str>,
+    reset: Option<&str>,
+    now: i64,
+    attempt: u32,
+) -> Option<Duration> {
+    let parse = |v: Option<&str>| v.and_then(|v| v.trim().parse::<i64>().ok());
+    let secs = parse(retry_after)
+        .or_else(|| parse(reset).map(|reset| reset - now))
+        .unwrap_or(1 << attempt.min(5))
+        .max(1) as u64;
+    let wait = Duration::from_secs(secs);
+    (wait <= MAX_BACKOFF).then_some(wait)
+}
+
+/// Pick the fields kissa keeps out of a project document. Both platforms
+/// name them alike, except for the star count.
+fn parse_upstream(platform: Platform, body: &Value) -> Option<UpstreamInfo> {
+    let stars_key = match platform {
+        Platform::GitHub => "stargazers_count",
+        Platform::GitLab => "star_count",
+    };
+    let text = |key: &str| {
+        body[key]
+            .as_str()
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+    };
+    Some(UpstreamInfo {
+        description: text("description"),
+        archived: body["archived"].as_bool()?,
+        stars: body[stars_key]
+            .as_u64()
+            .map_or(0, |n| n.min(u32::MAX as u64) as u32),
+        default_branch: text("default_branch"),
+        fetched_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platforms_are_recognized_by_host() {
+        assert_eq!(Platform::for_host("GitHub.com"), Some(Platform::GitHub));
+        assert_eq!(Platform::for_host("gitlab.com"), Some(Platform::GitLab));
+        assert_eq!(
+            Platform::for_host("gitlab.acme.internal"),
+            Some(Platform::GitLab)
+        );
+        assert_eq!(Platform::for_host("mygitlabby.example"), None);
+        assert_eq!(Platform::for_host("bitbucket.org"), None);
+
+        let target = ApiTarget {
+            platform: Platform::GitLab,
+            host: "gitlab.com".into(),
+            project: "group/sub/api".into(),
+        };
+        assert_eq!(
+            target.url(),
+            "https://gitlab.com/api/v4/projects/group%2Fsub%2Fapi"
+        );
+    }
+
+    #[test]
+    fn tokens_resolve_from_config_or_environment() {
+        let mut identity = IdentityConfig::default();
+        identity
+            .api_tokens
+            .insert("github.com".into(), "ghp_literal".into());
+        identity
+            .api_tokens
+            .insert("gitlab.com".into(), "$KISSA_TEST_UNSET_TOKEN".into());
+        assert_eq!(
+            api_token(&identity, "GITHUB.COM").as_deref(),
+            Some("ghp_literal")
+        );
+        assert_eq!(api_token(&identity, "gitlab.com"), None);
+        assert_eq!(api_token(&identity, "example.com"), None);
+    }
+
+    #[test]
+    fn rate_limits_back_off_within_bounds() {
+        assert!(is_rate_limited(429, None));
+        assert!(is_rate_limited(403, Some("0")));
+        assert!(!is_rate_limited(403, Some("12")));
+        assert!(!is_rate_limited(404, None));
+
+        assert_eq!(
+            rate_limit_wait(Some("7"), None, 0, 0),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            rate_limit_wait(None, Some("1030"), 1000, 0),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_wait(None, None, 0, 2),
+            Some(Duration::from_secs(4))
+        );
+        // An hour until the quota resets isn't worth waiting for
+        assert_eq!(rate_limit_wait(None, Some("4600"), 1000, 0), None);
+    }
+
+    #[test]
+    fn project_documents_parse_per_platform() {
+        let github = serde_json::json!({
+            "description": "Finally herd your repos.",
+            "archived": false,
+            "stargazers_count": 42,
+            "default_branch": "main",
+        });
+        let info = parse_upstream(Platform::GitHub, &github).unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("Finally herd your repos.")
+        );
+        assert_eq!((info.archived, info.stars), (false, 42));
+        assert_eq!(info.default_branch.as_deref(), Some("main"));
+
+        let gitlab = serde_json::json!({
+            "description": "",
+            "archived": true,
+            "star_count": 3,
+            "default_branch": "trunk",
+        });
+        let info = parse_upstream(Platform::GitLab, &gitlab).unwrap();
+        assert_eq!(info.description, None);
+        assert_eq!((info.archived, info.stars), (true, 3));
+
+        assert!(
+            parse_upstream(
+                Platform::GitHub,
+                &serde_json::json!({"message": "Not Found"})
+            )
+            .is_none()
+        );
+    }
+}
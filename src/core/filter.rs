@@ -1,7 +1,8 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::git_ops::parse_remote_org;
-use super::repo::{Freshness, Ownership, Repo, RepoState};
+use super::repo::{Freshness, Intention, Ownership, Repo, RepoState};
 
 /// A composable set of repo filters. All fields are AND-combined.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -14,78 +15,341 @@ pub struct RepoFilter {
     pub ownership: Option<String>,
     pub intention: Option<String>,
     pub category: Option<String>,
+    /// Every tag must be present (AND).
     pub tags: Option<Vec<String>>,
+    /// At least one tag must be present (OR).
+    #[serde(default)]
+    pub tags_any: Option<Vec<String>>,
     pub path_prefix: Option<String>,
     pub has_remote: Option<bool>,
+    /// Some remote must carry exactly this name (e.g. "upstream").
+    #[serde(default)]
+    pub has_remote_named: Option<String>,
+    /// No remote may carry this name.
+    #[serde(default)]
+    pub missing_remote: Option<String>,
     pub name_contains: Option<String>,
+    pub branch_drift: Option<bool>,
+    /// Some(true) = at least one stale branch, Some(false) = none.
+    #[serde(default)]
+    pub has_stale_branches: Option<bool>,
+    /// Some(true) = HEAD signed, Some(false) = HEAD unsigned; repos with no HEAD never match.
+    #[serde(default)]
+    pub head_signed: Option<bool>,
+    /// Some(true) = checked out on a branch other than the default one.
+    #[serde(default)]
+    pub on_non_default_branch: Option<bool>,
+    /// Some(true) = no branch checked out: a detached HEAD (or a bare repo).
+    #[serde(default)]
+    pub detached: Option<bool>,
+    /// Some(true) = no commits yet (unborn HEAD), Some(false) = has commits.
+    #[serde(default)]
+    pub empty: Option<bool>,
+    /// Some(true) = inside another repo's working tree, not as a submodule.
+    #[serde(default)]
+    pub nested: Option<bool>,
+    /// Some(true) = vitals moved since the last full scan's snapshot.
+    #[serde(default)]
+    pub changed_since_scan: Option<bool>,
+    /// Never fetched, or last fetched before this instant.
+    #[serde(default)]
+    pub not_fetched_since: Option<DateTime<Utc>>,
     pub state: Option<RepoState>,
     pub managed_by: Option<String>,
     /// None = show all, Some(true) = only managed, Some(false) = only unmanaged
     pub show_managed: Option<bool>,
+    /// None = show all, Some(true) = only archived, Some(false) = hide archived
+    pub show_archived: Option<bool>,
+    /// None = show all, Some(true) = only lost, Some(false) = hide lost
+    #[serde(default)]
+    pub show_lost: Option<bool>,
 }
 
 impl RepoFilter {
+    /// Start an empty filter that matches every repo.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a builder step only when `value` is present.
+    /// Lets optional CLI/MCP inputs thread through a single chain.
+    pub fn maybe<T>(self, value: Option<T>, step: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(v) => step(self, v),
+            None => self,
+        }
+    }
+
+    pub fn dirty(mut self, dirty: bool) -> Self {
+        self.dirty = Some(dirty);
+        self
+    }
+
+    pub fn unpushed(mut self, unpushed: bool) -> Self {
+        self.unpushed = Some(unpushed);
+        self
+    }
+
+    pub fn orphan(mut self, orphan: bool) -> Self {
+        self.orphan = Some(orphan);
+        self
+    }
+
+    pub fn org(mut self, org: impl Into<String>) -> Self {
+        self.org = Some(org.into());
+        self
+    }
+
+    pub fn freshness(mut self, freshness: Freshness) -> Self {
+        self.freshness = Some(freshness);
+        self
+    }
+
+    pub fn ownership(mut self, ownership: impl Into<String>) -> Self {
+        self.ownership = Some(ownership.into());
+        self
+    }
+
+    pub fn intention(mut self, intention: impl Into<String>) -> Self {
+        self.intention = Some(intention.into());
+        self
+    }
+
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Require all of the given tags (AND).
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags = Some(tags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn tags_any<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags_any = Some(tags.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn has_remote(mut self, has_remote: bool) -> Self {
+        self.has_remote = Some(has_remote);
+        self
+    }
+
+    pub fn has_remote_named(mut self, name: impl Into<String>) -> Self {
+        self.has_remote_named = Some(name.into());
+        self
+    }
+
+    pub fn missing_remote(mut self, name: impl Into<String>) -> Self {
+        self.missing_remote = Some(name.into());
+        self
+    }
+
+    pub fn not_fetched_since(mut self, cutoff: DateTime<Utc>) -> Self {
+        self.not_fetched_since = Some(cutoff);
+        self
+    }
+
+    pub fn name_contains(mut self, name: impl Into<String>) -> Self {
+        self.name_contains = Some(name.into());
+        self
+    }
+
+    pub fn state(mut self, state: RepoState) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    pub fn managed_by(mut self, tool: impl Into<String>) -> Self {
+        self.managed_by = Some(tool.into());
+        self
+    }
+
+    pub fn show_managed(mut self, show: bool) -> Self {
+        self.show_managed = Some(show);
+        self
+    }
+
+    pub fn show_archived(mut self, show: bool) -> Self {
+        self.show_archived = Some(show);
+        self
+    }
+
+    pub fn show_lost(mut self, show: bool) -> Self {
+        self.show_lost = Some(show);
+        self
+    }
+
+    pub fn branch_drift(mut self, drift: bool) -> Self {
+        self.branch_drift = Some(drift);
+        self
+    }
+
+    pub fn has_stale_branches(mut self, stale: bool) -> Self {
+        self.has_stale_branches = Some(stale);
+        self
+    }
+
+    pub fn head_signed(mut self, signed: bool) -> Self {
+        self.head_signed = Some(signed);
+        self
+    }
+
+    pub fn on_non_default_branch(mut self, off: bool) -> Self {
+        self.on_non_default_branch = Some(off);
+        self
+    }
+
+    pub fn detached(mut self, detached: bool) -> Self {
+        self.detached = Some(detached);
+        self
+    }
+
+    pub fn empty(mut self, empty: bool) -> Self {
+        self.empty = Some(empty);
+        self
+    }
+
+    pub fn nested(mut self, nested: bool) -> Self {
+        self.nested = Some(nested);
+        self
+    }
+
+    pub fn changed_since_scan(mut self, changed: bool) -> Self {
+        self.changed_since_scan = Some(changed);
+        self
+    }
+
     /// Test whether a Repo matches this filter in-memory.
     pub fn matches(&self, repo: &Repo) -> bool {
-        if let Some(dirty) = self.dirty {
-            if repo.dirty != dirty {
-                return false;
-            }
+        if let Some(dirty) = self.dirty
+            && repo.dirty != dirty
+        {
+            return false;
         }
-        if let Some(true) = self.unpushed {
-            if repo.ahead == 0 {
-                return false;
-            }
+        if let Some(true) = self.unpushed
+            && repo.ahead == 0
+        {
+            return false;
         }
-        if let Some(true) = self.orphan {
-            if !repo.remotes.is_empty() {
-                return false;
-            }
+        if let Some(true) = self.orphan
+            && !repo.remotes.is_empty()
+        {
+            return false;
         }
-        if let Some(ref freshness) = self.freshness {
-            if repo.freshness != *freshness {
-                return false;
-            }
+        if let Some(ref freshness) = self.freshness
+            && repo.freshness != *freshness
+        {
+            return false;
         }
-        if let Some(ref prefix) = self.path_prefix {
-            if !repo.path.to_string_lossy().starts_with(prefix.as_str()) {
-                return false;
-            }
+        if let Some(ref prefix) = self.path_prefix
+            && !repo.path.to_string_lossy().starts_with(prefix.as_str())
+        {
+            return false;
         }
-        if let Some(has_remote) = self.has_remote {
-            if repo.remotes.is_empty() == has_remote {
-                return false;
-            }
+        if let Some(has_remote) = self.has_remote
+            && repo.remotes.is_empty() == has_remote
+        {
+            return false;
         }
-        if let Some(ref name) = self.name_contains {
-            if !repo.name.to_lowercase().contains(&name.to_lowercase()) {
-                return false;
-            }
+        if let Some(ref name) = self.has_remote_named
+            && !repo.remotes.iter().any(|r| r.name == *name)
+        {
+            return false;
         }
-        if let Some(ref state) = self.state {
-            if repo.state != *state {
-                return false;
-            }
+        if let Some(ref name) = self.missing_remote
+            && repo.remotes.iter().any(|r| r.name == *name)
+        {
+            return false;
         }
-        if let Some(ref org) = self.org {
-            if !repo_matches_org(repo, org) {
-                return false;
-            }
+        if let Some(ref name) = self.name_contains
+            && !repo.name.to_lowercase().contains(&name.to_lowercase())
+        {
+            return false;
         }
-        if let Some(ref ownership) = self.ownership {
-            if !repo_matches_ownership(repo, ownership) {
-                return false;
-            }
+        if let Some(drift) = self.branch_drift
+            && repo.default_branch_drift != drift
+        {
+            return false;
         }
-        if let Some(ref intention) = self.intention {
-            if !repo_matches_enum_str(&repo.intention, intention) {
-                return false;
-            }
+        if let Some(stale) = self.has_stale_branches
+            && (repo.stale_branch_count > 0) != stale
+        {
+            return false;
         }
-        if let Some(ref category) = self.category {
-            if !repo_matches_enum_str(&repo.category, category) {
-                return false;
-            }
+        if let Some(signed) = self.head_signed
+            && repo.head_signed != Some(signed)
+        {
+            return false;
+        }
+        if let Some(off) = self.on_non_default_branch
+            && repo.off_default_branch() != off
+        {
+            return false;
+        }
+        if let Some(detached) = self.detached
+            && repo.current_branch.is_none() != detached
+        {
+            return false;
+        }
+        if let Some(empty) = self.empty
+            && repo.is_empty != empty
+        {
+            return false;
+        }
+        if let Some(nested) = self.nested
+            && (repo.nested_in.is_some() || repo.contains_nested) != nested
+        {
+            return false;
+        }
+        if let Some(changed) = self.changed_since_scan
+            && repo.scan_changes().is_empty() == changed
+        {
+            return false;
+        }
+        if let Some(cutoff) = self.not_fetched_since
+            && repo.last_fetched.is_some_and(|t| t >= cutoff)
+        {
+            return false;
+        }
+        if let Some(ref state) = self.state
+            && repo.state != *state
+        {
+            return false;
+        }
+        if let Some(ref org) = self.org
+            && !repo_matches_org(repo, org)
+        {
+            return false;
+        }
+        if let Some(ref ownership) = self.ownership
+            && !repo_matches_ownership(repo, ownership)
+        {
+            return false;
+        }
+        if let Some(ref intention) = self.intention
+            && !repo_matches_enum_str(&repo.intention, intention)
+        {
+            return false;
+        }
+        if let Some(ref category) = self.category
+            && !repo_matches_enum_str(&repo.category, category)
+        {
+            return false;
         }
         if let Some(ref tags) = self.tags {
             // All specified tags must be present
@@ -95,6 +359,13 @@ impl RepoFilter {
                 }
             }
         }
+        if let Some(ref any) = self.tags_any
+            && !any
+                .iter()
+                .any(|tag| repo.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        {
+            return false;
+        }
         if let Some(ref mb) = self.managed_by {
             match &repo.managed_by {
                 Some(rmb) => {
@@ -111,9 +382,38 @@ impl RepoFilter {
                 return false;
             }
         }
+        if let Some(show) = self.show_archived {
+            let is_archived = repo.intention == Some(Intention::Archived);
+            if show != is_archived {
+                return false;
+            }
+        }
+        if let Some(show) = self.show_lost
+            && show != (repo.state == RepoState::Lost)
+        {
+            return false;
+        }
         true
     }
 
+    /// Returns true if any set field can only be evaluated by `matches()`,
+    /// i.e. the index can't answer this filter with SQL alone.
+    pub fn needs_in_memory_match(&self) -> bool {
+        self.unpushed.is_some()
+            || self.orphan.is_some()
+            || self.org.is_some()
+            || self.ownership.is_some()
+            || self.intention.is_some()
+            || self.category.is_some()
+            || self.tags.is_some()
+            || self.tags_any.is_some()
+            || self.path_prefix.is_some()
+            || self.has_remote.is_some()
+            || self.has_remote_named.is_some()
+            || self.missing_remote.is_some()
+            || self.name_contains.is_some()
+    }
+
     /// Returns true if no filters are set.
     pub fn is_empty(&self) -> bool {
         self.dirty.is_none()
@@ -125,24 +425,38 @@ impl RepoFilter {
             && self.intention.is_none()
             && self.category.is_none()
             && self.tags.is_none()
+            && self.tags_any.is_none()
             && self.path_prefix.is_none()
             && self.has_remote.is_none()
+            && self.has_remote_named.is_none()
+            && self.missing_remote.is_none()
             && self.name_contains.is_none()
+            && self.branch_drift.is_none()
+            && self.has_stale_branches.is_none()
+            && self.head_signed.is_none()
+            && self.on_non_default_branch.is_none()
+            && self.detached.is_none()
+            && self.empty.is_none()
+            && self.nested.is_none()
+            && self.changed_since_scan.is_none()
+            && self.not_fetched_since.is_none()
             && self.state.is_none()
             && self.managed_by.is_none()
             && self.show_managed.is_none()
+            && self.show_archived.is_none()
+            && self.show_lost.is_none()
     }
 }
 
 /// Check if any remote's org matches the filter value.
 fn repo_matches_org(repo: &Repo, org_filter: &str) -> bool {
     repo.remotes.iter().any(|remote| {
-        parse_remote_org(&remote.url)
-            .is_some_and(|info| info.org.eq_ignore_ascii_case(org_filter))
+        parse_remote_org(&remote.url).is_some_and(|info| info.org_matches(org_filter))
     })
 }
 
-/// Check ownership classification matches filter string.
+/// Check ownership classification matches filter string. Work labels may be
+/// globs, case-insensitive: `work:*` is any work repo, `work:acme-*` a prefix.
 /// Accepts: "personal", "work:label", "community", "third-party", "local"
 fn repo_matches_ownership(repo: &Repo, filter: &str) -> bool {
     let Some(ref ownership) = repo.ownership else {
@@ -152,15 +466,20 @@ fn repo_matches_ownership(repo: &Repo, filter: &str) -> bool {
         Ownership::Personal => filter.eq_ignore_ascii_case("personal"),
         Ownership::Work { label } => {
             if let Some(work_label) = filter.strip_prefix("work:") {
-                label.eq_ignore_ascii_case(work_label)
+                let options = glob::MatchOptions {
+                    case_sensitive: false,
+                    ..Default::default()
+                };
+                glob::Pattern::new(work_label)
+                    .map(|p| p.matches_with(label, options))
+                    .unwrap_or_else(|_| label.eq_ignore_ascii_case(work_label))
             } else {
                 filter.eq_ignore_ascii_case("work")
             }
         }
         Ownership::Community => filter.eq_ignore_ascii_case("community"),
         Ownership::ThirdParty => {
-            filter.eq_ignore_ascii_case("third-party")
-                || filter.eq_ignore_ascii_case("thirdparty")
+            filter.eq_ignore_ascii_case("third-party") || filter.eq_ignore_ascii_case("thirdparty")
         }
         Ownership::Local => filter.eq_ignore_ascii_case("local"),
     }
@@ -185,44 +504,84 @@ mod tests {
     use super::*;
     use crate::core::repo::*;
     use chrono::Utc;
-    use std::path::PathBuf;
 
     fn make_repo(name: &str) -> Repo {
         Repo {
             id: 1,
-            name: name.to_string(),
-            path: PathBuf::from(format!("/home/user/code/{}", name)),
-            state: RepoState::Active,
             remotes: vec![Remote {
                 name: "origin".into(),
                 url: "git@github.com:initech/api-gateway.git".into(),
                 push_url: None,
             }],
-            default_branch: Some("main".into()),
-            current_branch: Some("main".into()),
-            branch_count: 1,
-            stale_branch_count: 0,
-            dirty: false,
-            staged: false,
-            untracked: false,
-            ahead: 0,
-            behind: 0,
-            last_commit: Some(Utc::now()),
-            last_verified: Some(Utc::now()),
-            first_seen: Utc::now(),
-            freshness: Freshness::Active,
             category: Some(Category::Origin),
             ownership: Some(Ownership::Work {
                 label: "initech".into(),
             }),
             intention: Some(Intention::Developing),
-            managed_by: None,
             tags: vec!["rust".into(), "work".into()],
-            project: None,
-            role: None,
+            ..Repo::test_default(name, format!("/home/user/code/{}", name))
         }
     }
 
+    #[test]
+    fn builders_set_fields() {
+        let filter = RepoFilter::new()
+            .dirty(true)
+            .unpushed(true)
+            .orphan(false)
+            .org("acme")
+            .freshness(Freshness::Stale)
+            .ownership("work:acme")
+            .intention("developing")
+            .category("fork")
+            .tags(["rust", "cli"])
+            .path_prefix("/code")
+            .has_remote(true)
+            .name_contains("api")
+            .state(RepoState::Lost)
+            .managed_by("cargo")
+            .show_managed(false)
+            .show_archived(true);
+
+        assert_eq!(filter.dirty, Some(true));
+        assert_eq!(filter.unpushed, Some(true));
+        assert_eq!(filter.orphan, Some(false));
+        assert_eq!(filter.org.as_deref(), Some("acme"));
+        assert_eq!(filter.freshness, Some(Freshness::Stale));
+        assert_eq!(filter.ownership.as_deref(), Some("work:acme"));
+        assert_eq!(filter.intention.as_deref(), Some("developing"));
+        assert_eq!(filter.category.as_deref(), Some("fork"));
+        assert_eq!(
+            filter.tags,
+            Some(vec!["rust".to_string(), "cli".to_string()])
+        );
+        assert_eq!(filter.path_prefix.as_deref(), Some("/code"));
+        assert_eq!(filter.has_remote, Some(true));
+        assert_eq!(filter.name_contains.as_deref(), Some("api"));
+        assert_eq!(filter.state, Some(RepoState::Lost));
+        assert_eq!(filter.managed_by.as_deref(), Some("cargo"));
+        assert_eq!(filter.show_managed, Some(false));
+        assert_eq!(filter.show_archived, Some(true));
+    }
+
+    #[test]
+    fn maybe_skips_none() {
+        let filter = RepoFilter::new()
+            .maybe(None::<String>, RepoFilter::org)
+            .maybe(Some(true), RepoFilter::dirty);
+        assert!(filter.org.is_none());
+        assert_eq!(filter.dirty, Some(true));
+    }
+
+    #[test]
+    fn builder_matches_struct_literal() {
+        let built = RepoFilter::new().dirty(true).org("initech");
+        let mut repo = make_repo("test");
+        assert!(!built.matches(&repo));
+        repo.dirty = true;
+        assert!(built.matches(&repo));
+    }
+
     #[test]
     fn empty_filter_matches_everything() {
         let filter = RepoFilter::default();
@@ -283,6 +642,17 @@ mod tests {
         assert!(!filter_wrong.matches(&make_repo("test")));
     }
 
+    #[test]
+    fn org_filter_nested_groups() {
+        let mut repo = make_repo("svc");
+        repo.remotes[0].url = "git@gitlab.com:acme/platform/svc.git".into();
+
+        for org in ["acme/platform", "acme", "platform"] {
+            assert!(RepoFilter::new().org(org).matches(&repo), "{}", org);
+        }
+        assert!(!RepoFilter::new().org("svc").matches(&repo));
+    }
+
     #[test]
     fn ownership_filter() {
         let repo = make_repo("test"); // ownership = Work { label: "initech" }
@@ -304,6 +674,15 @@ mod tests {
             ..Default::default()
         };
         assert!(!filter_wrong.matches(&repo));
+
+        for pattern in ["work:*", "work:Init*", "work:*tech"] {
+            assert!(
+                RepoFilter::new().ownership(pattern).matches(&repo),
+                "{}",
+                pattern
+            );
+        }
+        assert!(!RepoFilter::new().ownership("work:acme*").matches(&repo));
     }
 
     #[test]
@@ -343,6 +722,42 @@ mod tests {
         assert!(filter.matches(&repo)); // dirty + initech
     }
 
+    #[test]
+    fn archived_filter() {
+        let mut archived = make_repo("old");
+        archived.intention = Some(Intention::Archived);
+        let active = make_repo("new");
+
+        let hide = RepoFilter {
+            show_archived: Some(false),
+            ..Default::default()
+        };
+        assert!(!hide.matches(&archived));
+        assert!(hide.matches(&active));
+
+        let only = RepoFilter {
+            show_archived: Some(true),
+            ..Default::default()
+        };
+        assert!(only.matches(&archived));
+        assert!(!only.matches(&active));
+    }
+
+    #[test]
+    fn lost_filter() {
+        let mut lost = make_repo("gone");
+        lost.state = RepoState::Lost;
+        let present = make_repo("here");
+
+        let hide = RepoFilter::new().show_lost(false);
+        assert!(!hide.matches(&lost));
+        assert!(hide.matches(&present));
+
+        let only = RepoFilter::new().show_lost(true);
+        assert!(only.matches(&lost));
+        assert!(!only.matches(&present));
+    }
+
     #[test]
     fn name_contains_case_insensitive() {
         let filter = RepoFilter {
@@ -352,4 +767,118 @@ mod tests {
         assert!(filter.matches(&make_repo("api-gateway")));
         assert!(!filter.matches(&make_repo("frontend")));
     }
+
+    #[test]
+    fn branch_drift_filter() {
+        let filter = RepoFilter::new().branch_drift(true);
+        let mut drifted = make_repo("old-clone");
+        drifted.default_branch_drift = true;
+        assert!(filter.matches(&drifted));
+        assert!(!filter.matches(&make_repo("fresh-clone")));
+        assert!(!filter.needs_in_memory_match());
+    }
+
+    #[test]
+    fn stale_branches_filter() {
+        let filter = RepoFilter::new().has_stale_branches(true);
+        let mut crufty = make_repo("crufty");
+        crufty.stale_branch_count = 4;
+        assert!(filter.matches(&crufty));
+        assert!(!filter.matches(&make_repo("tidy")));
+        assert!(
+            RepoFilter::new()
+                .has_stale_branches(false)
+                .matches(&make_repo("tidy"))
+        );
+        assert!(!filter.needs_in_memory_match());
+        assert!(!filter.is_empty());
+    }
+
+    #[test]
+    fn off_default_and_detached_are_distinct() {
+        let off = RepoFilter::new().on_non_default_branch(true);
+        let detached = RepoFilter::new().detached(true);
+
+        let mut feature = make_repo("feature");
+        feature.default_branch = Some("main".into());
+        feature.current_branch = Some("wip/parser".into());
+        let mut on_main = feature.clone();
+        on_main.current_branch = Some("main".into());
+        let mut headless = feature.clone();
+        headless.current_branch = None;
+
+        assert!(off.matches(&feature));
+        assert!(!off.matches(&on_main));
+        assert!(!off.matches(&headless));
+        assert!(detached.matches(&headless));
+        assert!(!detached.matches(&feature));
+        assert!(
+            RepoFilter::new()
+                .on_non_default_branch(false)
+                .matches(&on_main)
+        );
+        assert!(!off.needs_in_memory_match());
+        assert!(!detached.is_empty());
+    }
+
+    #[test]
+    fn tags_any_is_or_and_tags_is_and() {
+        let mut rust = make_repo("cli");
+        rust.tags = vec!["rust".into(), "cli".into()];
+        let mut go = make_repo("svc");
+        go.tags = vec!["Go".into()];
+        let mut docs = make_repo("docs");
+        docs.tags.clear();
+
+        let any = RepoFilter::new().tags_any(["rust", "go", "python"]);
+        assert!(any.matches(&rust));
+        assert!(any.matches(&go));
+        assert!(!any.matches(&docs));
+
+        let all = RepoFilter::new().tags(["rust", "go"]);
+        assert!(!all.matches(&rust));
+        assert!(!all.matches(&go));
+
+        let both = RepoFilter::new().tags(["cli"]).tags_any(["rust", "go"]);
+        assert!(both.matches(&rust));
+        assert!(!both.matches(&go));
+    }
+
+    #[test]
+    fn remote_name_filters() {
+        let mut fork = make_repo("fork");
+        fork.remotes.push(Remote {
+            name: "upstream".into(),
+            url: "git@github.com:upstream/fork.git".into(),
+            push_url: None,
+        });
+        let plain = make_repo("plain");
+
+        let has = RepoFilter::new().has_remote_named("upstream");
+        assert!(has.matches(&fork));
+        assert!(!has.matches(&plain));
+        assert!(has.needs_in_memory_match());
+
+        let missing = RepoFilter::new().missing_remote("upstream");
+        assert!(!missing.matches(&fork));
+        assert!(missing.matches(&plain));
+        assert!(!missing.is_empty());
+    }
+
+    #[test]
+    fn not_fetched_since_includes_never_fetched() {
+        let now = Utc::now();
+        let filter = RepoFilter::new().not_fetched_since(now - chrono::Duration::days(30));
+
+        let never = make_repo("never");
+        let mut old = make_repo("old");
+        old.last_fetched = Some(now - chrono::Duration::days(90));
+        let mut fresh = make_repo("fresh");
+        fresh.last_fetched = Some(now - chrono::Duration::days(2));
+
+        assert!(filter.matches(&never));
+        assert!(filter.matches(&old));
+        assert!(!filter.matches(&fresh));
+        assert!(!filter.needs_in_memory_match());
+    }
 }
@@ -1,59 +1,134 @@
+use std::collections::HashMap;
 use std::path::Path;
 
-use chrono::{DateTime, TimeZone, Utc};
-use git2::{BranchType, Repository, StatusOptions};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use git2::{BranchType, Delta, DiffOptions, Repository, StatusOptions};
 
+use crate::config::types::{ScanConfig, UntrackedMode};
 use crate::error::{KissaError, Result};
 
-use super::repo::{Remote, RemoteInfo, RepoVitals};
+use super::repo::{ChangeKind, FileChange, LogMatch, Remote, RemoteInfo, RepoVitals};
 
-/// Extract full vitals from a git repo at the given path.
-pub fn extract_vitals(path: &Path) -> Result<RepoVitals> {
+/// Extract full vitals from a git repo at the given path. `scan.untracked`
+/// controls whether untracked files are looked for at all,
+/// `scan.compute_status` whether the working tree is read at all, and
+/// `scan.primary_remote` which remote names the repo and backs up the
+/// tracking branch for ahead/behind.
+pub fn extract_vitals(path: &Path, scan: &ScanConfig) -> Result<RepoVitals> {
     let repo = Repository::open(path).map_err(|e| KissaError::Git {
         path: path.to_path_buf(),
         source: e,
     })?;
 
     let remotes = extract_remotes(&repo);
-    let name = infer_name(path, &remotes);
+    let name = infer_name(path, &remotes, &scan.primary_remote);
     let is_bare = repo.is_bare();
+    let is_empty = head_is_unborn(&repo);
 
     let default_branch = detect_default_branch(&repo);
+    let default_branch_drift = primary_remote(&remotes, &scan.primary_remote)
+        .is_some_and(|remote| default_branch_drift(&repo, &remote.name));
+    let head_signed = head_signed(&repo);
+    let last_fetched = last_fetched(&repo);
     let current_branch = if is_bare {
         None
     } else {
         repo.head()
             .ok()
             .and_then(|h| h.shorthand().map(String::from))
+            .or_else(|| is_empty.then(|| unborn_branch(&repo)).flatten())
     };
 
-    let (branch_count, stale_branch_count) = count_branches(&repo);
-    let (dirty, staged, untracked) = if is_bare {
+    let (branch_count, stale_branch_count, branches_truncated) =
+        count_branches(&repo, scan.max_branches);
+    // A bare repo has no working tree, so its (clean) status is always known
+    let status_checked = is_bare || scan.compute_status;
+    let (dirty, staged, untracked) = if is_bare || !scan.compute_status {
         (false, false, false)
     } else {
-        working_tree_status(&repo)
+        working_tree_status(&repo, scan.untracked)
     };
 
-    let (ahead, behind) = ahead_behind(&repo);
+    let (ahead, behind) = ahead_behind(&repo, &scan.primary_remote);
     let last_commit = last_commit_time(&repo);
+    let last_commit_offset = last_commit.map(|dt| dt.offset().local_minus_utc() / 60);
+    let commits_last_30d = commits_since(&repo, Utc::now() - chrono::Duration::days(30));
 
     Ok(RepoVitals {
         name,
         remotes,
         default_branch,
         current_branch,
+        default_branch_drift,
+        head_signed,
+        last_fetched,
+        commits_last_30d,
         branch_count,
         stale_branch_count,
+        branches_truncated,
         dirty,
         staged,
         untracked,
+        status_checked,
         ahead,
         behind,
-        last_commit,
+        last_commit: last_commit.map(|dt| dt.to_utc()),
+        last_commit_offset,
         is_bare,
+        is_empty,
     })
 }
 
+/// File-level changes against HEAD: the working tree (including staged
+/// changes) by default, or only the index when `staged` is set.
+/// Untracked files are not included. An unborn HEAD diffs against an empty tree.
+pub fn diff_summary(path: &Path, staged: bool) -> Result<Vec<FileChange>> {
+    let git_err = |e| KissaError::Git {
+        path: path.to_path_buf(),
+        source: e,
+    };
+    let repo = Repository::open(path).map_err(git_err)?;
+    if repo.is_bare() {
+        return Err(KissaError::Git {
+            path: path.to_path_buf(),
+            source: git2::Error::from_str("bare repository has no working tree"),
+        });
+    }
+
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+    let mut opts = DiffOptions::new();
+    let mut diff = if staged {
+        repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))
+    } else {
+        repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))
+    }
+    .map_err(git_err)?;
+    diff.find_similar(None).map_err(git_err)?;
+
+    Ok(diff
+        .deltas()
+        .filter_map(|delta| {
+            let kind = match delta.status() {
+                Delta::Added => ChangeKind::Added,
+                Delta::Modified => ChangeKind::Modified,
+                Delta::Deleted => ChangeKind::Deleted,
+                Delta::Renamed => ChangeKind::Renamed,
+                Delta::Typechange => ChangeKind::TypeChange,
+                _ => return None,
+            };
+            let file = if kind == ChangeKind::Deleted {
+                delta.old_file()
+            } else {
+                delta.new_file()
+            };
+            Some(FileChange {
+                kind,
+                path: file.path()?.to_path_buf(),
+            })
+        })
+        .collect())
+}
+
 /// Extract all remotes from a repository.
 fn extract_remotes(repo: &Repository) -> Vec<Remote> {
     let Ok(remote_names) = repo.remotes() else {
@@ -76,27 +151,78 @@ fn extract_remotes(repo: &Repository) -> Vec<Remote> {
 /// Detect the default branch (HEAD target or common names).
 fn detect_default_branch(repo: &Repository) -> Option<String> {
     // Try HEAD's target
-    if let Ok(head) = repo.head() {
-        if let Some(name) = head.shorthand() {
-            return Some(name.to_string());
-        }
+    if let Ok(head) = repo.head()
+        && let Some(name) = head.shorthand()
+    {
+        return Some(name.to_string());
     }
     // Try common default branch names
     for name in &["main", "master", "develop", "trunk"] {
-        if repo
-            .find_branch(name, BranchType::Local)
-            .is_ok()
-        {
+        if repo.find_branch(name, BranchType::Local).is_ok() {
             return Some(name.to_string());
         }
     }
     None
 }
 
-/// Count total local branches and stale branches (> 90 days since last commit).
-fn count_branches(repo: &Repository) -> (u32, u32) {
+/// The branch `<remote>/HEAD` points at, read offline from the symref that
+/// `git clone` and `git remote set-head` maintain.
+fn remote_default_branch(repo: &Repository, remote: &str) -> Option<String> {
+    let prefix = format!("refs/remotes/{}/", remote);
+    let head = repo.find_reference(&format!("{}HEAD", prefix)).ok()?;
+    let target = head.symbolic_target()?;
+    target.strip_prefix(&prefix).map(String::from)
+}
+
+/// Whether `remote`'s default branch has no local branch of the same name,
+/// typically because the remote renamed `master` to `main` after cloning.
+/// Repos without a `<remote>/HEAD` symref (or without local branches) never drift.
+fn default_branch_drift(repo: &Repository, remote: &str) -> bool {
+    let Some(remote_default) = remote_default_branch(repo, remote) else {
+        return false;
+    };
+    let has_local_branches = repo
+        .branches(Some(BranchType::Local))
+        .is_ok_and(|mut branches| branches.next().is_some());
+    has_local_branches
+        && repo
+            .find_branch(&remote_default, BranchType::Local)
+            .is_err()
+}
+
+/// Whether the HEAD commit has a signature attached. Presence only: checking
+/// it against a keyring needs gpg/ssh-keygen, which kissa doesn't shell out to.
+fn head_signed(repo: &Repository) -> Option<bool> {
+    let oid = repo.head().ok()?.peel_to_commit().ok()?.id();
+    Some(repo.extract_signature(&oid, None).is_ok())
+}
+
+/// When the repo was last fetched, from the mtime of `FETCH_HEAD`, which git
+/// rewrites on every fetch or pull. None if it has never been fetched.
+fn last_fetched(repo: &Repository) -> Option<DateTime<Utc>> {
+    let modified = std::fs::metadata(repo.commondir().join("FETCH_HEAD"))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(DateTime::<Utc>::from(modified))
+}
+
+/// Whether `inner` is one of the submodules registered in the repo at `outer`.
+pub fn is_submodule(outer: &Path, inner: &Path) -> bool {
+    let Ok(relative) = inner.strip_prefix(outer) else {
+        return false;
+    };
+    Repository::open(outer).is_ok_and(|repo| {
+        repo.submodules()
+            .is_ok_and(|subs| subs.iter().any(|sub| sub.path() == relative))
+    })
+}
+
+/// Count local branches and those without commits for 90 days:
+/// (total, stale, truncated). Stops after `max` branches (0 = no cap),
+/// setting `truncated`, since each branch costs a commit lookup.
+fn count_branches(repo: &Repository, max: u32) -> (u32, u32, bool) {
     let Ok(branches) = repo.branches(Some(BranchType::Local)) else {
-        return (0, 0);
+        return (0, 0, false);
     };
 
     let mut total = 0u32;
@@ -104,24 +230,29 @@ fn count_branches(repo: &Repository) -> (u32, u32) {
     let ninety_days_ago = Utc::now() - chrono::Duration::days(90);
 
     for branch in branches.flatten() {
+        if max > 0 && total == max {
+            return (total, stale, true);
+        }
         total += 1;
         let (branch_ref, _) = branch;
         if let Ok(commit) = branch_ref.get().peel_to_commit() {
             let time = commit.time();
-            if let Some(dt) = Utc.timestamp_opt(time.seconds(), 0).single() {
-                if dt < ninety_days_ago {
-                    stale += 1;
-                }
+            if let Some(dt) = Utc.timestamp_opt(time.seconds(), 0).single()
+                && dt < ninety_days_ago
+            {
+                stale += 1;
             }
         }
     }
-    (total, stale)
+    (total, stale, false)
 }
 
-/// Check working tree status: (dirty, staged, untracked).
-fn working_tree_status(repo: &Repository) -> (bool, bool, bool) {
+/// Check working tree status: (dirty, staged, untracked). Untracked files
+/// never make a repo dirty; they only set the separate `untracked` flag.
+fn working_tree_status(repo: &Repository, mode: UntrackedMode) -> (bool, bool, bool) {
     let mut opts = StatusOptions::new();
-    opts.include_untracked(true);
+    opts.include_untracked(mode != UntrackedMode::No)
+        .recurse_untracked_dirs(mode == UntrackedMode::All);
 
     let Ok(statuses) = repo.statuses(Some(&mut opts)) else {
         return (false, false, false);
@@ -161,8 +292,9 @@ fn working_tree_status(repo: &Repository) -> (bool, bool, bool) {
     (dirty, staged, untracked)
 }
 
-/// Compute ahead/behind counts relative to upstream tracking branch.
-fn ahead_behind(repo: &Repository) -> (u32, u32) {
+/// Compute ahead/behind counts for HEAD's branch against its upstream
+/// tracking branch, else against its namesake on `primary_remote`.
+fn ahead_behind(repo: &Repository, primary_remote: &str) -> (u32, u32) {
     let Ok(head) = repo.head() else {
         return (0, 0);
     };
@@ -170,22 +302,29 @@ fn ahead_behind(repo: &Repository) -> (u32, u32) {
         Some(oid) => oid,
         None => return (0, 0),
     };
+    let branch_name = head.shorthand().unwrap_or("");
 
-    // Find the upstream tracking branch
-    let Ok(branch) = repo.find_branch(
-        head.shorthand().unwrap_or(""),
-        BranchType::Local,
-    ) else {
-        return (0, 0);
-    };
-
-    let Ok(upstream) = branch.upstream() else {
-        return (0, 0);
-    };
-
-    let upstream_oid = match upstream.get().target() {
+    let tracking = repo
+        .find_branch(branch_name, BranchType::Local)
+        .and_then(|branch| branch.upstream())
+        .ok()
+        .and_then(|upstream| upstream.get().target());
+    // Fall back to the primary remote's branch of the same name
+    let upstream_oid = match tracking {
         Some(oid) => oid,
-        None => return (0, 0),
+        None => {
+            let Some(oid) = repo
+                .find_branch(
+                    &format!("{}/{}", primary_remote, branch_name),
+                    BranchType::Remote,
+                )
+                .ok()
+                .and_then(|b| b.get().target())
+            else {
+                return (0, 0);
+            };
+            oid
+        }
     };
 
     repo.graph_ahead_behind(local_oid, upstream_oid)
@@ -193,56 +332,199 @@ fn ahead_behind(repo: &Repository) -> (u32, u32) {
         .unwrap_or((0, 0))
 }
 
-/// Get the timestamp of the most recent commit on HEAD.
-fn last_commit_time(repo: &Repository) -> Option<DateTime<Utc>> {
+/// True when HEAD points at a branch that has no commits yet, as in a
+/// freshly `git init`ed repo.
+fn head_is_unborn(repo: &Repository) -> bool {
+    matches!(repo.head(), Err(ref e) if e.code() == git2::ErrorCode::UnbornBranch)
+}
+
+/// The branch an unborn HEAD will create on first commit.
+fn unborn_branch(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    head.symbolic_target()?
+        .strip_prefix("refs/heads/")
+        .map(String::from)
+}
+
+/// Get the timestamp of the most recent commit on HEAD, in the committer's
+/// own timezone.
+fn last_commit_time(repo: &Repository) -> Option<DateTime<FixedOffset>> {
     let head = repo.head().ok()?;
     let commit = head.peel_to_commit().ok()?;
     let time = commit.time();
-    Utc.timestamp_opt(time.seconds(), 0).single()
+    FixedOffset::east_opt(time.offset_minutes() * 60)?
+        .timestamp_opt(time.seconds(), 0)
+        .single()
 }
 
-/// Infer the repo name from path or remote URL.
-pub fn infer_name(path: &Path, remotes: &[Remote]) -> String {
-    // Prefer remote URL repo name, fall back to directory name
-    if let Some(remote) = remotes.iter().find(|r| r.name == "origin") {
-        if let Some(info) = parse_remote_org(&remote.url) {
-            return info.repo_name;
+/// Upper bound on commits walked per repo, so a monorepo with a busy month
+/// can't stall a scan.
+const MAX_ACTIVITY_WALK: u32 = 10_000;
+
+/// Count commits reachable from HEAD with a commit time at or after `since`.
+/// Walks newest-first and stops at the first older commit, so the cost is
+/// proportional to recent activity, not history size. None without a HEAD.
+fn commits_since(repo: &Repository, since: DateTime<Utc>) -> Option<u32> {
+    let head = repo.head().ok()?.peel_to_commit().ok()?;
+    let mut walk = repo.revwalk().ok()?;
+    walk.set_sorting(git2::Sort::TIME).ok()?;
+    walk.push(head.id()).ok()?;
+
+    let cutoff = since.timestamp();
+    let mut count = 0;
+    for oid in walk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        if commit.time().seconds() < cutoff || count >= MAX_ACTIVITY_WALK {
+            break;
         }
+        count += 1;
+    }
+    Some(count)
+}
+
+/// Distinct author emails among the newest `limit` commits reachable from
+/// HEAD, with how many of those commits each authored, busiest first.
+/// Emails are lowercased so case variants count as one author. Empty for
+/// an unborn HEAD; the bound keeps huge histories from blocking.
+pub fn author_emails(path: &Path, limit: usize) -> Result<Vec<(String, usize)>> {
+    let git_err = |e| KissaError::Git {
+        path: path.to_path_buf(),
+        source: e,
+    };
+    let repo = Repository::open(path).map_err(git_err)?;
+    let Some(head) = repo.head().ok().and_then(|h| h.peel_to_commit().ok()) else {
+        return Ok(Vec::new());
+    };
+    let mut walk = repo.revwalk().map_err(git_err)?;
+    walk.set_sorting(git2::Sort::TIME).map_err(git_err)?;
+    walk.push(head.id()).map_err(git_err)?;
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for oid in walk.flatten().take(limit) {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        if let Some(email) = commit.author().email() {
+            *counts.entry(email.to_lowercase()).or_default() += 1;
+        }
+    }
+    let mut authors: Vec<(String, usize)> = counts.into_iter().collect();
+    authors.sort_by(|(a, a_n), (b, b_n)| b_n.cmp(a_n).then_with(|| a.cmp(b)));
+    Ok(authors)
+}
+
+/// Commits among the newest `limit` reachable from HEAD whose summary line
+/// matches `pattern`, newest first. Empty for a repo without commits.
+pub fn grep_log(path: &Path, pattern: &regex::Regex, limit: usize) -> Result<Vec<LogMatch>> {
+    let git_err = |e| KissaError::Git {
+        path: path.to_path_buf(),
+        source: e,
+    };
+    let repo = Repository::open(path).map_err(git_err)?;
+    let Some(head) = repo.head().ok().and_then(|h| h.peel_to_commit().ok()) else {
+        return Ok(Vec::new());
+    };
+    let mut walk = repo.revwalk().map_err(git_err)?;
+    walk.set_sorting(git2::Sort::TIME).map_err(git_err)?;
+    walk.push(head.id()).map_err(git_err)?;
+
+    let mut matches = Vec::new();
+    for oid in walk.flatten().take(limit) {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let Some(summary) = commit.summary().filter(|s| pattern.is_match(s)) else {
+            continue;
+        };
+        let Some(time) = Utc.timestamp_opt(commit.time().seconds(), 0).single() else {
+            continue;
+        };
+        matches.push(LogMatch {
+            sha: oid.to_string(),
+            summary: summary.to_string(),
+            time,
+        });
+    }
+    Ok(matches)
+}
+
+/// Infer the repo name from the `primary_remote` URL, or else the path.
+pub fn infer_name(path: &Path, remotes: &[Remote], primary_remote: &str) -> String {
+    // Prefer remote URL repo name, fall back to directory name
+    if let Some(remote) = remotes.iter().find(|r| r.name == primary_remote)
+        && let Some(info) = parse_remote_org(&remote.url)
+    {
+        return info.repo_name;
     }
     path.file_name()
         .map(|n| n.to_string_lossy().into_owned())
         .unwrap_or_else(|| "unknown".into())
 }
 
+/// The remote named `preferred`, else the first one: the remote whose
+/// org stands for the repo in ownership and exclusion checks.
+pub fn primary_remote<'a>(remotes: &'a [Remote], preferred: &str) -> Option<&'a Remote> {
+    remotes
+        .iter()
+        .find(|r| r.name == preferred)
+        .or_else(|| remotes.first())
+}
+
 /// Parse org/owner from a remote URL.
+/// Everything between the host and the final segment is the namespace,
+/// so nested groups (`group/subgroup/project`) keep their full path.
+/// Ports and user names are dropped from the platform, so self-hosted
+/// remotes on nonstandard ports group with the rest of their host.
 pub fn parse_remote_org(url: &str) -> Option<RemoteInfo> {
-    // Handle SSH: git@github.com:org/repo.git
-    if let Some(rest) = url.strip_prefix("git@") {
-        let (platform, path) = rest.split_once(':')?;
-        let parts: Vec<&str> = path.trim_end_matches(".git").split('/').collect();
-        if parts.len() >= 2 {
-            return Some(RemoteInfo {
-                platform: platform.to_string(),
-                org: parts[0].to_string(),
-                repo_name: parts[1].to_string(),
-            });
+    // Handle URLs: https://github.com/org/repo.git, ssh://git@host:2222/org/repo.git
+    if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(
+            scheme,
+            "https" | "http" | "ssh" | "git" | "git+ssh" | "ssh+git"
+        ) {
+            return None;
         }
+        let (authority, path) = rest.split_once('/')?;
+        return split_namespace(host_of(authority), path);
     }
 
-    // Handle HTTPS: https://github.com/org/repo.git
-    if url.starts_with("https://") || url.starts_with("http://") {
-        let url = url.trim_end_matches(".git");
-        let parts: Vec<&str> = url.split('/').collect();
-        if parts.len() >= 5 {
-            return Some(RemoteInfo {
-                platform: parts[2].to_string(),
-                org: parts[3].to_string(),
-                repo_name: parts[4].to_string(),
-            });
-        }
+    // Handle scp-like SSH: git@github.com:org/repo.git. A colon after the
+    // first slash means a local path, and a lone letter is a DOS drive.
+    let (authority, path) = url.split_once(':')?;
+    if authority.contains('/') || (authority.len() == 1 && authority.is_ascii()) {
+        return None;
     }
+    // git@host:2222/org/repo.git isn't valid scp syntax, but it's common
+    // enough in configs that the leading number is taken as a port.
+    let path = match path.split_once('/') {
+        Some((port, rest)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => rest,
+        _ => path,
+    };
+    split_namespace(host_of(authority), path)
+}
 
-    None
+/// Host part of `[user@]host[:port]`.
+fn host_of(authority: &str) -> &str {
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    host.split_once(':').map_or(host, |(host, _)| host)
+}
+
+/// Split `ns/.../repo(.git)` into namespace and repo name. Needs at least two segments.
+fn split_namespace(platform: &str, path: &str) -> Option<RemoteInfo> {
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let (org, repo_name) = path.rsplit_once('/')?;
+    if platform.is_empty() || org.is_empty() || repo_name.is_empty() {
+        return None;
+    }
+    Some(RemoteInfo {
+        platform: platform.to_string(),
+        org: org.trim_start_matches('/').to_string(),
+        repo_name: repo_name.to_string(),
+    })
 }
 
 #[cfg(test)]
@@ -274,6 +556,72 @@ mod tests {
         assert_eq!(info.repo_name, "myrepo");
     }
 
+    #[test]
+    fn parse_nested_ssh_groups() {
+        let info = parse_remote_org("git@gitlab.com:group/subgroup/project.git").unwrap();
+        assert_eq!(info.platform, "gitlab.com");
+        assert_eq!(info.org, "group/subgroup");
+        assert_eq!(info.repo_name, "project");
+    }
+
+    #[test]
+    fn parse_ssh_scheme_with_port() {
+        let info = parse_remote_org("ssh://git@git.example.com:2222/org/repo.git").unwrap();
+        assert_eq!(info.platform, "git.example.com");
+        assert_eq!(info.org, "org");
+        assert_eq!(info.repo_name, "repo");
+    }
+
+    #[test]
+    fn parse_ssh_scheme_without_port() {
+        let info = parse_remote_org("ssh://git@github.com/initech/api-gateway.git").unwrap();
+        assert_eq!(info.platform, "github.com");
+        assert_eq!(info.org, "initech");
+        assert_eq!(info.repo_name, "api-gateway");
+    }
+
+    #[test]
+    fn parse_scp_like_with_port() {
+        let info = parse_remote_org("git@git.example.com:2222/org/repo.git").unwrap();
+        assert_eq!(info.platform, "git.example.com");
+        assert_eq!(info.org, "org");
+        assert_eq!(info.repo_name, "repo");
+    }
+
+    #[test]
+    fn parse_scp_like_other_user() {
+        let info = parse_remote_org("gitea@git.example.com:team/tool.git").unwrap();
+        assert_eq!(info.platform, "git.example.com");
+        assert_eq!(info.org, "team");
+        assert_eq!(info.repo_name, "tool");
+    }
+
+    #[test]
+    fn parse_https_with_port_and_user() {
+        let info = parse_remote_org("https://me@git.example.com:3000/org/repo.git").unwrap();
+        assert_eq!(info.platform, "git.example.com");
+        assert_eq!(info.org, "org");
+        assert_eq!(info.repo_name, "repo");
+    }
+
+    #[test]
+    fn parse_nested_https_groups() {
+        let info = parse_remote_org("https://gitlab.com/a/b/c/project.git").unwrap();
+        assert_eq!(info.platform, "gitlab.com");
+        assert_eq!(info.org, "a/b/c");
+        assert_eq!(info.repo_name, "project");
+    }
+
+    #[test]
+    fn parse_rejects_single_segment() {
+        assert!(parse_remote_org("git@github.com:lonely.git").is_none());
+        assert!(parse_remote_org("https://github.com/lonely").is_none());
+        assert!(parse_remote_org("/local/path/repo").is_none());
+        assert!(parse_remote_org("./dir:name/org/repo").is_none());
+        assert!(parse_remote_org("C:/code/org/repo").is_none());
+        assert!(parse_remote_org("file:///srv/git/org/repo.git").is_none());
+    }
+
     #[test]
     fn infer_name_from_remote() {
         let remotes = vec![Remote {
@@ -281,14 +629,81 @@ mod tests {
             url: "git@github.com:aaronsb/kissa.git".into(),
             push_url: None,
         }];
-        assert_eq!(infer_name(Path::new("/code/whatever"), &remotes), "kissa");
+        assert_eq!(
+            infer_name(Path::new("/code/whatever"), &remotes, "origin"),
+            "kissa"
+        );
+    }
+
+    #[test]
+    fn infer_name_prefers_primary_remote() {
+        let remotes = vec![
+            Remote {
+                name: "origin".into(),
+                url: "git@github.com:me/kissa-fork.git".into(),
+                push_url: None,
+            },
+            Remote {
+                name: "upstream".into(),
+                url: "git@github.com:aaronsb/kissa.git".into(),
+                push_url: None,
+            },
+        ];
+        let path = Path::new("/code/whatever");
+        assert_eq!(infer_name(path, &remotes, "origin"), "kissa-fork");
+        assert_eq!(infer_name(path, &remotes, "upstream"), "kissa");
+        assert_eq!(infer_name(path, &remotes, "missing"), "whatever");
+    }
+
+    #[test]
+    fn ahead_behind_prefers_tracking_branch_over_primary_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let base = repo.head().unwrap().target().unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let tree = repo.find_commit(base).unwrap().tree().unwrap();
+        let base_commit = repo.find_commit(base).unwrap();
+        let next = repo
+            .commit(Some("HEAD"), &sig, &sig, "second", &tree, &[&base_commit])
+            .unwrap();
+
+        // origin has caught up; upstream is still one commit behind
+        repo.reference(
+            &format!("refs/remotes/origin/{}", branch),
+            next,
+            true,
+            "test",
+        )
+        .unwrap();
+        repo.reference(
+            &format!("refs/remotes/upstream/{}", branch),
+            base,
+            true,
+            "test",
+        )
+        .unwrap();
+
+        // No tracking branch configured: the primary remote's branch counts
+        assert_eq!(ahead_behind(&repo, "origin"), (0, 0));
+        assert_eq!(ahead_behind(&repo, "upstream"), (1, 0));
+        assert_eq!(ahead_behind(&repo, "fork"), (0, 0));
+
+        // A configured upstream wins over the primary remote
+        repo.remote("upstream", "https://example.com/tool.git")
+            .unwrap();
+        repo.find_branch(&branch, BranchType::Local)
+            .unwrap()
+            .set_upstream(Some(&format!("upstream/{}", branch)))
+            .unwrap();
+        assert_eq!(ahead_behind(&repo, "origin"), (1, 0));
     }
 
     #[test]
     fn infer_name_from_path() {
         let remotes = vec![];
         assert_eq!(
-            infer_name(Path::new("/home/user/code/my-project"), &remotes),
+            infer_name(Path::new("/home/user/code/my-project"), &remotes, "origin"),
             "my-project"
         );
     }
@@ -318,23 +733,380 @@ mod tests {
         // Create a dirty file
         fs::write(repo_path.join("dirty.txt"), "uncommitted").unwrap();
 
-        let vitals = extract_vitals(repo_path).unwrap();
+        let vitals = extract_vitals(repo_path, &ScanConfig::default()).unwrap();
         assert!(!vitals.name.is_empty());
         assert!(vitals.dirty || vitals.untracked); // dirty.txt is untracked
         assert!(!vitals.is_bare);
         assert!(vitals.last_commit.is_some());
+        assert!(!vitals.is_empty);
         assert!(vitals.branch_count >= 1);
         assert_eq!(vitals.ahead, 0);
         assert_eq!(vitals.behind, 0);
     }
 
+    #[test]
+    fn extract_vitals_unborn_head_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/trunk").unwrap();
+
+        let vitals = extract_vitals(dir.path(), &ScanConfig::default()).unwrap();
+        assert!(vitals.is_empty);
+        assert!(vitals.last_commit.is_none());
+        assert_eq!(vitals.current_branch.as_deref(), Some("trunk"));
+    }
+
+    #[test]
+    fn compute_status_off_skips_working_tree() {
+        let dir = tempfile::tempdir().unwrap();
+        Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("new.txt"), "x").unwrap();
+
+        let vitals = extract_vitals(dir.path(), &ScanConfig::default()).unwrap();
+        assert!(vitals.untracked && vitals.status_checked);
+
+        let scan = ScanConfig {
+            compute_status: false,
+            ..ScanConfig::default()
+        };
+        let vitals = extract_vitals(dir.path(), &scan).unwrap();
+        assert!(!vitals.untracked && !vitals.status_checked);
+    }
+
+    #[test]
+    fn last_commit_keeps_committer_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        // 2026-03-02 14:05 UTC, committed at 09:05 in UTC-5
+        let when = git2::Time::new(1_772_460_300, -300);
+        let sig = git2::Signature::new("Test", "test@test.com", &when).unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        let vitals = extract_vitals(dir.path(), &ScanConfig::default()).unwrap();
+        assert_eq!(vitals.last_commit_offset, Some(-300));
+        assert_eq!(
+            vitals.last_commit.unwrap().to_rfc3339(),
+            "2026-03-02T14:05:00+00:00"
+        );
+    }
+
+    fn init_with_commit(path: &Path) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+        drop(tree);
+        repo
+    }
+
+    #[test]
+    fn default_branch_drift_from_origin_head_symref() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let local = repo.head().unwrap().shorthand().unwrap().to_string();
+        let head_oid = repo.head().unwrap().target().unwrap();
+
+        // No origin/HEAD: nothing to compare against
+        assert!(!default_branch_drift(&repo, "origin"));
+
+        // Remote default matches a local branch
+        repo.reference(
+            &format!("refs/remotes/origin/{}", local),
+            head_oid,
+            true,
+            "test",
+        )
+        .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            &format!("refs/remotes/origin/{}", local),
+            true,
+            "test",
+        )
+        .unwrap();
+        assert_eq!(
+            remote_default_branch(&repo, "origin").as_deref(),
+            Some(local.as_str())
+        );
+        assert!(!default_branch_drift(&repo, "origin"));
+
+        // Remote switched to a branch we don't have locally
+        repo.reference("refs/remotes/origin/trunk-renamed", head_oid, true, "test")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/trunk-renamed",
+            true,
+            "test",
+        )
+        .unwrap();
+        assert!(default_branch_drift(&repo, "origin"));
+        repo.remote("origin", "https://github.com/acme/web.git")
+            .unwrap();
+        assert!(
+            extract_vitals(dir.path(), &ScanConfig::default())
+                .unwrap()
+                .default_branch_drift
+        );
+    }
+
+    #[test]
+    fn default_branch_drift_reads_the_primary_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let head_oid = repo.head().unwrap().target().unwrap();
+        repo.remote("origin", "https://github.com/me/web.git")
+            .unwrap();
+        repo.remote("upstream", "https://github.com/acme/web.git")
+            .unwrap();
+
+        // Only upstream has moved its default branch
+        repo.reference("refs/remotes/upstream/renamed", head_oid, true, "test")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/upstream/HEAD",
+            "refs/remotes/upstream/renamed",
+            true,
+            "test",
+        )
+        .unwrap();
+
+        let drift = |primary: &str| {
+            let scan = ScanConfig {
+                primary_remote: primary.into(),
+                ..ScanConfig::default()
+            };
+            extract_vitals(dir.path(), &scan)
+                .unwrap()
+                .default_branch_drift
+        };
+        assert!(drift("upstream"));
+        assert!(!drift("origin"));
+    }
+
+    #[test]
+    fn last_fetched_reads_fetch_head_mtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        assert_eq!(last_fetched(&repo), None);
+
+        std::fs::write(repo.path().join("FETCH_HEAD"), "").unwrap();
+        let fetched = last_fetched(&repo).unwrap();
+        assert!((Utc::now() - fetched).num_seconds().abs() < 60);
+    }
+
+    #[test]
+    fn diff_summary_separates_staged_and_worktree() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+
+        fs::write(dir.path().join("staged.txt"), "a").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("staged.txt")).unwrap();
+        index.write().unwrap();
+        fs::write(dir.path().join("untracked.txt"), "b").unwrap();
+
+        let staged = diff_summary(dir.path(), true).unwrap();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0].kind, ChangeKind::Added);
+        assert_eq!(staged[0].path, Path::new("staged.txt"));
+
+        // Working tree vs HEAD includes staged changes but not untracked files
+        fs::write(dir.path().join("staged.txt"), "changed").unwrap();
+        let worktree = diff_summary(dir.path(), false).unwrap();
+        assert_eq!(worktree.len(), 1);
+        assert_eq!(worktree[0].kind.letter(), 'A');
+    }
+
+    #[test]
+    fn commits_since_stops_at_cutoff() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let old_time = git2::Time::new((Utc::now() - chrono::Duration::days(90)).timestamp(), 0);
+        let old_sig = git2::Signature::new("Test", "test@test.com", &old_time).unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+
+        // Rewrite history: one old root commit, then two recent ones
+        let root = repo
+            .commit(None, &old_sig, &old_sig, "old", &tree, &[])
+            .unwrap();
+        let mut parent = repo.find_commit(root).unwrap();
+        for msg in ["recent 1", "recent 2"] {
+            let id = repo
+                .commit(None, &sig, &sig, msg, &tree, &[&parent])
+                .unwrap();
+            parent = repo.find_commit(id).unwrap();
+        }
+        repo.reference("refs/heads/activity", parent.id(), true, "test")
+            .unwrap();
+        repo.set_head("refs/heads/activity").unwrap();
+
+        let since = Utc::now() - chrono::Duration::days(30);
+        assert_eq!(commits_since(&repo, since), Some(2));
+
+        let unborn = Repository::init(dir.path().join("unborn")).unwrap();
+        assert_eq!(commits_since(&unborn, since), None);
+    }
+
+    #[test]
+    fn author_emails_counts_bounded_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let mut parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let base = Utc::now().timestamp();
+        for (i, email) in ["me@personal.dev", "ME@personal.dev", "me@initech.com"]
+            .iter()
+            .enumerate()
+        {
+            let when = git2::Time::new(base + 60 * (i as i64 + 1), 0);
+            let sig = git2::Signature::new("Me", email, &when).unwrap();
+            let id = repo
+                .commit(Some("HEAD"), &sig, &sig, "work", &tree, &[&parent])
+                .unwrap();
+            parent = repo.find_commit(id).unwrap();
+        }
+
+        let authors = author_emails(dir.path(), 100).unwrap();
+        assert_eq!(
+            authors,
+            vec![
+                ("me@personal.dev".to_string(), 2),
+                ("me@initech.com".to_string(), 1),
+                ("test@test.com".to_string(), 1),
+            ]
+        );
+
+        // Only the newest commits count once the bound is hit
+        let recent = author_emails(dir.path(), 2).unwrap();
+        assert_eq!(
+            recent,
+            vec![
+                ("me@initech.com".to_string(), 1),
+                ("me@personal.dev".to_string(), 1)
+            ]
+        );
+
+        let unborn = dir.path().join("unborn");
+        Repository::init(&unborn).unwrap();
+        assert!(author_emails(&unborn, 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn grep_log_matches_recent_summaries() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let mut parents: Vec<git2::Oid> = Vec::new();
+        for (i, message) in [
+            "Fix flaky retry test",
+            "Add config loader",
+            "fix retry backoff\n\nretry",
+        ]
+        .iter()
+        .enumerate()
+        {
+            let when = git2::Time::new(1_700_000_000 + i as i64 * 60, 0);
+            let sig = git2::Signature::new("Test", "test@test.com", &when).unwrap();
+            let parent = parents.last().map(|oid| repo.find_commit(*oid).unwrap());
+            let oid = repo
+                .commit(
+                    Some("HEAD"),
+                    &sig,
+                    &sig,
+                    message,
+                    &tree,
+                    &parent.iter().collect::<Vec<_>>(),
+                )
+                .unwrap();
+            parents.push(oid);
+        }
+
+        let pattern = regex::Regex::new("(?i)retry").unwrap();
+        let found = grep_log(dir.path(), &pattern, 100).unwrap();
+        let summaries: Vec<&str> = found.iter().map(|m| m.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["fix retry backoff", "Fix flaky retry test"]);
+        assert_eq!(found[0].sha, parents[2].to_string());
+
+        // Only the newest commit is walked
+        assert_eq!(grep_log(dir.path(), &pattern, 1).unwrap().len(), 1);
+        let loader = regex::Regex::new("loader").unwrap();
+        assert!(grep_log(dir.path(), &loader, 1).unwrap().is_empty());
+
+        let unborn = dir.path().join("unborn");
+        Repository::init(&unborn).unwrap();
+        assert!(grep_log(&unborn, &pattern, 100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn head_signed_detects_signature_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        assert_eq!(head_signed(&repo), Some(false));
+
+        // Build a commit carrying a (fake) signature header and point HEAD at it
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = git2::Signature::now("Test", "test@test.com").unwrap();
+        let buf = repo
+            .commit_create_buffer(&sig, &sig, "signed", &head.tree().unwrap(), &[&head])
+            .unwrap();
+        let signed = repo
+            .commit_signed(
+                buf.as_str().unwrap(),
+                "-----BEGIN PGP SIGNATURE-----\n\nfake\n-----END PGP SIGNATURE-----",
+                None,
+            )
+            .unwrap();
+        repo.head().unwrap().set_target(signed, "sign").unwrap();
+        assert_eq!(head_signed(&repo), Some(true));
+
+        let empty = tempfile::tempdir().unwrap();
+        let unborn = Repository::init(empty.path()).unwrap();
+        assert_eq!(head_signed(&unborn), None);
+    }
+
+    #[test]
+    fn count_branches_stops_at_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        for name in ["a", "b", "c", "d"] {
+            repo.branch(name, &head, false).unwrap();
+        }
+
+        assert_eq!(count_branches(&repo, 0), (5, 0, false));
+        assert_eq!(count_branches(&repo, 5), (5, 0, false));
+        assert_eq!(count_branches(&repo, 3), (3, 0, true));
+    }
+
+    #[test]
+    fn untracked_mode_controls_untracked_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = init_with_commit(dir.path());
+        fs::write(dir.path().join("build.log"), "artifact").unwrap();
+
+        let normal = working_tree_status(&repo, UntrackedMode::Normal);
+        assert_eq!(normal, (false, false, true)); // untracked-only is not dirty
+        let all = working_tree_status(&repo, UntrackedMode::All);
+        assert_eq!(all, (false, false, true));
+        let no = working_tree_status(&repo, UntrackedMode::No);
+        assert_eq!(no, (false, false, false));
+    }
+
     #[test]
     fn extract_vitals_bare_repo() {
         let dir = tempfile::tempdir().unwrap();
         let repo_path = dir.path().join("bare.git");
         Repository::init_bare(&repo_path).unwrap();
 
-        let vitals = extract_vitals(&repo_path).unwrap();
+        let vitals = extract_vitals(&repo_path, &ScanConfig::default()).unwrap();
         assert!(vitals.is_bare);
         assert!(!vitals.dirty);
         assert!(!vitals.staged);
@@ -343,7 +1115,7 @@ mod tests {
 
     #[test]
     fn extract_vitals_nonexistent_path() {
-        let result = extract_vitals(Path::new("/nonexistent/repo"));
+        let result = extract_vitals(Path::new("/nonexistent/repo"), &ScanConfig::default());
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,299 @@
+//! Repo relationship graph: clones and forks linked to their inferred origin
+//! by shared remote identity, rendered as Graphviz DOT.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use super::git_ops::{self, parse_remote_org};
+use super::repo::{Category, Freshness, RemoteInfo, Repo};
+
+/// How a repo relates to the repo an edge points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    /// Same remote identity as the target, which is the canonical checkout.
+    Clone,
+    /// `upstream` remote points at the target's identity.
+    Fork,
+    /// Same identity, explicitly classified as a mirror.
+    Mirror,
+}
+
+impl Relation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Relation::Clone => "clone",
+            Relation::Fork => "fork",
+            Relation::Mirror => "mirror",
+        }
+    }
+}
+
+/// A directed edge from `from` to its inferred origin `to` (indices into the repo slice).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub relation: Relation,
+}
+
+/// The remote a repo primarily tracks: `preferred` (`scan.primary_remote`),
+/// else the first remote.
+fn primary_remote(repo: &Repo, preferred: &str) -> Option<RemoteInfo> {
+    let remote = git_ops::primary_remote(&repo.remotes, preferred)?;
+    parse_remote_org(&remote.url)
+}
+
+fn upstream_remote(repo: &Repo) -> Option<RemoteInfo> {
+    let remote = repo.remotes.iter().find(|r| r.name == "upstream")?;
+    parse_remote_org(&remote.url)
+}
+
+/// Infer clone/fork/mirror edges between repos.
+///
+/// Repos sharing a primary identity are grouped; the one classified as
+/// `Category::Origin` (or else the first in slice order) is canonical and the
+/// rest point at it. A repo whose `upstream` remote matches another group's
+/// identity gets a fork edge to that group's canonical repo.
+pub fn infer_edges(repos: &[Repo], preferred: &str) -> Vec<Edge> {
+    let identities: Vec<Option<String>> = repos
+        .iter()
+        .map(|r| primary_remote(r, preferred).map(|info| info.identity()))
+        .collect();
+
+    let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, id) in identities.iter().enumerate() {
+        if let Some(id) = id {
+            groups.entry(id.as_str()).or_default().push(i);
+        }
+    }
+
+    let canonical: HashMap<&str, usize> = groups
+        .iter()
+        .map(|(id, members)| {
+            let head = members
+                .iter()
+                .copied()
+                .find(|&i| repos[i].category == Some(Category::Origin))
+                .unwrap_or(members[0]);
+            (*id, head)
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for (i, repo) in repos.iter().enumerate() {
+        if let Some(id) = identities[i].as_deref() {
+            let head = canonical[id];
+            if head != i {
+                let relation = if repo.category == Some(Category::Mirror) {
+                    Relation::Mirror
+                } else {
+                    Relation::Clone
+                };
+                edges.push(Edge {
+                    from: i,
+                    to: head,
+                    relation,
+                });
+                continue;
+            }
+        }
+
+        if let Some(upstream) = upstream_remote(repo)
+            && identities[i].as_deref() != Some(upstream.identity().as_str())
+            && let Some(&target) = canonical.get(upstream.identity().as_str())
+        {
+            edges.push(Edge {
+                from: i,
+                to: target,
+                relation: Relation::Fork,
+            });
+        }
+    }
+    edges
+}
+
+fn freshness_color(f: Freshness) -> &'static str {
+    match f {
+        Freshness::Active => "palegreen",
+        Freshness::Recent => "lightcyan",
+        Freshness::Stale => "khaki",
+        Freshness::Dormant => "lightsalmon",
+        Freshness::Ancient => "lightgray",
+        Freshness::Empty => "white",
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render repos as a Graphviz DOT digraph, clustered by `platform/org` and
+/// colored by freshness tier. Repos without a parseable remote sit outside any cluster.
+pub fn to_dot(repos: &[Repo], preferred: &str) -> String {
+    let edges = infer_edges(repos, preferred);
+
+    let mut clusters: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut loose = Vec::new();
+    for (i, repo) in repos.iter().enumerate() {
+        match primary_remote(repo, preferred) {
+            Some(info) => clusters
+                .entry(format!("{}/{}", info.platform, info.org))
+                .or_default()
+                .push(i),
+            None => loose.push(i),
+        }
+    }
+
+    let node = |out: &mut String, indent: &str, i: usize| {
+        let repo = &repos[i];
+        let _ = writeln!(
+            out,
+            "{}n{} [label={}, tooltip={}, fillcolor={}];",
+            indent,
+            i,
+            quote(&repo.name),
+            quote(&repo.path.display().to_string()),
+            freshness_color(repo.freshness),
+        );
+    };
+
+    let mut out = String::new();
+    out.push_str("digraph kissa {\n");
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=filled];\n");
+
+    for (n, (label, members)) in clusters.iter().enumerate() {
+        let _ = writeln!(out, "  subgraph cluster_{} {{", n);
+        let _ = writeln!(out, "    label={};", quote(label));
+        for &i in members {
+            node(&mut out, "    ", i);
+        }
+        out.push_str("  }\n");
+    }
+    for &i in &loose {
+        node(&mut out, "  ", i);
+    }
+
+    for edge in &edges {
+        let _ = writeln!(
+            out,
+            "  n{} -> n{} [label={}];",
+            edge.from,
+            edge.to,
+            quote(edge.relation.label()),
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::repo::Remote;
+
+    fn make_repo(name: &str, remotes: &[(&str, &str)]) -> Repo {
+        Repo {
+            remotes: remotes
+                .iter()
+                .map(|(n, u)| Remote {
+                    name: (*n).into(),
+                    url: (*u).into(),
+                    push_url: None,
+                })
+                .collect(),
+            default_branch: None,
+            current_branch: None,
+            last_verified: None,
+            ..Repo::test_default(name, format!("/code/{}", name))
+        }
+    }
+
+    #[test]
+    fn clones_point_at_origin_category() {
+        let mut origin = make_repo("kissa", &[("origin", "git@github.com:aaronsb/kissa.git")]);
+        origin.category = Some(Category::Origin);
+        let clone = make_repo(
+            "kissa-copy",
+            &[("origin", "https://github.com/AaronSB/kissa")],
+        );
+        let repos = vec![clone, origin];
+
+        let edges = infer_edges(&repos, "origin");
+        assert_eq!(
+            edges,
+            vec![Edge {
+                from: 0,
+                to: 1,
+                relation: Relation::Clone
+            }]
+        );
+    }
+
+    #[test]
+    fn upstream_remote_yields_fork_edge() {
+        let upstream = make_repo(
+            "tokio",
+            &[("origin", "https://github.com/tokio-rs/tokio.git")],
+        );
+        let fork = make_repo(
+            "tokio-fork",
+            &[
+                ("origin", "git@github.com:me/tokio.git"),
+                ("upstream", "https://github.com/tokio-rs/tokio.git"),
+            ],
+        );
+        let edges = infer_edges(&[upstream, fork], "origin");
+        assert_eq!(
+            edges,
+            vec![Edge {
+                from: 1,
+                to: 0,
+                relation: Relation::Fork
+            }]
+        );
+    }
+
+    #[test]
+    fn identity_follows_the_primary_remote() {
+        let a = make_repo(
+            "a",
+            &[
+                ("backup", "git@gitlab.com:me/a.git"),
+                ("gh", "git@github.com:acme/a.git"),
+            ],
+        );
+        let b = make_repo("a-copy", &[("gh", "https://github.com/acme/a.git")]);
+        let repos = vec![a, b];
+
+        assert_eq!(
+            infer_edges(&repos, "gh"),
+            vec![Edge {
+                from: 1,
+                to: 0,
+                relation: Relation::Clone
+            }]
+        );
+        // Without a remote of that name, the first one stands in
+        assert!(infer_edges(&repos, "origin").is_empty());
+    }
+
+    #[test]
+    fn unrelated_repos_have_no_edges() {
+        let a = make_repo("a", &[("origin", "git@github.com:x/a.git")]);
+        let b = make_repo("b", &[]);
+        assert!(infer_edges(&[a, b], "origin").is_empty());
+    }
+
+    #[test]
+    fn dot_clusters_by_org_and_quotes_labels() {
+        let a = make_repo("a\"b", &[("origin", "git@github.com:initech/a.git")]);
+        let loose = make_repo("scratch", &[]);
+        let dot = to_dot(&[a, loose], "origin");
+        assert!(dot.starts_with("digraph kissa {"));
+        assert!(dot.contains("label=\"github.com/initech\";"));
+        assert!(dot.contains("label=\"a\\\"b\""));
+        assert!(dot.contains("n1 [label=\"scratch\""));
+        assert!(dot.contains("fillcolor=palegreen"));
+    }
+}
@@ -0,0 +1,199 @@
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use super::permissions::{self, OperationClass};
+use super::repo::Repo;
+use crate::config::types::KissaConfig;
+use crate::error::{KissaError, Result};
+
+/// Run `classification.hook` for a newly indexed repo and return the extra
+/// tags it asks for, or None when no hook is configured.
+///
+/// The hook is arbitrary code, so it only runs where the repo's effective
+/// difficulty allows writes (`commit` or above); below that this returns
+/// `PermissionDenied`.
+pub fn classify_hook_tags(
+    config: &KissaConfig,
+    repo: &Repo,
+    is_mcp: bool,
+) -> Result<Option<Vec<String>>> {
+    let Some(ref hook) = config.classification.hook else {
+        return Ok(None);
+    };
+    permissions::check_permission(OperationClass::Write, &repo.path, config, is_mcp)?;
+    let timeout = Duration::from_secs(config.classification.hook_timeout_secs);
+    run_hook(hook, repo, timeout).map(Some)
+}
+
+/// The hook contract: the repo as one line of JSON (the same shape as
+/// `kissa list --format json` entries) on stdin, one tag per line on
+/// stdout, exit 0, within `timeout`; past that it is killed. The hook's
+/// stderr passes straight through.
+fn run_hook(hook: &Path, repo: &Repo, timeout: Duration) -> Result<Vec<String>> {
+    let failed = |message: String| KissaError::Hook {
+        path: hook.to_path_buf(),
+        message,
+    };
+    let input = serde_json::to_vec(repo).map_err(|e| failed(e.to_string()))?;
+
+    let mut child = Command::new(hook)
+        .current_dir(&repo.path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+
+    // Feed stdin and drain stdout on the side, so neither a hook that never
+    // reads its input nor a chatty one can hold us past the deadline.
+    // Threads still blocked then (a grandchild keeping a pipe open) are
+    // left behind rather than waited for.
+    let mut stdin = child.stdin.take();
+    let (wrote_tx, wrote) = mpsc::channel();
+    std::thread::spawn(move || {
+        let result = match stdin {
+            Some(ref mut stdin) => stdin
+                .write_all(&input)
+                .and_then(|()| stdin.write_all(b"\n")),
+            None => Ok(()),
+        };
+        let _ = wrote_tx.send(result);
+    });
+    let mut stdout = child.stdout.take();
+    let (read_tx, read) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut out = Vec::new();
+        if let Some(ref mut stdout) = stdout {
+            let _ = stdout.read_to_end(&mut out);
+        }
+        let _ = read_tx.send(out);
+    });
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = || failed(format!("timed out after {}s", timeout.as_secs()));
+    let status = loop {
+        match child.try_wait().map_err(|e| failed(e.to_string()))? {
+            Some(status) => break status,
+            None if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            None => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+        }
+    };
+    if !status.success() {
+        return Err(failed(status.to_string()));
+    }
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    match wrote.recv_timeout(remaining()) {
+        // A hook that ignores its input may exit before reading it
+        Ok(Err(e)) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+            return Err(failed(e.to_string()));
+        }
+        Ok(_) => {}
+        Err(_) => return Err(timed_out()),
+    }
+    let stdout = read.recv_timeout(remaining()).map_err(|_| timed_out())?;
+    Ok(parse_hook_tags(&String::from_utf8_lossy(&stdout)))
+}
+
+fn parse_hook_tags(stdout: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in stdout.lines().map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_output_is_one_tag_per_line() {
+        assert_eq!(
+            parse_hook_tags("infra\n\n  team-a  \nINFRA\n"),
+            vec!["infra".to_string(), "team-a".to_string()]
+        );
+        assert!(parse_hook_tags("").is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_reads_repo_json_and_emits_tags() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let hook = dir.path().join("hook.sh");
+        std::fs::write(
+            &hook,
+            "#!/bin/sh\nread -r repo\necho registered\n\
+             case \"$repo\" in *'\"name\":\"widget\"'*) echo widget-seen ;; esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut config = KissaConfig::default();
+        config.classification.hook = Some(hook.clone());
+        let repo = Repo::test_default("widget", dir.path());
+
+        let tags = classify_hook_tags(&config, &repo, false).unwrap().unwrap();
+        assert_eq!(
+            tags,
+            vec!["registered".to_string(), "widget-seen".to_string()]
+        );
+
+        // MCP defaults to readonly, which is below what a hook needs
+        let err = classify_hook_tags(&config, &repo, true).unwrap_err();
+        assert!(matches!(err, KissaError::PermissionDenied { .. }));
+
+        std::fs::write(&hook, "#!/bin/sh\nexit 3\n").unwrap();
+        let err = classify_hook_tags(&config, &repo, false).unwrap_err();
+        assert!(matches!(err, KissaError::Hook { .. }));
+
+        std::fs::write(&hook, "#!/bin/sh\nexec sleep 30\n").unwrap();
+        config.classification.hook_timeout_secs = 1;
+        let started = Instant::now();
+        let err = classify_hook_tags(&config, &repo, false).unwrap_err();
+        assert!(
+            matches!(err, KissaError::Hook { ref message, .. } if message.contains("timed out"))
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_timeout_bounds_stdin_and_inherited_stdout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = |name: &str, body: &str| {
+            let hook = dir.path().join(name);
+            std::fs::write(&hook, body).unwrap();
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+            hook
+        };
+        let timeout = Duration::from_secs(1);
+        let timed_out = |result: Result<Vec<String>>| matches!(result, Err(KissaError::Hook { ref message, .. }) if message.contains("timed out"));
+
+        // More input than a pipe buffers, to a hook that never reads it
+        let deaf = script("deaf.sh", "#!/bin/sh\nexec sleep 5\n");
+        let mut repo = Repo::test_default("widget", dir.path());
+        repo.note = Some("x".repeat(1 << 20));
+        let started = Instant::now();
+        assert!(timed_out(run_hook(&deaf, &repo, timeout)));
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        // The hook exits, but a grandchild keeps its stdout open
+        let forks = script("forks.sh", "#!/bin/sh\nsleep 5 &\necho tag\n");
+        let repo = Repo::test_default("widget", dir.path());
+        let started = Instant::now();
+        assert!(timed_out(run_hook(&forks, &repo, timeout)));
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
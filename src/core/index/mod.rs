@@ -1,21 +1,37 @@
+mod multi;
 mod types;
 
-pub use types::{FreshnessSummary, IndexSummary};
+pub use multi::MultiIndex;
 use types::RepoRow;
+pub use types::{FreshnessSummary, IndexSummary, RemoteUrlRecord, ScanError};
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
 
 use super::filter::RepoFilter;
-use super::repo::{Ownership, Remote, Repo, RepoId};
-use crate::error::Result;
+use super::repo::{Freshness, Intention, Ownership, Remote, Repo, RepoId, UpstreamInfo};
+use super::{git_ops, scanner};
+use crate::config::types::NameCollision;
+use crate::error::{KissaError, Result};
 
-const SCHEMA_VERSION: i32 = 2;
+const SCHEMA_VERSION: i32 = 20;
+
+/// How long SQLite itself waits on a locked database before returning SQLITE_BUSY.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(5000);
+
+/// Extra attempts for writes that still fail with SQLITE_BUSY, e.g. when a
+/// read transaction can't upgrade and SQLite skips the busy handler entirely.
+const BUSY_RETRIES: u32 = 3;
 
 /// The persistent repo index backed by SQLite (ADR-103).
 pub struct Index {
     conn: rusqlite::Connection,
+    /// When set, every read is restricted to repos at or under this path.
+    root: Option<PathBuf>,
 }
 
 impl Index {
@@ -31,22 +47,77 @@ impl Index {
             })?;
         }
         let conn = rusqlite::Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
         conn.pragma_update(None, "journal_mode", "wal")?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
-        let index = Self { conn };
+        let index = Self { conn, root: None };
         index.migrate()?;
         Ok(index)
     }
 
+    /// Open an existing index read-only, e.g. a teammate's exported index.
+    /// Never migrates; the schema must already match this build.
+    pub fn open_readonly(path: &Path) -> Result<Self> {
+        let conn = rusqlite::Connection::open_with_flags(
+            path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let index = Self { conn, root: None };
+        let version = index.schema_version();
+        if version != SCHEMA_VERSION {
+            return Err(crate::error::KissaError::Config(format!(
+                "index {} has schema v{}, expected v{}; open it with this kissa version first",
+                path.display(),
+                version,
+                SCHEMA_VERSION
+            )));
+        }
+        Ok(index)
+    }
+
     /// Open an in-memory database (for testing).
     pub fn open_in_memory() -> Result<Self> {
         let conn = rusqlite::Connection::open_in_memory()?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
-        let index = Self { conn };
+        let index = Self { conn, root: None };
         index.migrate()?;
         Ok(index)
     }
 
+    /// Restrict all reads (listing, counts, summaries, name lookups) to repos
+    /// at or under `root`, and `forget_managed` with them. Other writes and
+    /// exact-path lookups are unaffected.
+    pub fn with_root(mut self, root: Option<PathBuf>) -> Self {
+        self.root = root;
+        self
+    }
+
+    /// The scoping root set by `with_root`, if any.
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// SQL condition limiting `path` to the scoping root, binding from `?{next}`.
+    fn scope_clause(&self, next: usize) -> (String, Vec<String>) {
+        match &self.root {
+            Some(root) => under_clause(root, next),
+            None => ("1=1".to_string(), Vec::new()),
+        }
+    }
+
+    /// COUNT(*) of repos matching `condition` (no bound params) within the scope.
+    fn scoped_count(&self, condition: &str) -> Result<usize> {
+        let (scope, params) = self.scope_clause(1);
+        let sql = format!(
+            "SELECT COUNT(*) FROM repos WHERE ({}) AND {}",
+            condition, scope
+        );
+        Ok(self
+            .conn
+            .query_row(&sql, rusqlite::params_from_iter(params), |row| row.get(0))?)
+    }
+
     /// Run schema migrations to latest version.
     pub fn migrate(&self) -> Result<()> {
         let current = self.schema_version();
@@ -113,20 +184,229 @@ impl Index {
                 ",
             )?;
 
-            self.conn.execute(
-                "INSERT INTO schema_version (version) VALUES (?1)",
-                [1],
-            )?;
+            self.conn
+                .execute("INSERT INTO schema_version (version) VALUES (?1)", [1])?;
         }
 
         if current < 2 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN managed_by TEXT;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [2])?;
+        }
+
+        if current < 3 {
             self.conn.execute_batch(
-                "ALTER TABLE repos ADD COLUMN managed_by TEXT;"
+                "
+                CREATE TABLE IF NOT EXISTS scan_errors (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    scan_id INTEGER NOT NULL REFERENCES scans(id) ON DELETE CASCADE,
+                    path TEXT NOT NULL,
+                    message TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_scan_errors_scan_id ON scan_errors(scan_id);
+                ",
             )?;
-            self.conn.execute(
-                "UPDATE schema_version SET version = ?1",
-                [SCHEMA_VERSION],
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [3])?;
+        }
+
+        if current < 4 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN note TEXT;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [4])?;
+        }
+
+        if current < 5 {
+            self.conn.execute_batch(
+                "ALTER TABLE repos ADD COLUMN default_branch_drift INTEGER NOT NULL DEFAULT 0;",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [5])?;
+        }
+
+        if current < 6 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN head_signed INTEGER;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [6])?;
+        }
+
+        if current < 7 {
+            self.conn.execute_batch(
+                "ALTER TABLE repos ADD COLUMN snapshot_dirty INTEGER;
+                ALTER TABLE repos ADD COLUMN snapshot_ahead INTEGER;
+                ALTER TABLE repos ADD COLUMN snapshot_last_commit TEXT;",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [7])?;
+        }
+
+        if current < 8 {
+            // Full-text index over name, path, tags and note. rowid = repos.id;
+            // triggers keep it in step with upserts, targeted updates and deletes.
+            self.conn.execute_batch(
+                "
+                CREATE VIRTUAL TABLE IF NOT EXISTS repos_fts
+                    USING fts5(name, path, tags, note);
+
+                CREATE TRIGGER IF NOT EXISTS repos_fts_insert AFTER INSERT ON repos BEGIN
+                    INSERT INTO repos_fts (rowid, name, path, tags, note)
+                    VALUES (new.id, new.name, new.path, '', new.note);
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS repos_fts_update
+                AFTER UPDATE OF name, path, note ON repos BEGIN
+                    UPDATE repos_fts SET name = new.name, path = new.path, note = new.note
+                    WHERE rowid = new.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS repos_fts_delete AFTER DELETE ON repos BEGIN
+                    DELETE FROM repos_fts WHERE rowid = old.id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS repos_fts_tag_insert AFTER INSERT ON tags BEGIN
+                    UPDATE repos_fts
+                    SET tags = (SELECT group_concat(tag, ' ') FROM tags WHERE repo_id = new.repo_id)
+                    WHERE rowid = new.repo_id;
+                END;
+
+                CREATE TRIGGER IF NOT EXISTS repos_fts_tag_delete AFTER DELETE ON tags BEGIN
+                    UPDATE repos_fts
+                    SET tags = coalesce(
+                        (SELECT group_concat(tag, ' ') FROM tags WHERE repo_id = old.repo_id), '')
+                    WHERE rowid = old.repo_id;
+                END;
+
+                INSERT INTO repos_fts (rowid, name, path, tags, note)
+                SELECT id, name, path,
+                    coalesce((SELECT group_concat(tag, ' ') FROM tags WHERE repo_id = repos.id), ''),
+                    note
+                FROM repos;
+                ",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [8])?;
+        }
+
+        if current < 9 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN last_fetched TEXT;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [9])?;
+        }
+
+        if current < 10 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN commits_last_30d INTEGER;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [10])?;
+        }
+
+        if current < 11 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN origin_org TEXT;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [11])?;
+        }
+
+        if current < 12 {
+            // Data only: rows stored before upserts canonicalized their path
+            self.collapse_duplicate_paths()?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [12])?;
+        }
+
+        if current < 13 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN last_commit_offset INTEGER;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [13])?;
+        }
+
+        if current < 14 {
+            self.conn.execute_batch(
+                "ALTER TABLE repos ADD COLUMN is_empty INTEGER NOT NULL DEFAULT 0;",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [14])?;
+        }
+
+        if current < 15 {
+            self.conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS scan_ignore (
+                    path TEXT PRIMARY KEY,
+                    added_at TEXT NOT NULL
+                );
+                ",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [15])?;
+        }
+
+        if current < 16 {
+            self.conn.execute_batch(
+                "ALTER TABLE repos ADD COLUMN branches_truncated INTEGER NOT NULL DEFAULT 0;",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [16])?;
+        }
+
+        if current < 17 {
+            self.conn
+                .execute_batch("ALTER TABLE repos ADD COLUMN nested_in TEXT;")?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [17])?;
+        }
+
+        if current < 18 {
+            // Seed each remote's current URL, dated when its repo was first seen
+            self.conn.execute_batch(
+                "
+                CREATE TABLE IF NOT EXISTS remote_url_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    repo_id INTEGER NOT NULL REFERENCES repos(id) ON DELETE CASCADE,
+                    remote_name TEXT NOT NULL,
+                    url TEXT NOT NULL,
+                    seen_at TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_remote_url_history_repo
+                    ON remote_url_history(repo_id);
+
+                INSERT INTO remote_url_history (repo_id, remote_name, url, seen_at)
+                    SELECT remotes.repo_id, remotes.name, remotes.url, repos.first_seen
+                    FROM remotes JOIN repos ON repos.id = remotes.repo_id;
+                ",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [18])?;
+        }
+
+        if current < 19 {
+            // Every earlier scan read the working tree along with the rest
+            self.conn.execute_batch(
+                "
+                ALTER TABLE repos ADD COLUMN status_checked TEXT;
+                UPDATE repos SET status_checked = last_verified;
+                ",
+            )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [19])?;
+        }
+
+        if current < 20 {
+            self.conn.execute_batch(
+                "ALTER TABLE repos ADD COLUMN upstream_description TEXT;
+                ALTER TABLE repos ADD COLUMN upstream_archived INTEGER;
+                ALTER TABLE repos ADD COLUMN upstream_stars INTEGER;
+                ALTER TABLE repos ADD COLUMN upstream_default_branch TEXT;
+                ALTER TABLE repos ADD COLUMN upstream_fetched_at TEXT;",
             )?;
+            self.conn
+                .execute("UPDATE schema_version SET version = ?1", [SCHEMA_VERSION])?;
         }
 
         Ok(())
@@ -134,110 +414,194 @@ impl Index {
 
     fn schema_version(&self) -> i32 {
         self.conn
-            .query_row(
-                "SELECT version FROM schema_version LIMIT 1",
-                [],
-                |row| row.get(0),
-            )
+            .query_row("SELECT version FROM schema_version LIMIT 1", [], |row| {
+                row.get(0)
+            })
             .unwrap_or(0)
     }
 
     /// Insert or update a repo in the index.
+    /// `note` is user metadata: it is written on insert but never overwritten
+    /// by an update; use `set_note` to change it. A repo whose working tree
+    /// wasn't read (`status_checked` unset) keeps its stored dirty, staged
+    /// and untracked flags.
     pub fn upsert_repo(&self, repo: &Repo) -> Result<RepoId> {
-        let (ownership_type, ownership_label) = match &repo.ownership {
-            Some(Ownership::Personal) => (Some("personal"), None),
-            Some(Ownership::Work { label }) => (Some("work"), Some(label.as_str())),
-            Some(Ownership::Community) => (Some("community"), None),
-            Some(Ownership::ThirdParty) => (Some("thirdparty"), None),
-            Some(Ownership::Local) => (Some("local"), None),
-            None => (None, None),
-        };
+        retry_busy(|| self.upsert_repo_once(repo, MergePolicy::Replace))
+    }
+
+    /// Like `upsert_repo`, but an already indexed repo only gets its
+    /// git-derived columns and remotes updated: tags, classification,
+    /// project and role stay as they are, so a rescan can't wipe what was
+    /// set by hand. New repos are inserted whole. `classify --reapply`
+    /// re-derives classification for indexed repos.
+    pub fn upsert_vitals(&self, repo: &Repo) -> Result<RepoId> {
+        retry_busy(|| self.upsert_repo_once(repo, MergePolicy::KeepMetadata))
+    }
+
+    fn upsert_repo_once(&self, repo: &Repo, policy: MergePolicy) -> Result<RepoId> {
+        let (ownership_type, ownership_label) = ownership_columns(repo.ownership.as_ref());
 
         let state_str = serde_plain::to_string(&repo.state).unwrap_or_else(|_| "active".into());
         let freshness_str =
             serde_plain::to_string(&repo.freshness).unwrap_or_else(|_| "ancient".into());
-        let category_str = repo.category.as_ref().and_then(|c| serde_plain::to_string(c).ok());
+        let category_str = repo
+            .category
+            .as_ref()
+            .and_then(|c| serde_plain::to_string(c).ok());
         let intention_str = repo
             .intention
             .as_ref()
             .and_then(|i| serde_plain::to_string(i).ok());
         let last_commit_str = repo.last_commit.map(|dt| dt.to_rfc3339());
         let last_verified_str = repo.last_verified.map(|dt| dt.to_rfc3339());
+        let last_fetched_str = repo.last_fetched.map(|dt| dt.to_rfc3339());
         let first_seen_str = repo.first_seen.to_rfc3339();
-        let path_str = repo.path.to_string_lossy();
-
-        self.conn.execute(
-            "INSERT INTO repos (
-                name, path, state, default_branch, current_branch,
-                branch_count, stale_branch_count, dirty, staged, untracked,
-                ahead, behind, last_commit, last_verified, first_seen,
-                freshness, category, ownership_type, ownership_label,
-                intention, project, role, managed_by
-            ) VALUES (
-                ?1, ?2, ?3, ?4, ?5,
-                ?6, ?7, ?8, ?9, ?10,
-                ?11, ?12, ?13, ?14, ?15,
-                ?16, ?17, ?18, ?19,
-                ?20, ?21, ?22, ?23
+        let path = scanner::canonical_path(&repo.path);
+        let path_str = path.to_string_lossy();
+        let nested_in_str = repo
+            .nested_in
+            .as_ref()
+            .map(|p| p.to_string_lossy().into_owned());
+        let status_checked_str = repo.status_checked.map(|dt| dt.to_rfc3339());
+
+        let params = rusqlite::params![
+            repo.name,
+            path_str,
+            state_str,
+            repo.default_branch,
+            repo.current_branch,
+            repo.branch_count,
+            repo.stale_branch_count,
+            repo.dirty,
+            repo.staged,
+            repo.untracked,
+            repo.ahead,
+            repo.behind,
+            last_commit_str,
+            last_verified_str,
+            first_seen_str,
+            freshness_str,
+            category_str,
+            ownership_type,
+            ownership_label,
+            intention_str,
+            repo.project,
+            repo.role,
+            repo.managed_by,
+            repo.note,
+            repo.default_branch_drift,
+            repo.head_signed,
+            last_fetched_str,
+            repo.commits_last_30d,
+            repo.origin_org,
+            repo.last_commit_offset,
+            repo.is_empty,
+            repo.branches_truncated,
+            nested_in_str,
+            status_checked_str,
+        ];
+
+        let tx = self.conn.unchecked_transaction()?;
+        let inserted: Option<i64> = self
+            .conn
+            .query_row(
+                "INSERT INTO repos (
+                    name, path, state, default_branch, current_branch,
+                    branch_count, stale_branch_count, dirty, staged, untracked,
+                    ahead, behind, last_commit, last_verified, first_seen,
+                    freshness, category, ownership_type, ownership_label,
+                    intention, project, role, managed_by, note,
+                    default_branch_drift, head_signed, last_fetched, commits_last_30d,
+                    origin_org, last_commit_offset, is_empty, branches_truncated,
+                    nested_in, status_checked
+                ) VALUES (
+                    ?1, ?2, ?3, ?4, ?5,
+                    ?6, ?7, ?8, ?9, ?10,
+                    ?11, ?12, ?13, ?14, ?15,
+                    ?16, ?17, ?18, ?19,
+                    ?20, ?21, ?22, ?23, ?24,
+                    ?25, ?26, ?27, ?28,
+                    ?29, ?30, ?31, ?32,
+                    ?33, ?34
+                )
+                ON CONFLICT(path) DO NOTHING
+                RETURNING id",
+                params,
+                |row| row.get(0),
             )
-            ON CONFLICT(path) DO UPDATE SET
-                name = excluded.name,
-                state = excluded.state,
-                default_branch = excluded.default_branch,
-                current_branch = excluded.current_branch,
-                branch_count = excluded.branch_count,
-                stale_branch_count = excluded.stale_branch_count,
-                dirty = excluded.dirty,
-                staged = excluded.staged,
-                untracked = excluded.untracked,
-                ahead = excluded.ahead,
-                behind = excluded.behind,
-                last_commit = excluded.last_commit,
-                last_verified = excluded.last_verified,
-                freshness = excluded.freshness,
-                category = excluded.category,
-                ownership_type = excluded.ownership_type,
-                ownership_label = excluded.ownership_label,
-                intention = excluded.intention,
-                project = excluded.project,
-                role = excluded.role,
-                managed_by = excluded.managed_by
-            ",
-            rusqlite::params![
-                repo.name,
-                path_str,
-                state_str,
-                repo.default_branch,
-                repo.current_branch,
-                repo.branch_count,
-                repo.stale_branch_count,
-                repo.dirty,
-                repo.staged,
-                repo.untracked,
-                repo.ahead,
-                repo.behind,
-                last_commit_str,
-                last_verified_str,
-                first_seen_str,
-                freshness_str,
-                category_str,
-                ownership_type,
-                ownership_label,
-                intention_str,
-                repo.project,
-                repo.role,
-                repo.managed_by,
-            ],
-        )?;
-
-        // Always query for the canonical id (UPSERT doesn't reliably set last_insert_rowid)
-        let repo_id: i64 = self.conn.query_row(
-            "SELECT id FROM repos WHERE path = ?1",
-            [path_str.as_ref()],
-            |row| row.get(0),
-        )?;
+            .optional()?;
+
+        // Already indexed: update in place, from the same parameters
+        let repo_id = match inserted {
+            Some(id) => id,
+            None => {
+                let metadata = match policy {
+                    MergePolicy::Replace => {
+                        ",
+                        category = ?17,
+                        ownership_type = ?18,
+                        ownership_label = ?19,
+                        intention = ?20,
+                        project = ?21,
+                        role = ?22,
+                        managed_by = ?23,
+                        origin_org = ?29"
+                    }
+                    MergePolicy::KeepMetadata => "",
+                };
+                self.conn.query_row(
+                    &format!(
+                        "UPDATE repos SET
+                        name = ?1,
+                        state = ?3,
+                        default_branch = ?4,
+                        current_branch = ?5,
+                        branch_count = ?6,
+                        stale_branch_count = ?7,
+                        dirty = CASE WHEN ?34 IS NULL THEN dirty ELSE ?8 END,
+                        staged = CASE WHEN ?34 IS NULL THEN staged ELSE ?9 END,
+                        untracked = CASE WHEN ?34 IS NULL THEN untracked ELSE ?10 END,
+                        ahead = ?11,
+                        behind = ?12,
+                        last_commit = ?13,
+                        last_verified = ?14,
+                        freshness = ?16,
+                        default_branch_drift = ?25,
+                        head_signed = ?26,
+                        last_fetched = ?27,
+                        commits_last_30d = ?28,
+                        last_commit_offset = ?30,
+                        is_empty = ?31,
+                        branches_truncated = ?32,
+                        nested_in = ?33,
+                        status_checked = COALESCE(?34, status_checked)
+                        {}
+                    WHERE path = ?2
+                    RETURNING id",
+                        metadata
+                    ),
+                    params,
+                    |row| row.get(0),
+                )?
+            }
+        };
 
-        // Replace remotes
+        // Log remotes that are new or point somewhere else, then replace them
+        let previous: HashMap<String, String> = self
+            .conn
+            .prepare("SELECT name, url FROM remotes WHERE repo_id = ?1")?
+            .query_map([repo_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        let now = Utc::now().to_rfc3339();
+        for remote in &repo.remotes {
+            if previous.get(&remote.name) != Some(&remote.url) {
+                self.conn.execute(
+                    "INSERT INTO remote_url_history (repo_id, remote_name, url, seen_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    rusqlite::params![repo_id, remote.name, remote.url, now],
+                )?;
+            }
+        }
         self.conn
             .execute("DELETE FROM remotes WHERE repo_id = ?1", [repo_id])?;
         for remote in &repo.remotes {
@@ -248,18 +612,169 @@ impl Index {
         }
 
         // Replace tags
-        self.conn
-            .execute("DELETE FROM tags WHERE repo_id = ?1", [repo_id])?;
-        for tag in &repo.tags {
-            self.conn.execute(
-                "INSERT INTO tags (repo_id, tag) VALUES (?1, ?2)",
-                rusqlite::params![repo_id, tag],
-            )?;
+        if policy == MergePolicy::Replace || inserted.is_some() {
+            self.conn
+                .execute("DELETE FROM tags WHERE repo_id = ?1", [repo_id])?;
+            for tag in &repo.tags {
+                self.conn.execute(
+                    "INSERT INTO tags (repo_id, tag) VALUES (?1, ?2)",
+                    rusqlite::params![repo_id, tag],
+                )?;
+            }
         }
+        tx.commit()?;
 
         Ok(repo_id)
     }
 
+    /// Replace a repo's tags without touching any other column.
+    pub fn set_tags(&self, id: RepoId, tags: &[String]) -> Result<()> {
+        self.ensure_repo(id)?;
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM tags WHERE repo_id = ?1", [id])?;
+            for tag in tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO tags (repo_id, tag) VALUES (?1, ?2)",
+                    rusqlite::params![id, tag],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Add `tag` to every repo in `ids` in one transaction. Returns how many
+    /// didn't have it yet.
+    pub fn add_tag(&self, ids: &[RepoId], tag: &str) -> Result<usize> {
+        self.change_tag(
+            ids,
+            tag,
+            "INSERT OR IGNORE INTO tags (repo_id, tag) VALUES (?1, ?2)",
+        )
+    }
+
+    /// Remove `tag` from every repo in `ids` in one transaction. Returns how
+    /// many had it.
+    pub fn remove_tag(&self, ids: &[RepoId], tag: &str) -> Result<usize> {
+        self.change_tag(ids, tag, "DELETE FROM tags WHERE repo_id = ?1 AND tag = ?2")
+    }
+
+    fn change_tag(&self, ids: &[RepoId], tag: &str, sql: &str) -> Result<usize> {
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            let mut changed = 0;
+            {
+                let mut stmt = tx.prepare(sql)?;
+                for id in ids {
+                    changed += stmt.execute(rusqlite::params![id, tag])?;
+                }
+            }
+            tx.commit()?;
+            Ok(changed)
+        })
+    }
+
+    /// Set or clear a repo's ownership without touching any other column.
+    pub fn set_ownership(&self, id: RepoId, ownership: Option<&Ownership>) -> Result<()> {
+        let (ownership_type, ownership_label) = ownership_columns(ownership);
+        self.update_one(
+            id,
+            "UPDATE repos SET ownership_type = ?2, ownership_label = ?3 WHERE id = ?1",
+            rusqlite::params![id, ownership_type, ownership_label],
+        )
+    }
+
+    /// Set or clear a repo's intention without touching any other column.
+    pub fn set_intention(&self, id: RepoId, intention: Option<Intention>) -> Result<()> {
+        let intention_str = intention.and_then(|i| serde_plain::to_string(&i).ok());
+        self.update_one(
+            id,
+            "UPDATE repos SET intention = ?2 WHERE id = ?1",
+            rusqlite::params![id, intention_str],
+        )
+    }
+
+    /// Set or clear a repo's free-form note.
+    pub fn set_note(&self, id: RepoId, note: Option<&str>) -> Result<()> {
+        self.update_one(
+            id,
+            "UPDATE repos SET note = ?2 WHERE id = ?1",
+            rusqlite::params![id, note],
+        )
+    }
+
+    /// Write only the classification fields of `repo` (managed_by, ownership,
+    /// intention, category, origin_org, tags), leaving scanned vitals alone.
+    /// The columns and tags change together in one transaction.
+    pub fn set_classification(&self, repo: &Repo) -> Result<()> {
+        let (ownership_type, ownership_label) = ownership_columns(repo.ownership.as_ref());
+        let intention_str = repo
+            .intention
+            .as_ref()
+            .and_then(|i| serde_plain::to_string(i).ok());
+        let category_str = repo
+            .category
+            .as_ref()
+            .and_then(|c| serde_plain::to_string(c).ok());
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            let updated = tx.execute(
+                "UPDATE repos SET
+                    managed_by = ?2, ownership_type = ?3, ownership_label = ?4,
+                    intention = ?5, category = ?6, origin_org = ?7
+                WHERE id = ?1",
+                rusqlite::params![
+                    repo.id,
+                    repo.managed_by,
+                    ownership_type,
+                    ownership_label,
+                    intention_str,
+                    category_str,
+                    repo.origin_org,
+                ],
+            )?;
+            if updated == 0 {
+                return Err(KissaError::RepoNotFound(format!("id {}", repo.id)));
+            }
+            tx.execute("DELETE FROM tags WHERE repo_id = ?1", [repo.id])?;
+            for tag in &repo.tags {
+                tx.execute(
+                    "INSERT OR IGNORE INTO tags (repo_id, tag) VALUES (?1, ?2)",
+                    rusqlite::params![repo.id, tag],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Run a single-row UPDATE, failing with RepoNotFound if nothing matched.
+    fn update_one(
+        &self,
+        id: RepoId,
+        sql: &str,
+        params: impl rusqlite::Params + Copy,
+    ) -> Result<()> {
+        if retry_busy(|| Ok(self.conn.execute(sql, params)?))? == 0 {
+            return Err(KissaError::RepoNotFound(format!("id {}", id)));
+        }
+        Ok(())
+    }
+
+    fn ensure_repo(&self, id: RepoId) -> Result<()> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM repos WHERE id = ?1)",
+            [id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            Ok(())
+        } else {
+            Err(KissaError::RepoNotFound(format!("id {}", id)))
+        }
+    }
+
     /// Get a repo by its absolute path.
     pub fn get_repo_by_path(&self, path: &Path) -> Result<Option<Repo>> {
         let path_str = path.to_string_lossy();
@@ -276,89 +791,112 @@ impl Index {
     }
 
     /// Get a repo by name (exact match first, then prefix, then contains).
+    /// Lost repos are skipped; see `get_repo_by_name_including_lost`.
     pub fn get_repo_by_name(&self, name: &str) -> Result<Option<Repo>> {
-        // Exact match
-        let result = self.conn.query_row(
-            "SELECT id FROM repos WHERE name = ?1 AND state != 'lost' LIMIT 1",
-            [name],
-            |row| row.get::<_, i64>(0),
-        );
-        if let Ok(id) = result {
-            return Ok(Some(self.load_repo(id)?));
-        }
+        self.find_repo_by_name(name, false, NameCollision::First)
+    }
 
-        // Prefix match
-        let like_prefix = format!("{}%", name);
-        let result = self.conn.query_row(
-            "SELECT id FROM repos WHERE name LIKE ?1 AND state != 'lost' LIMIT 1",
-            [&like_prefix],
-            |row| row.get::<_, i64>(0),
-        );
-        if let Ok(id) = result {
-            return Ok(Some(self.load_repo(id)?));
-        }
+    /// Like `get_repo_by_name`, but lost repos can match too, for inspecting
+    /// or cleaning up their last-known state.
+    pub fn get_repo_by_name_including_lost(&self, name: &str) -> Result<Option<Repo>> {
+        self.find_repo_by_name(name, true, NameCollision::First)
+    }
 
-        // Contains match
-        let like_contains = format!("%{}%", name);
-        let result = self.conn.query_row(
-            "SELECT id FROM repos WHERE name LIKE ?1 AND state != 'lost' LIMIT 1",
-            [&like_contains],
-            |row| row.get::<_, i64>(0),
-        );
-        match result {
-            Ok(id) => Ok(Some(self.load_repo(id)?)),
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+    /// Name lookup as in `get_repo_by_name`, settling several matches at the
+    /// first level that has any with `strategy` (`display.name_collision`).
+    pub fn resolve_repo_name(
+        &self,
+        name: &str,
+        include_lost: bool,
+        strategy: NameCollision,
+    ) -> Result<Option<Repo>> {
+        self.find_repo_by_name(name, include_lost, strategy)
     }
 
-    /// List repos matching the given filter.
-    /// Uses SQL for basic column filters, then applies RepoFilter::matches() for complex ones.
-    pub fn list_repos(&self, filter: &RepoFilter) -> Result<Vec<Repo>> {
-        let mut where_clauses = vec!["1=1".to_string()];
-        let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-
-        // Simple SQL-expressible filters
-        if let Some(dirty) = filter.dirty {
-            where_clauses.push(format!("dirty = ?{}", params.len() + 1));
-            params.push(Box::new(dirty));
-        }
-        if let Some(ref state) = filter.state {
-            let s = serde_plain::to_string(state).unwrap_or_else(|_| "active".into());
-            where_clauses.push(format!("state = ?{}", params.len() + 1));
-            params.push(Box::new(s));
-        }
-        if let Some(ref freshness) = filter.freshness {
-            let s = serde_plain::to_string(freshness).unwrap_or_else(|_| "ancient".into());
-            where_clauses.push(format!("freshness = ?{}", params.len() + 1));
-            params.push(Box::new(s));
-        }
-        if let Some(ref prefix) = filter.path_prefix {
-            where_clauses.push(format!("path LIKE ?{}", params.len() + 1));
-            params.push(Box::new(format!("{}%", prefix)));
-        }
-        if let Some(ref name) = filter.name_contains {
-            where_clauses.push(format!("name LIKE ?{}", params.len() + 1));
-            params.push(Box::new(format!("%{}%", name)));
-        }
-        if let Some(ref mb) = filter.managed_by {
-            where_clauses.push(format!("managed_by = ?{}", params.len() + 1));
-            params.push(Box::new(mb.clone()));
-        }
-        if let Some(show) = filter.show_managed {
-            if show {
-                where_clauses.push("managed_by IS NOT NULL".to_string());
-            } else {
-                where_clauses.push("managed_by IS NULL".to_string());
+    fn find_repo_by_name(
+        &self,
+        name: &str,
+        include_lost: bool,
+        strategy: NameCollision,
+    ) -> Result<Option<Repo>> {
+        let (scope, scope_params) = self.scope_clause(2);
+        let state = if include_lost {
+            ""
+        } else {
+            "AND state != 'lost'"
+        };
+        // RFC 3339 timestamps in UTC sort chronologically as text
+        let order = match strategy {
+            NameCollision::MostRecent => "last_commit IS NULL, last_commit DESC, id",
+            NameCollision::First | NameCollision::Error => "id",
+        };
+        let lookups = [
+            ("=", name.to_string()),
+            ("LIKE", format!("{}%", name)),
+            ("LIKE", format!("%{}%", name)),
+        ];
+
+        for (op, pattern) in lookups {
+            let sql = format!(
+                "SELECT id, path FROM repos WHERE name {} ?1 {} AND {} ORDER BY {}",
+                op, state, scope, order
+            );
+            let params = std::iter::once(pattern).chain(scope_params.iter().cloned());
+            let mut stmt = self.conn.prepare(&sql)?;
+            let matches = stmt
+                .query_map(rusqlite::params_from_iter(params), |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            match matches.as_slice() {
+                [] => continue,
+                [_, _, ..] if strategy == NameCollision::Error => {
+                    return Err(KissaError::AmbiguousName {
+                        name: name.to_string(),
+                        candidates: matches.into_iter().map(|(_, path)| path).collect(),
+                    });
+                }
+                [(id, _), ..] => return Ok(Some(self.load_repo(*id)?)),
             }
         }
+        Ok(None)
+    }
 
+    /// Full-text search over name, path, tags and note, best match first.
+    /// Every word in `query` must match, each as a prefix ("kis" finds "kissa").
+    pub fn search_fulltext(&self, query: &str) -> Result<Vec<Repo>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let (scope, scope_params) = self.scope_clause(2);
+        // Weight name hits above tags, tags above notes, notes above path segments.
         let sql = format!(
-            "SELECT id FROM repos WHERE {}",
-            where_clauses.join(" AND ")
+            "SELECT repos.id FROM (
+                SELECT rowid AS rid, bm25(repos_fts, 10.0, 1.0, 5.0, 2.0) AS score
+                FROM repos_fts WHERE repos_fts MATCH ?1
+            ) AS hits
+            JOIN repos ON repos.id = hits.rid
+            WHERE {}
+            ORDER BY hits.score, repos.name",
+            scope
         );
+        let params = std::iter::once(fts_query).chain(scope_params);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let ids: Vec<i64> = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        ids.into_iter().map(|id| self.load_repo(id)).collect()
+    }
+
+    /// List repos matching the given filter.
+    /// Uses SQL for basic column filters, then applies RepoFilter::matches() for complex ones.
+    pub fn list_repos(&self, filter: &RepoFilter) -> Result<Vec<Repo>> {
+        let (where_sql, params) = self.scoped_where_clause(filter);
+        let sql = format!("SELECT id FROM repos WHERE {}", where_sql);
 
-        let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+        let param_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params.iter().map(|p| p.as_ref()).collect();
         let mut stmt = self.conn.prepare(&sql)?;
         let ids: Vec<i64> = stmt
             .query_map(param_refs.as_slice(), |row| row.get(0))?
@@ -376,55 +914,349 @@ impl Index {
         Ok(repos)
     }
 
+    /// Count repos matching the given filter without materializing them
+    /// when the filter is fully expressible in SQL.
+    pub fn count_repos(&self, filter: &RepoFilter) -> Result<usize> {
+        if filter.needs_in_memory_match() {
+            return Ok(self.list_repos(filter)?.len());
+        }
+
+        let (where_sql, params) = self.scoped_where_clause(filter);
+        let sql = format!("SELECT COUNT(*) FROM repos WHERE {}", where_sql);
+        let param_refs: Vec<&dyn rusqlite::types::ToSql> =
+            params.iter().map(|p| p.as_ref()).collect();
+        let count = self
+            .conn
+            .query_row(&sql, param_refs.as_slice(), |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// `filter_where_clause` plus the scoping root condition.
+    fn scoped_where_clause(
+        &self,
+        filter: &RepoFilter,
+    ) -> (String, Vec<Box<dyn rusqlite::types::ToSql>>) {
+        let (where_sql, mut params) = filter_where_clause(filter);
+        let (scope, scope_params) = self.scope_clause(params.len() + 1);
+        for p in scope_params {
+            params.push(Box::new(p));
+        }
+        (format!("{} AND {}", where_sql, scope), params)
+    }
+
+    /// Every tag in use, sorted.
+    pub fn distinct_tags(&self) -> Result<Vec<String>> {
+        self.scoped_distinct("tags.tag", "tags JOIN repos ON repos.id = tags.repo_id")
+    }
+
+    /// Every project name in use, sorted.
+    pub fn distinct_projects(&self) -> Result<Vec<String>> {
+        self.scoped_distinct("project", "repos")
+    }
+
+    /// Every tool that manages an indexed repo, sorted.
+    pub fn distinct_managed_by(&self) -> Result<Vec<String>> {
+        self.scoped_distinct("managed_by", "repos")
+    }
+
+    /// Every org (namespace) parsed from any remote URL, sorted. These are
+    /// the values `--org` matches against.
+    pub fn distinct_orgs(&self) -> Result<Vec<String>> {
+        let urls = self.scoped_distinct(
+            "remotes.url",
+            "remotes JOIN repos ON repos.id = remotes.repo_id",
+        )?;
+        let orgs: std::collections::BTreeSet<String> = urls
+            .iter()
+            .filter_map(|url| git_ops::parse_remote_org(url))
+            .map(|info| info.org)
+            .collect();
+        Ok(orgs.into_iter().collect())
+    }
+
+    /// Sorted non-null values of `column` across in-scope repos.
+    fn scoped_distinct(&self, column: &str, from: &str) -> Result<Vec<String>> {
+        let (scope, params) = self.scope_clause(1);
+        let sql = format!(
+            "SELECT DISTINCT {col} FROM {from} WHERE {col} IS NOT NULL AND {scope} ORDER BY {col}",
+            col = column,
+            from = from,
+            scope = scope,
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let values = stmt
+            .query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(values)
+    }
+
+    /// Repos at or under `root` (canonicalized first, like stored paths),
+    /// within any `with_root` scope. Unlike the `path_prefix` filter this
+    /// respects component boundaries: `/a` holds `/a/b` but not `/ab`.
+    pub fn repos_under(&self, root: &Path) -> Result<Vec<Repo>> {
+        let (under, mut params) = under_clause(&scanner::canonical_path(root), 1);
+        let (scope, scope_params) = self.scope_clause(params.len() + 1);
+        params.extend(scope_params);
+        let sql = format!(
+            "SELECT id FROM repos WHERE {} AND {} ORDER BY path",
+            under, scope
+        );
+        let ids: Vec<RepoId> = {
+            let mut stmt = self.conn.prepare(&sql)?;
+            stmt.query_map(rusqlite::params_from_iter(params), |row| row.get(0))?
+                .collect::<rusqlite::Result<_>>()?
+        };
+        ids.into_iter().map(|id| self.load_repo(id)).collect()
+    }
+
     /// Get all repos (unfiltered).
     pub fn all_repos(&self) -> Result<Vec<Repo>> {
         self.list_repos(&RepoFilter::default())
     }
 
+    /// Every path in the index, whatever its state or scope.
+    pub fn indexed_paths(&self) -> Result<HashSet<PathBuf>> {
+        let mut stmt = self.conn.prepare("SELECT path FROM repos")?;
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(PathBuf::from))
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    }
+
+    /// Recompute each repo's freshness tier from its stored `last_commit`
+    /// against the current time, without touching git, so tiers don't stay
+    /// frozen at whatever they were when the repo was last scanned. Repos
+    /// with no recorded commit are ancient. Returns how many tiers changed.
+    pub fn recompute_freshness(&self) -> Result<usize> {
+        let changed: Vec<(RepoId, Freshness)> = self
+            .all_repos()?
+            .into_iter()
+            .filter_map(|repo| {
+                let freshness = Freshness::of(repo.last_commit, repo.is_empty);
+                (freshness != repo.freshness).then_some((repo.id, freshness))
+            })
+            .collect();
+
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            for (id, freshness) in &changed {
+                let freshness_str =
+                    serde_plain::to_string(freshness).unwrap_or_else(|_| "ancient".into());
+                tx.execute(
+                    "UPDATE repos SET freshness = ?2 WHERE id = ?1",
+                    rusqlite::params![id, freshness_str],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })?;
+        Ok(changed.len())
+    }
+
     /// Mark a repo as lost (path no longer exists).
     pub fn mark_lost(&self, id: RepoId) -> Result<()> {
-        self.conn
-            .execute("UPDATE repos SET state = 'lost' WHERE id = ?1", [id])?;
-        Ok(())
+        retry_busy(|| {
+            self.conn
+                .execute("UPDATE repos SET state = 'lost' WHERE id = ?1", [id])?;
+            Ok(())
+        })
+    }
+
+    /// Mark a repo whose path stopped answering within
+    /// `scan.boundaries.stat_timeout_ms`, e.g. on a hung mount. Unlike lost,
+    /// the repo may well still be there; the next successful read clears it.
+    pub fn mark_timeout(&self, id: RepoId) -> Result<()> {
+        retry_busy(|| {
+            self.conn
+                .execute("UPDATE repos SET state = 'timeout' WHERE id = ?1", [id])?;
+            Ok(())
+        })
+    }
+
+    /// Clear a `timeout` mark once the repo's path answers again.
+    pub fn mark_active(&self, id: RepoId) -> Result<()> {
+        retry_busy(|| {
+            self.conn
+                .execute("UPDATE repos SET state = 'active' WHERE id = ?1", [id])?;
+            Ok(())
+        })
     }
 
     /// Remove a repo from the index permanently.
     pub fn forget_repo(&self, id: RepoId) -> Result<()> {
-        self.conn.execute("DELETE FROM repos WHERE id = ?1", [id])?;
-        Ok(())
+        retry_busy(|| {
+            self.conn.execute("DELETE FROM repos WHERE id = ?1", [id])?;
+            Ok(())
+        })
     }
 
-    /// Get summary statistics for the entire index.
-    pub fn summary(&self) -> Result<IndexSummary> {
-        let total_repos: usize = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM repos", [], |row| row.get(0))?;
-        let dirty_count: usize = self
-            .conn
-            .query_row("SELECT COUNT(*) FROM repos WHERE dirty = 1", [], |row| {
-                row.get(0)
-            })?;
-        let unpushed_count: usize = self.conn.query_row(
-            "SELECT COUNT(*) FROM repos WHERE ahead > 0",
-            [],
-            |row| row.get(0),
-        )?;
-        let orphan_count: usize = self.conn.query_row(
-            "SELECT COUNT(*) FROM repos WHERE id NOT IN (SELECT DISTINCT repo_id FROM remotes)",
-            [],
-            |row| row.get(0),
-        )?;
-        let lost_count: usize = self.conn.query_row(
-            "SELECT COUNT(*) FROM repos WHERE state = 'lost'",
-            [],
-            |row| row.get(0),
-        )?;
+    /// Remember `path` so later scans never index it again. Idempotent.
+    pub fn ignore_path(&self, path: &Path) -> Result<()> {
+        let path = scanner::canonical_path(path);
+        retry_busy(|| {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO scan_ignore (path, added_at) VALUES (?1, ?2)",
+                rusqlite::params![path.to_string_lossy(), Utc::now().to_rfc3339()],
+            )?;
+            Ok(())
+        })
+    }
 
-        let managed_count: usize = self.conn.query_row(
-            "SELECT COUNT(*) FROM repos WHERE managed_by IS NOT NULL",
-            [],
+    /// Let scans index `path` again. Returns false if it wasn't ignored.
+    pub fn unignore_path(&self, path: &Path) -> Result<bool> {
+        let path = scanner::canonical_path(path);
+        retry_busy(|| {
+            Ok(self.conn.execute(
+                "DELETE FROM scan_ignore WHERE path = ?1",
+                [path.to_string_lossy()],
+            )? > 0)
+        })
+    }
+
+    /// Whether scans must skip `path` (see `ignore_path`).
+    pub fn is_ignored(&self, path: &Path) -> Result<bool> {
+        let path = scanner::canonical_path(path);
+        Ok(self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM scan_ignore WHERE path = ?1)",
+            [path.to_string_lossy()],
             |row| row.get(0),
-        )?;
+        )?)
+    }
+
+    /// Every path ignored with `ignore_path`, in canonical form.
+    pub fn ignored_paths(&self) -> Result<HashSet<PathBuf>> {
+        let mut stmt = self.conn.prepare("SELECT path FROM scan_ignore")?;
+        let paths = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(PathBuf::from))
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(paths)
+    }
+
+    /// Rewrite every stored path to its canonical form, merging rows that
+    /// turn out to be the same repo (e.g. indexed once through a symlinked
+    /// root). The oldest row survives and picks up the others' tags and any
+    /// note, project, role or intention it lacks. Returns how many rows were
+    /// merged away.
+    pub fn collapse_duplicate_paths(&self) -> Result<usize> {
+        let rows: Vec<(RepoId, String)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, path FROM repos ORDER BY id")?;
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<rusqlite::Result<_>>()?
+        };
+
+        // Ordered by first id, so the oldest row of each group comes first
+        let mut groups: Vec<(String, Vec<RepoId>)> = Vec::new();
+        let mut group_of: std::collections::HashMap<String, usize> = Default::default();
+        for (id, path) in rows {
+            let canonical = scanner::canonical_path(Path::new(&path))
+                .to_string_lossy()
+                .into_owned();
+            match group_of.get(&canonical) {
+                Some(&i) => groups[i].1.push(id),
+                None => {
+                    group_of.insert(canonical.clone(), groups.len());
+                    groups.push((canonical, vec![id]));
+                }
+            }
+        }
+
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            let mut merged = 0;
+            for (canonical, ids) in &groups {
+                let (keep, dups) = ids.split_first().expect("groups are never empty");
+                for dup in dups {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO tags (repo_id, tag)
+                         SELECT ?1, tag FROM tags WHERE repo_id = ?2",
+                        [keep, dup],
+                    )?;
+                    tx.execute(
+                        "UPDATE repos SET
+                            note = COALESCE(note, (SELECT note FROM repos WHERE id = ?2)),
+                            project = COALESCE(project, (SELECT project FROM repos WHERE id = ?2)),
+                            role = COALESCE(role, (SELECT role FROM repos WHERE id = ?2)),
+                            intention = COALESCE(intention, (SELECT intention FROM repos WHERE id = ?2))
+                         WHERE id = ?1",
+                        [keep, dup],
+                    )?;
+                    tx.execute("DELETE FROM repos WHERE id = ?1", [dup])?;
+                    merged += 1;
+                }
+                tx.execute(
+                    "UPDATE repos SET path = ?2 WHERE id = ?1 AND path != ?2",
+                    rusqlite::params![keep, canonical],
+                )?;
+            }
+            tx.commit()?;
+            Ok(merged)
+        })
+    }
+
+    /// Record the repo's current dirty/ahead/last-commit as the scan snapshot.
+    /// Called by full scans only, so later refreshes can be diffed against it.
+    pub fn record_scan_snapshot(&self, id: RepoId) -> Result<()> {
+        self.update_one(
+            id,
+            "UPDATE repos SET
+                snapshot_dirty = dirty,
+                snapshot_ahead = ahead,
+                snapshot_last_commit = last_commit
+            WHERE id = ?1",
+            [id],
+        )
+    }
+
+    /// Store what the platform API reported for a repo. Scans never touch
+    /// these columns, so the data stays until the next `kissa enrich`.
+    pub fn set_upstream(&self, id: RepoId, info: &UpstreamInfo) -> Result<()> {
+        self.update_one(
+            id,
+            "UPDATE repos SET
+                upstream_description = ?2,
+                upstream_archived = ?3,
+                upstream_stars = ?4,
+                upstream_default_branch = ?5,
+                upstream_fetched_at = ?6
+            WHERE id = ?1",
+            rusqlite::params![
+                id,
+                info.description,
+                info.archived,
+                info.stars,
+                info.default_branch,
+                info.fetched_at.to_rfc3339(),
+            ],
+        )
+    }
+
+    /// Remove every tool-managed repo (`managed_by` set) within the scope
+    /// from the index. Returns how many were removed.
+    pub fn forget_managed(&self) -> Result<usize> {
+        let (scope, params) = self.scope_clause(1);
+        let sql = format!(
+            "DELETE FROM repos WHERE managed_by IS NOT NULL AND {}",
+            scope
+        );
+        retry_busy(|| {
+            Ok(self
+                .conn
+                .execute(&sql, rusqlite::params_from_iter(&params))?)
+        })
+    }
+
+    /// Get summary statistics for the index (or its scoping root).
+    pub fn summary(&self) -> Result<IndexSummary> {
+        let total_repos = self.scoped_count("1=1")?;
+        let dirty_count = self.scoped_count("dirty = 1")?;
+        let unpushed_count = self.scoped_count("ahead > 0")?;
+        let orphan_count = self.scoped_count("id NOT IN (SELECT DISTINCT repo_id FROM remotes)")?;
+        let lost_count = self.scoped_count("state = 'lost'")?;
+        let managed_count = self.scoped_count("managed_by IS NOT NULL")?;
 
         let freshness = self.freshness_summary()?;
         let last_scan = self.last_scan_time()?;
@@ -447,32 +1279,85 @@ impl Index {
 
     /// Get counts per freshness tier.
     pub fn freshness_summary(&self) -> Result<FreshnessSummary> {
-        let count = |tier: &str| -> Result<usize> {
-            Ok(self.conn.query_row(
-                "SELECT COUNT(*) FROM repos WHERE freshness = ?1",
-                [tier],
-                |row| row.get(0),
-            )?)
-        };
-
         Ok(FreshnessSummary {
-            active: count("active")?,
-            recent: count("recent")?,
-            stale: count("stale")?,
-            dormant: count("dormant")?,
-            ancient: count("ancient")?,
+            active: self.scoped_count("freshness = 'active'")?,
+            recent: self.scoped_count("freshness = 'recent'")?,
+            stale: self.scoped_count("freshness = 'stale'")?,
+            dormant: self.scoped_count("freshness = 'dormant'")?,
+            ancient: self.scoped_count("freshness = 'ancient'")?,
+            empty: self.scoped_count("freshness = 'empty'")?,
         })
     }
 
-    /// Record that a scan completed.
-    pub fn record_scan(&self, roots: &[PathBuf], repo_count: usize) -> Result<()> {
+    /// Record that a scan completed, along with any per-path errors it hit.
+    /// `roots` should already be resolved to absolute paths by the caller
+    /// (see `scanner::resolve_roots`); they are stored as given.
+    pub fn record_scan(
+        &self,
+        roots: &[PathBuf],
+        repo_count: usize,
+        errors: &[(PathBuf, String)],
+    ) -> Result<()> {
         let roots_json = serde_json::to_string(roots).unwrap_or_else(|_| "[]".into());
         let now = Utc::now().to_rfc3339();
-        self.conn.execute(
-            "INSERT INTO scans (completed_at, roots, repo_count) VALUES (?1, ?2, ?3)",
-            rusqlite::params![now, roots_json, repo_count],
+        retry_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute(
+                "INSERT INTO scans (completed_at, roots, repo_count) VALUES (?1, ?2, ?3)",
+                rusqlite::params![now, roots_json, repo_count],
+            )?;
+            let scan_id = tx.last_insert_rowid();
+
+            for (path, message) in errors {
+                tx.execute(
+                    "INSERT INTO scan_errors (scan_id, path, message) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![scan_id, path.to_string_lossy(), message],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Get the errors recorded by the most recent scan.
+    pub fn last_scan_errors(&self) -> Result<Vec<ScanError>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT path, message FROM scan_errors
+             WHERE scan_id = (SELECT MAX(id) FROM scans)
+             ORDER BY id",
         )?;
-        Ok(())
+        let errors = stmt
+            .query_map([], |row| {
+                Ok(ScanError {
+                    path: PathBuf::from(row.get::<_, String>(0)?),
+                    message: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(errors)
+    }
+
+    /// Every URL each of a repo's remotes has had since it was indexed,
+    /// oldest first within each remote. A new row means the URL changed.
+    pub fn remote_url_history(&self, id: RepoId) -> Result<Vec<RemoteUrlRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT remote_name, url, seen_at FROM remote_url_history
+             WHERE repo_id = ?1
+             ORDER BY remote_name, id",
+        )?;
+        let records = stmt
+            .query_map([id], |row| {
+                let seen_at: String = row.get(2)?;
+                Ok(RemoteUrlRecord {
+                    remote: row.get(0)?,
+                    url: row.get(1)?,
+                    seen_at: DateTime::parse_from_rfc3339(&seen_at)
+                        .map(|dt| dt.to_utc())
+                        .unwrap_or_default(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(records)
     }
 
     /// Get the timestamp of the last completed scan.
@@ -510,7 +1395,14 @@ impl Index {
                 branch_count, stale_branch_count, dirty, staged, untracked,
                 ahead, behind, last_commit, last_verified, first_seen,
                 freshness, category, ownership_type, ownership_label,
-                intention, project, role, managed_by
+                intention, project, role, managed_by, note,
+                default_branch_drift, head_signed,
+                snapshot_dirty, snapshot_ahead, snapshot_last_commit,
+                last_fetched, commits_last_30d, origin_org, last_commit_offset,
+                is_empty, branches_truncated, nested_in, status_checked,
+                upstream_description, upstream_archived, upstream_stars,
+                upstream_default_branch, upstream_fetched_at,
+                EXISTS(SELECT 1 FROM repos AS nested WHERE nested.nested_in = repos.path)
             FROM repos WHERE id = ?1",
             [id],
             |row| {
@@ -539,6 +1431,26 @@ impl Index {
                     project: row.get(21)?,
                     role: row.get(22)?,
                     managed_by: row.get(23)?,
+                    note: row.get(24)?,
+                    default_branch_drift: row.get(25)?,
+                    head_signed: row.get(26)?,
+                    snapshot_dirty: row.get(27)?,
+                    snapshot_ahead: row.get(28)?,
+                    snapshot_last_commit: row.get(29)?,
+                    last_fetched: row.get(30)?,
+                    commits_last_30d: row.get(31)?,
+                    origin_org: row.get(32)?,
+                    last_commit_offset: row.get(33)?,
+                    is_empty: row.get(34)?,
+                    branches_truncated: row.get(35)?,
+                    nested_in: row.get(36)?,
+                    status_checked: row.get(37)?,
+                    upstream_description: row.get(38)?,
+                    upstream_archived: row.get(39)?,
+                    upstream_stars: row.get(40)?,
+                    upstream_default_branch: row.get(41)?,
+                    upstream_fetched_at: row.get(42)?,
+                    contains_nested: row.get(43)?,
                 })
             },
         )?;
@@ -576,6 +1488,199 @@ impl Index {
     }
 }
 
+/// Whether an error is SQLite reporting lock contention.
+fn is_busy(err: &KissaError) -> bool {
+    matches!(
+        err,
+        KissaError::Index(rusqlite::Error::SqliteFailure(e, _))
+            if matches!(
+                e.code,
+                rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked
+            )
+    )
+}
+
+/// SQL condition matching `path` at or under `root`, binding from `?{next}`.
+/// Compares with `substr` rather than LIKE so `_` and `%` in paths match literally.
+fn under_clause(root: &Path, next: usize) -> (String, Vec<String>) {
+    let root = root
+        .to_string_lossy()
+        .trim_end_matches(std::path::MAIN_SEPARATOR)
+        .to_string();
+    let dir = format!("{}{}", root, std::path::MAIN_SEPARATOR);
+    (
+        format!(
+            "(path = ?{a} OR substr(path, 1, length(?{b})) = ?{b})",
+            a = next,
+            b = next + 1
+        ),
+        vec![root, dir],
+    )
+}
+
+/// Run a write, retrying with exponential backoff while the database is busy.
+/// The closure must be safe to re-run: wrap multi-statement writes in a transaction.
+fn retry_busy<T>(mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delay = Duration::from_millis(50);
+    for _ in 0..BUSY_RETRIES {
+        match op() {
+            Err(e) if is_busy(&e) => {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            other => return other,
+        }
+    }
+    op()
+}
+
+/// What an upsert does with the user metadata of a repo already indexed.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MergePolicy {
+    /// Overwrite it from the incoming repo, except the note.
+    Replace,
+    /// Keep what is stored; only git-derived columns change.
+    KeepMetadata,
+}
+
+/// Map an ownership value onto its (ownership_type, ownership_label) columns.
+fn ownership_columns(ownership: Option<&Ownership>) -> (Option<&'static str>, Option<&str>) {
+    match ownership {
+        Some(Ownership::Personal) => (Some("personal"), None),
+        Some(Ownership::Work { label }) => (Some("work"), Some(label.as_str())),
+        Some(Ownership::Community) => (Some("community"), None),
+        Some(Ownership::ThirdParty) => (Some("thirdparty"), None),
+        Some(Ownership::Local) => (Some("local"), None),
+        None => (None, None),
+    }
+}
+
+/// Build the SQL WHERE clause for the column-backed parts of a filter.
+/// Anything not covered here is left to `RepoFilter::matches()`.
+fn filter_where_clause(filter: &RepoFilter) -> (String, Vec<Box<dyn rusqlite::types::ToSql>>) {
+    let mut where_clauses = vec!["1=1".to_string()];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(dirty) = filter.dirty {
+        where_clauses.push(format!("dirty = ?{}", params.len() + 1));
+        params.push(Box::new(dirty));
+    }
+    if let Some(ref state) = filter.state {
+        let s = serde_plain::to_string(state).unwrap_or_else(|_| "active".into());
+        where_clauses.push(format!("state = ?{}", params.len() + 1));
+        params.push(Box::new(s));
+    }
+    if let Some(ref freshness) = filter.freshness {
+        let s = serde_plain::to_string(freshness).unwrap_or_else(|_| "ancient".into());
+        where_clauses.push(format!("freshness = ?{}", params.len() + 1));
+        params.push(Box::new(s));
+    }
+    if let Some(drift) = filter.branch_drift {
+        where_clauses.push(format!("default_branch_drift = ?{}", params.len() + 1));
+        params.push(Box::new(drift));
+    }
+    if let Some(stale) = filter.has_stale_branches {
+        where_clauses.push(
+            if stale {
+                "stale_branch_count > 0"
+            } else {
+                "stale_branch_count = 0"
+            }
+            .into(),
+        );
+    }
+    if let Some(signed) = filter.head_signed {
+        where_clauses.push(format!("head_signed = ?{}", params.len() + 1));
+        params.push(Box::new(signed));
+    }
+    if let Some(off) = filter.on_non_default_branch {
+        where_clauses.push(format!(
+            "IFNULL(current_branch != default_branch, 0) = ?{}",
+            params.len() + 1
+        ));
+        params.push(Box::new(off));
+    }
+    if let Some(detached) = filter.detached {
+        where_clauses.push(format!("(current_branch IS NULL) = ?{}", params.len() + 1));
+        params.push(Box::new(detached));
+    }
+    if let Some(empty) = filter.empty {
+        where_clauses.push(format!("is_empty = ?{}", params.len() + 1));
+        params.push(Box::new(empty));
+    }
+    if let Some(nested) = filter.nested {
+        where_clauses.push(format!(
+            "(nested_in IS NOT NULL OR EXISTS(SELECT 1 FROM repos AS nested \
+             WHERE nested.nested_in = repos.path)) = ?{}",
+            params.len() + 1
+        ));
+        params.push(Box::new(nested));
+    }
+    if let Some(changed) = filter.changed_since_scan {
+        let moved = "(dirty != snapshot_dirty OR ahead != snapshot_ahead \
+                     OR last_commit IS NOT snapshot_last_commit)";
+        where_clauses.push(if changed {
+            format!("snapshot_dirty IS NOT NULL AND {}", moved)
+        } else {
+            format!("(snapshot_dirty IS NULL OR NOT {})", moved)
+        });
+    }
+    if let Some(cutoff) = filter.not_fetched_since {
+        where_clauses.push(format!(
+            "(last_fetched IS NULL OR last_fetched < ?{})",
+            params.len() + 1
+        ));
+        params.push(Box::new(cutoff.to_rfc3339()));
+    }
+    if let Some(ref prefix) = filter.path_prefix {
+        where_clauses.push(format!("path LIKE ?{}", params.len() + 1));
+        params.push(Box::new(format!("{}%", prefix)));
+    }
+    if let Some(ref name) = filter.name_contains {
+        where_clauses.push(format!("name LIKE ?{}", params.len() + 1));
+        params.push(Box::new(format!("%{}%", name)));
+    }
+    if let Some(ref mb) = filter.managed_by {
+        where_clauses.push(format!("managed_by = ?{}", params.len() + 1));
+        params.push(Box::new(mb.clone()));
+    }
+    if let Some(show) = filter.show_managed {
+        if show {
+            where_clauses.push("managed_by IS NOT NULL".to_string());
+        } else {
+            where_clauses.push("managed_by IS NULL".to_string());
+        }
+    }
+    if let Some(show) = filter.show_archived {
+        if show {
+            where_clauses.push("intention = 'archived'".to_string());
+        } else {
+            where_clauses.push("intention IS NOT 'archived'".to_string());
+        }
+    }
+    if let Some(show) = filter.show_lost {
+        if show {
+            where_clauses.push("state = 'lost'".to_string());
+        } else {
+            where_clauses.push("state != 'lost'".to_string());
+        }
+    }
+
+    (where_clauses.join(" AND "), params)
+}
+
+/// Translate free text into an FTS5 query: each word becomes a quoted prefix
+/// term (implicitly AND-ed), so user input can't trip FTS5 query syntax.
+/// Returns None when there is nothing searchable.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -583,99 +1688,425 @@ mod tests {
 
     fn make_repo(name: &str, path: &str) -> Repo {
         Repo {
-            id: 0,
-            name: name.to_string(),
-            path: PathBuf::from(path),
-            state: RepoState::Active,
             remotes: vec![Remote {
                 name: "origin".into(),
                 url: "git@github.com:initech/api-gateway.git".into(),
                 push_url: None,
             }],
-            default_branch: Some("main".into()),
             current_branch: Some("feature/auth".into()),
             branch_count: 3,
             stale_branch_count: 1,
+            status_checked: Some(Utc::now()),
             dirty: true,
-            staged: false,
             untracked: true,
             ahead: 2,
-            behind: 0,
-            last_commit: Some(Utc::now()),
-            last_verified: Some(Utc::now()),
-            first_seen: Utc::now(),
-            freshness: Freshness::Active,
             category: Some(Category::Origin),
             ownership: Some(Ownership::Work {
                 label: "initech".into(),
             }),
             intention: Some(Intention::Developing),
-            managed_by: None,
             tags: vec!["rust".into(), "backend".into()],
             project: Some("platform".into()),
             role: Some("service".into()),
+            ..Repo::test_default(name, path)
         }
     }
 
     #[test]
-    fn open_in_memory_and_migrate() {
+    fn open_in_memory_and_migrate() {
+        let idx = Index::open_in_memory().unwrap();
+        assert_eq!(idx.schema_version(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn upsert_and_get_by_path() {
+        let idx = Index::open_in_memory().unwrap();
+        let repo = make_repo("api-gateway", "/home/user/code/api-gateway");
+
+        let id = idx.upsert_repo(&repo).unwrap();
+        assert!(id > 0);
+
+        let loaded = idx
+            .get_repo_by_path(Path::new("/home/user/code/api-gateway"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.name, "api-gateway");
+        assert!(loaded.dirty);
+        assert_eq!(loaded.ahead, 2);
+        assert_eq!(loaded.remotes.len(), 1);
+        assert_eq!(loaded.remotes[0].name, "origin");
+        let mut tags = loaded.tags.clone();
+        tags.sort();
+        assert_eq!(tags, vec!["backend", "rust"]);
+        assert_eq!(loaded.freshness, Freshness::Active);
+        assert_eq!(loaded.state, RepoState::Active);
+        assert_eq!(
+            loaded.ownership,
+            Some(Ownership::Work {
+                label: "initech".into()
+            })
+        );
+        assert_eq!(loaded.category, Some(Category::Origin));
+        assert_eq!(loaded.intention, Some(Intention::Developing));
+        assert_eq!(loaded.project, Some("platform".into()));
+    }
+
+    #[test]
+    fn upsert_updates_existing() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut repo = make_repo("api-gateway", "/home/user/code/api-gateway");
+
+        idx.upsert_repo(&repo).unwrap();
+
+        repo.dirty = false;
+        repo.ahead = 0;
+        repo.tags = vec!["rust".into(), "backend".into(), "v2".into()];
+
+        idx.upsert_repo(&repo).unwrap();
+
+        let loaded = idx
+            .get_repo_by_path(Path::new("/home/user/code/api-gateway"))
+            .unwrap()
+            .unwrap();
+        assert!(!loaded.dirty);
+        assert_eq!(loaded.ahead, 0);
+        assert_eq!(loaded.tags.len(), 3);
+    }
+
+    #[test]
+    fn vitals_upsert_keeps_user_metadata() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut repo = make_repo("api-gateway", "/code/api");
+        repo.tags = vec!["rule-tag".into()];
+        let id = idx.upsert_vitals(&repo).unwrap();
+        assert_eq!(
+            idx.get_repo_by_path(Path::new("/code/api"))
+                .unwrap()
+                .unwrap()
+                .tags,
+            repo.tags
+        );
+
+        idx.set_tags(id, &["hand-tagged".to_string()]).unwrap();
+        idx.set_intention(id, Some(Intention::Reference)).unwrap();
+
+        // A rescan builds the repo afresh: no intention, no hand tags
+        let mut rescanned = make_repo("api-gateway", "/code/api");
+        rescanned.tags.clear();
+        rescanned.intention = None;
+        rescanned.ownership = None;
+        rescanned.ahead = 7;
+        idx.upsert_vitals(&rescanned).unwrap();
+
+        let loaded = idx
+            .get_repo_by_path(Path::new("/code/api"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.ahead, 7);
+        assert_eq!(loaded.tags, vec!["hand-tagged".to_string()]);
+        assert_eq!(loaded.intention, Some(Intention::Reference));
+        assert_eq!(loaded.ownership, repo.ownership);
+
+        // A plain upsert still writes everything
+        idx.upsert_repo(&rescanned).unwrap();
+        let loaded = idx
+            .get_repo_by_path(Path::new("/code/api"))
+            .unwrap()
+            .unwrap();
+        assert!(loaded.tags.is_empty());
+        assert_eq!(loaded.intention, None);
+    }
+
+    #[test]
+    fn targeted_updates_leave_other_columns_alone() {
+        let idx = Index::open_in_memory().unwrap();
+        let repo = make_repo("api-gateway", "/code/api");
+        let id = idx.upsert_repo(&repo).unwrap();
+
+        idx.set_tags(id, &["ops".to_string()]).unwrap();
+        idx.set_ownership(id, Some(&Ownership::Personal)).unwrap();
+        idx.set_intention(id, Some(Intention::Reference)).unwrap();
+        idx.set_note(id, Some("keep an eye on this")).unwrap();
+
+        let loaded = idx
+            .get_repo_by_path(Path::new("/code/api"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.tags, vec!["ops"]);
+        assert_eq!(loaded.ownership, Some(Ownership::Personal));
+        assert_eq!(loaded.intention, Some(Intention::Reference));
+        assert_eq!(loaded.note.as_deref(), Some("keep an eye on this"));
+
+        // Untouched vitals and metadata
+        assert!(loaded.dirty);
+        assert_eq!(loaded.ahead, 2);
+        assert_eq!(loaded.current_branch.as_deref(), Some("feature/auth"));
+        assert_eq!(loaded.remotes.len(), 1);
+        assert_eq!(loaded.category, Some(Category::Origin));
+        assert_eq!(loaded.project.as_deref(), Some("platform"));
+    }
+
+    #[test]
+    fn set_classification_keeps_vitals() {
+        let idx = Index::open_in_memory().unwrap();
+        let id = idx.upsert_repo(&make_repo("a", "/code/a")).unwrap();
+
+        // Stale in-memory copy with different vitals must not clobber the row
+        let mut stale = idx.get_repo_by_path(Path::new("/code/a")).unwrap().unwrap();
+        stale.dirty = false;
+        stale.ahead = 0;
+        stale.managed_by = Some("cargo".into());
+        stale.ownership = None;
+        stale.tags = vec!["dep".into()];
+        idx.set_classification(&stale).unwrap();
+
+        let loaded = idx.get_repo_by_path(Path::new("/code/a")).unwrap().unwrap();
+        assert_eq!(loaded.id, id);
+        assert_eq!(loaded.managed_by.as_deref(), Some("cargo"));
+        assert!(loaded.ownership.is_none());
+        assert_eq!(loaded.tags, vec!["dep"]);
+        assert!(loaded.dirty);
+        assert_eq!(loaded.ahead, 2);
+    }
+
+    #[test]
+    fn note_survives_rescan_upsert() {
+        let idx = Index::open_in_memory().unwrap();
+        let id = idx.upsert_repo(&make_repo("a", "/code/a")).unwrap();
+        idx.set_note(id, Some("ask about license")).unwrap();
+
+        // A fresh scan produces a Repo without the note
+        idx.upsert_repo(&make_repo("a", "/code/a")).unwrap();
+        let loaded = idx.get_repo_by_path(Path::new("/code/a")).unwrap().unwrap();
+        assert_eq!(loaded.note.as_deref(), Some("ask about license"));
+    }
+
+    #[test]
+    fn targeted_update_unknown_id_errors() {
+        let idx = Index::open_in_memory().unwrap();
+        assert!(matches!(
+            idx.set_note(999, Some("x")),
+            Err(KissaError::RepoNotFound(_))
+        ));
+        assert!(matches!(
+            idx.set_tags(999, &[]),
+            Err(KissaError::RepoNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn repos_under_respects_component_boundaries() {
+        let idx = Index::open_in_memory().unwrap();
+        for (name, path) in [
+            ("a", "/code/a"),
+            ("a-child", "/code/a/b"),
+            ("ab", "/code/ab"),
+            ("odd", "/code/a_x"),
+            ("elsewhere", "/srv/a"),
+        ] {
+            idx.upsert_repo(&make_repo(name, path)).unwrap();
+        }
+        let names = |repos: Vec<Repo>| repos.into_iter().map(|r| r.name).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(idx.repos_under(Path::new("/code/a")).unwrap()),
+            vec!["a", "a-child"]
+        );
+        assert_eq!(
+            names(idx.repos_under(Path::new("/code/a/")).unwrap()),
+            vec!["a", "a-child"]
+        );
+        assert_eq!(
+            names(idx.repos_under(Path::new("/code/a/b")).unwrap()),
+            vec!["a-child"]
+        );
+        assert!(idx.repos_under(Path::new("/code/a_")).unwrap().is_empty());
+        assert_eq!(idx.repos_under(Path::new("/")).unwrap().len(), 5);
+
+        let scoped = idx.with_root(Some(PathBuf::from("/code/a/b")));
+        assert_eq!(
+            names(scoped.repos_under(Path::new("/code")).unwrap()),
+            vec!["a-child"]
+        );
+    }
+
+    #[test]
+    fn root_scope_limits_reads() {
+        let idx = Index::open_in_memory()
+            .unwrap()
+            .with_root(Some(PathBuf::from("/code/work/")));
+        idx.upsert_repo(&make_repo("api", "/code/work/api"))
+            .unwrap();
+        idx.upsert_repo(&make_repo("web", "/code/work_old/web"))
+            .unwrap();
+        idx.upsert_repo(&make_repo("dots", "/home/me/dots"))
+            .unwrap();
+
+        let repos = idx.list_repos(&RepoFilter::default()).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "api");
+        assert_eq!(idx.count_repos(&RepoFilter::default()).unwrap(), 1);
+        assert_eq!(idx.summary().unwrap().total_repos, 1);
+        assert_eq!(idx.freshness_summary().unwrap().active, 1);
+
+        assert!(idx.get_repo_by_name("api").unwrap().is_some());
+        assert!(idx.get_repo_by_name("dots").unwrap().is_none());
+        // Exact-path lookups ignore the scope
+        assert!(
+            idx.get_repo_by_path(Path::new("/home/me/dots"))
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    fn busy_error() -> KissaError {
+        KissaError::Index(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY),
+            None,
+        ))
+    }
+
+    #[test]
+    fn retry_busy_retries_then_succeeds() {
+        let mut attempts = 0;
+        let result = retry_busy(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(busy_error())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn retry_busy_gives_up_and_passes_other_errors_through() {
+        let mut attempts = 0;
+        let result: Result<()> = retry_busy(|| {
+            attempts += 1;
+            Err(busy_error())
+        });
+        assert!(is_busy(&result.unwrap_err()));
+        assert_eq!(attempts, BUSY_RETRIES + 1);
+
+        let mut attempts = 0;
+        let result: Result<()> = retry_busy(|| {
+            attempts += 1;
+            Err(KissaError::RepoNotFound("x".into()))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn concurrent_writer_waits_for_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.db");
+        let idx = Index::open(&path).unwrap();
+
+        // Another process (e.g. the MCP server) holds the write lock briefly
+        let other = rusqlite::Connection::open(&path).unwrap();
+        other.execute_batch("BEGIN IMMEDIATE").unwrap();
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            other.execute_batch("COMMIT").unwrap();
+        });
+
+        idx.upsert_repo(&make_repo("a", "/code/a")).unwrap();
+        holder.join().unwrap();
+        assert!(
+            idx.get_repo_by_path(Path::new("/code/a"))
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn ignored_paths_stick_until_unignored() {
         let idx = Index::open_in_memory().unwrap();
-        assert_eq!(idx.schema_version(), SCHEMA_VERSION);
+        let vendored = Path::new("/code/app/vendor/lib");
+        assert!(!idx.is_ignored(vendored).unwrap());
+
+        idx.ignore_path(vendored).unwrap();
+        idx.ignore_path(vendored).unwrap();
+        assert!(idx.is_ignored(vendored).unwrap());
+        assert_eq!(
+            idx.ignored_paths().unwrap(),
+            HashSet::from([vendored.to_path_buf()])
+        );
+        assert!(!idx.is_ignored(Path::new("/code/app")).unwrap());
+
+        assert!(idx.unignore_path(vendored).unwrap());
+        assert!(!idx.unignore_path(vendored).unwrap());
+        assert!(!idx.is_ignored(vendored).unwrap());
     }
 
     #[test]
-    fn upsert_and_get_by_path() {
+    fn forget_managed_removes_only_managed() {
         let idx = Index::open_in_memory().unwrap();
-        let repo = make_repo("api-gateway", "/home/user/code/api-gateway");
+        let mut plugin = make_repo("telescope", "/home/me/.local/share/nvim/lazy/telescope");
+        plugin.managed_by = Some("lazy.nvim".into());
+        idx.upsert_repo(&plugin).unwrap();
+        idx.upsert_repo(&make_repo("api", "/code/api")).unwrap();
+
+        assert_eq!(idx.forget_managed().unwrap(), 1);
+        let remaining = idx.all_repos().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "api");
+        assert_eq!(idx.forget_managed().unwrap(), 0);
+    }
 
-        let id = idx.upsert_repo(&repo).unwrap();
-        assert!(id > 0);
+    #[test]
+    fn forget_managed_stays_within_the_root_scope() {
+        let idx = Index::open_in_memory().unwrap();
+        for path in ["/code/tools/lazy/a", "/home/me/.cargo/git/checkouts/b"] {
+            let mut repo = make_repo("plugin", path);
+            repo.managed_by = Some("test".into());
+            idx.upsert_repo(&repo).unwrap();
+        }
 
-        let loaded = idx
-            .get_repo_by_path(Path::new("/home/user/code/api-gateway"))
-            .unwrap()
-            .unwrap();
-        assert_eq!(loaded.name, "api-gateway");
-        assert!(loaded.dirty);
-        assert_eq!(loaded.ahead, 2);
-        assert_eq!(loaded.remotes.len(), 1);
-        assert_eq!(loaded.remotes[0].name, "origin");
-        let mut tags = loaded.tags.clone();
-        tags.sort();
-        assert_eq!(tags, vec!["backend", "rust"]);
-        assert_eq!(loaded.freshness, Freshness::Active);
-        assert_eq!(loaded.state, RepoState::Active);
+        let scoped = idx.with_root(Some("/code/tools".into()));
+        assert_eq!(scoped.forget_managed().unwrap(), 1);
+        let remaining = scoped.with_root(None).all_repos().unwrap();
+        assert_eq!(remaining.len(), 1);
         assert_eq!(
-            loaded.ownership,
-            Some(Ownership::Work {
-                label: "initech".into()
-            })
+            remaining[0].path,
+            PathBuf::from("/home/me/.cargo/git/checkouts/b")
         );
-        assert_eq!(loaded.category, Some(Category::Origin));
-        assert_eq!(loaded.intention, Some(Intention::Developing));
-        assert_eq!(loaded.project, Some("platform".into()));
     }
 
     #[test]
-    fn upsert_updates_existing() {
+    fn changed_since_scan_diffs_against_snapshot() {
         let idx = Index::open_in_memory().unwrap();
-        let mut repo = make_repo("api-gateway", "/home/user/code/api-gateway");
+        let a = idx.upsert_repo(&make_repo("a", "/code/a")).unwrap();
+        let b = idx.upsert_repo(&make_repo("b", "/code/b")).unwrap();
+        idx.upsert_repo(&make_repo("never-scanned", "/code/c"))
+            .unwrap();
+        idx.record_scan_snapshot(a).unwrap();
+        idx.record_scan_snapshot(b).unwrap();
 
-        idx.upsert_repo(&repo).unwrap();
+        let changed = RepoFilter {
+            changed_since_scan: Some(true),
+            ..Default::default()
+        };
+        assert!(idx.list_repos(&changed).unwrap().is_empty());
 
-        repo.dirty = false;
-        repo.ahead = 0;
-        repo.tags = vec!["rust".into(), "backend".into(), "v2".into()];
+        // A later refresh (not a scan) moves `a`
+        let mut refreshed = idx.get_repo_by_path(Path::new("/code/a")).unwrap().unwrap();
+        refreshed.ahead = 5;
+        idx.upsert_repo(&refreshed).unwrap();
 
-        idx.upsert_repo(&repo).unwrap();
+        let moved = idx.list_repos(&changed).unwrap();
+        assert_eq!(moved.len(), 1);
+        assert_eq!(moved[0].name, "a");
+        assert_eq!(moved[0].scan_changes(), vec!["ahead 2 → 5"]);
 
-        let loaded = idx
-            .get_repo_by_path(Path::new("/home/user/code/api-gateway"))
-            .unwrap()
-            .unwrap();
-        assert!(!loaded.dirty);
-        assert_eq!(loaded.ahead, 0);
-        assert_eq!(loaded.tags.len(), 3);
+        let unchanged = RepoFilter {
+            changed_since_scan: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(idx.count_repos(&unchanged).unwrap(), 2);
     }
 
     #[test]
@@ -702,6 +2133,105 @@ mod tests {
         assert!(idx.get_repo_by_name("nonexistent").unwrap().is_none());
     }
 
+    #[test]
+    fn name_collisions_follow_strategy() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut old = make_repo("api-old", "/code/api-old");
+        old.last_commit = Some(Utc::now() - chrono::Duration::days(90));
+        let mut new = make_repo("api-new", "/code/api-new");
+        new.last_commit = Some(Utc::now());
+        idx.upsert_repo(&old).unwrap();
+        idx.upsert_repo(&new).unwrap();
+
+        let pick = |strategy| {
+            idx.resolve_repo_name("api", false, strategy)
+                .unwrap()
+                .unwrap()
+                .name
+        };
+        assert_eq!(pick(NameCollision::First), "api-old");
+        assert_eq!(pick(NameCollision::MostRecent), "api-new");
+
+        let err = idx
+            .resolve_repo_name("api", false, NameCollision::Error)
+            .unwrap_err();
+        let KissaError::AmbiguousName { candidates, .. } = err else {
+            panic!("expected AmbiguousName, got {err:?}");
+        };
+        assert_eq!(
+            candidates,
+            vec!["/code/api-old".to_string(), "/code/api-new".to_string()]
+        );
+
+        // An exact match wins before prefixes are considered
+        let exact = idx
+            .resolve_repo_name("api-new", false, NameCollision::Error)
+            .unwrap();
+        assert_eq!(exact.unwrap().name, "api-new");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn upsert_canonicalizes_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("x");
+        std::fs::create_dir(&real).unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+        let canonical = std::fs::canonicalize(&real).unwrap();
+
+        let idx = Index::open_in_memory().unwrap();
+        let id = idx
+            .upsert_repo(&make_repo("x", real.to_str().unwrap()))
+            .unwrap();
+        for alias in [dir.path().join(".").join("x"), dir.path().join("link")] {
+            let again = make_repo("x", alias.to_str().unwrap());
+            assert_eq!(idx.upsert_repo(&again).unwrap(), id);
+        }
+        let repos = idx.all_repos().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].path, canonical);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn collapse_merges_rows_for_the_same_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("x");
+        std::fs::create_dir(&real).unwrap();
+        std::os::unix::fs::symlink(&real, dir.path().join("link")).unwrap();
+        let canonical = std::fs::canonicalize(&real).unwrap();
+
+        let idx = Index::open_in_memory().unwrap();
+        let mut original = make_repo("x", real.to_str().unwrap());
+        original.tags = vec!["keep".into()];
+        original.project = None;
+        let keep = idx.upsert_repo(&original).unwrap();
+
+        // A row from before upserts canonicalized, stored via the symlink
+        let mut legacy = make_repo("x", "/placeholder");
+        legacy.tags = vec!["extra".into()];
+        legacy.project = Some("platform".into());
+        let dup = idx.upsert_repo(&legacy).unwrap();
+        idx.conn
+            .execute(
+                "UPDATE repos SET path = ?1 WHERE id = ?2",
+                rusqlite::params![dir.path().join("link").to_string_lossy(), dup],
+            )
+            .unwrap();
+
+        assert_eq!(idx.collapse_duplicate_paths().unwrap(), 1);
+        let repos = idx.all_repos().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].id, keep);
+        assert_eq!(repos[0].path, canonical);
+        assert_eq!(repos[0].project.as_deref(), Some("platform"));
+        let mut tags = repos[0].tags.clone();
+        tags.sort();
+        assert_eq!(tags, vec!["extra", "keep"]);
+
+        assert_eq!(idx.collapse_duplicate_paths().unwrap(), 0);
+    }
+
     #[test]
     fn list_repos_empty_filter() {
         let idx = Index::open_in_memory().unwrap();
@@ -731,10 +2261,153 @@ mod tests {
         assert_eq!(repos[0].name, "dirty-repo");
     }
 
+    #[test]
+    fn list_repos_stale_branch_filter() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut crufty = make_repo("crufty", "/code/crufty");
+        crufty.stale_branch_count = 3;
+        let mut tidy = make_repo("tidy", "/code/tidy");
+        tidy.stale_branch_count = 0;
+        idx.upsert_repo(&crufty).unwrap();
+        idx.upsert_repo(&tidy).unwrap();
+
+        let stale = idx
+            .list_repos(&RepoFilter::new().has_stale_branches(true))
+            .unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].name, "crufty");
+        let none = idx
+            .count_repos(&RepoFilter::new().has_stale_branches(false))
+            .unwrap();
+        assert_eq!(none, 1);
+    }
+
+    #[test]
+    fn list_repos_empty_filter_and_summary() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut fresh = make_repo("fresh", "/code/fresh");
+        fresh.is_empty = true;
+        fresh.last_commit = None;
+        fresh.freshness = Freshness::Empty;
+        idx.upsert_repo(&fresh).unwrap();
+        idx.upsert_repo(&make_repo("used", "/code/used")).unwrap();
+
+        let empty = idx.list_repos(&RepoFilter::new().empty(true)).unwrap();
+        assert_eq!(empty.len(), 1);
+        assert!(empty[0].is_empty);
+        assert_eq!(empty[0].freshness, Freshness::Empty);
+
+        let summary = idx.freshness_summary().unwrap();
+        assert_eq!(summary.empty, 1);
+        assert_eq!(summary.ancient, 0);
+        assert_eq!(summary.total(), 2);
+    }
+
+    #[test]
+    fn upsert_logs_remote_url_changes() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut repo = make_repo("api", "/code/api");
+        let id = idx.upsert_repo(&repo).unwrap();
+        idx.upsert_repo(&repo).unwrap(); // unchanged: no new row
+
+        repo.remotes[0].url = "git@github.com:globex/api-gateway.git".into();
+        repo.remotes.push(Remote {
+            name: "upstream".into(),
+            url: "https://gitlab.com/initech/api-gateway.git".into(),
+            push_url: None,
+        });
+        idx.upsert_repo(&repo).unwrap();
+
+        let history: Vec<(String, String)> = idx
+            .remote_url_history(id)
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.remote, r.url))
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                (
+                    "origin".into(),
+                    "git@github.com:initech/api-gateway.git".into()
+                ),
+                (
+                    "origin".into(),
+                    "git@github.com:globex/api-gateway.git".into()
+                ),
+                (
+                    "upstream".into(),
+                    "https://gitlab.com/initech/api-gateway.git".into()
+                ),
+            ]
+        );
+
+        idx.forget_repo(id).unwrap();
+        assert!(idx.remote_url_history(id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn list_repos_nested_filter() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut stray = make_repo("stray", "/code/app/stray");
+        stray.nested_in = Some(PathBuf::from("/code/app"));
+        idx.upsert_repo(&stray).unwrap();
+        idx.upsert_repo(&make_repo("app", "/code/app")).unwrap();
+        idx.upsert_repo(&make_repo("web", "/code/web")).unwrap();
+
+        // Both the stray and the repo it sits in are flagged
+        let nested = idx.list_repos(&RepoFilter::new().nested(true)).unwrap();
+        assert_eq!(nested.len(), 2);
+        let stray = nested.iter().find(|r| r.name == "stray").unwrap();
+        assert_eq!(stray.nested_in.as_deref(), Some(Path::new("/code/app")));
+        assert!(!stray.contains_nested);
+        let app = nested.iter().find(|r| r.name == "app").unwrap();
+        assert!(app.contains_nested && app.nested_in.is_none());
+        assert_eq!(
+            idx.count_repos(&RepoFilter::new().nested(false)).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn list_repos_branch_position_filters() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut feature = make_repo("feature", "/code/feature");
+        feature.default_branch = Some("main".into());
+        feature.current_branch = Some("wip".into());
+        let mut on_main = make_repo("on-main", "/code/on-main");
+        on_main.default_branch = Some("main".into());
+        on_main.current_branch = Some("main".into());
+        let mut headless = make_repo("headless", "/code/headless");
+        headless.current_branch = None;
+        for repo in [&feature, &on_main, &headless] {
+            idx.upsert_repo(repo).unwrap();
+        }
+
+        let names = |filter: RepoFilter| -> Vec<String> {
+            idx.list_repos(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect()
+        };
+        assert_eq!(
+            names(RepoFilter::new().on_non_default_branch(true)),
+            vec!["feature"]
+        );
+        assert_eq!(names(RepoFilter::new().detached(true)), vec!["headless"]);
+        assert_eq!(
+            idx.count_repos(&RepoFilter::new().on_non_default_branch(false))
+                .unwrap(),
+            2
+        );
+    }
+
     #[test]
     fn list_repos_name_filter() {
         let idx = Index::open_in_memory().unwrap();
-        idx.upsert_repo(&make_repo("api-gateway", "/code/api")).unwrap();
+        idx.upsert_repo(&make_repo("api-gateway", "/code/api"))
+            .unwrap();
         idx.upsert_repo(&make_repo("web-app", "/code/web")).unwrap();
 
         let filter = RepoFilter {
@@ -746,6 +2419,82 @@ mod tests {
         assert_eq!(repos[0].name, "api-gateway");
     }
 
+    #[test]
+    fn count_repos_matches_list() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut clean = make_repo("clean", "/code/clean");
+        clean.dirty = false;
+        let mut other_org = make_repo("other", "/code/other");
+        other_org.remotes[0].url = "git@github.com:vandelay/import.git".into();
+
+        idx.upsert_repo(&make_repo("dirty", "/code/dirty")).unwrap();
+        idx.upsert_repo(&clean).unwrap();
+        idx.upsert_repo(&other_org).unwrap();
+
+        assert_eq!(idx.count_repos(&RepoFilter::default()).unwrap(), 3);
+
+        // SQL-only path
+        let dirty = RepoFilter {
+            dirty: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(idx.count_repos(&dirty).unwrap(), 2);
+
+        // In-memory path
+        let org = RepoFilter {
+            dirty: Some(true),
+            org: Some("initech".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            idx.count_repos(&org).unwrap(),
+            idx.list_repos(&org).unwrap().len()
+        );
+        assert_eq!(idx.count_repos(&org).unwrap(), 1);
+    }
+
+    #[test]
+    fn list_repos_hides_archived() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut old = make_repo("old", "/code/old");
+        old.intention = Some(Intention::Archived);
+        let mut unclassified = make_repo("new", "/code/new");
+        unclassified.intention = None;
+        idx.upsert_repo(&old).unwrap();
+        idx.upsert_repo(&unclassified).unwrap();
+
+        let filter = RepoFilter {
+            show_archived: Some(false),
+            ..Default::default()
+        };
+        let repos = idx.list_repos(&filter).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "new");
+
+        // Archived repos still count toward index totals
+        assert_eq!(idx.summary().unwrap().total_repos, 2);
+    }
+
+    #[test]
+    fn open_readonly_rejects_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.db");
+        Index::open(&path)
+            .unwrap()
+            .upsert_repo(&make_repo("a", "/code/a"))
+            .unwrap();
+
+        let ro = Index::open_readonly(&path).unwrap();
+        assert_eq!(ro.all_repos().unwrap().len(), 1);
+        assert!(ro.upsert_repo(&make_repo("b", "/code/b")).is_err());
+    }
+
+    #[test]
+    fn open_readonly_missing_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(Index::open_readonly(&dir.path().join("nope.db")).is_err());
+    }
+
     #[test]
     fn list_repos_org_filter_in_memory() {
         let idx = Index::open_in_memory().unwrap();
@@ -768,6 +2517,113 @@ mod tests {
         assert_eq!(repos[0].name, "a");
     }
 
+    #[test]
+    fn timeout_state_is_listed_and_cleared() {
+        let idx = Index::open_in_memory().unwrap();
+        let id = idx.upsert_repo(&make_repo("nfs", "/mnt/nfs/app")).unwrap();
+        idx.upsert_repo(&make_repo("local", "/code/local")).unwrap();
+
+        idx.mark_timeout(id).unwrap();
+        let timed_out = idx
+            .list_repos(&RepoFilter::new().state(RepoState::Timeout))
+            .unwrap();
+        assert_eq!(timed_out.len(), 1);
+        assert_eq!(timed_out[0].name, "nfs");
+        // Unlike lost repos, timed-out ones stay in default listings
+        assert_eq!(
+            idx.count_repos(&RepoFilter::new().show_lost(false))
+                .unwrap(),
+            2
+        );
+
+        idx.mark_active(id).unwrap();
+        assert_eq!(
+            idx.count_repos(&RepoFilter::new().state(RepoState::Timeout))
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn skipped_status_keeps_cached_flags() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut repo = make_repo("slow", "/mnt/nfs/slow");
+        repo.dirty = true;
+        repo.untracked = true;
+        repo.status_checked = repo.last_verified;
+        idx.upsert_repo(&repo).unwrap();
+
+        // A --no-status rescan reports clean placeholders and no status time
+        let checked = repo.status_checked;
+        repo.dirty = false;
+        repo.untracked = false;
+        repo.status_checked = None;
+        repo.last_verified = Some(Utc::now() + chrono::Duration::seconds(5));
+        idx.upsert_repo(&repo).unwrap();
+
+        let r = idx
+            .get_repo_by_path(Path::new("/mnt/nfs/slow"))
+            .unwrap()
+            .unwrap();
+        assert!(r.dirty && r.untracked);
+        assert_eq!(r.status_checked, checked);
+        assert!(r.status_is_stale());
+
+        repo.status_checked = repo.last_verified;
+        idx.upsert_repo(&repo).unwrap();
+        let r = idx
+            .get_repo_by_path(Path::new("/mnt/nfs/slow"))
+            .unwrap()
+            .unwrap();
+        assert!(!r.dirty && !r.status_is_stale());
+    }
+
+    #[test]
+    fn upstream_info_survives_rescans() {
+        let idx = Index::open_in_memory().unwrap();
+        let repo = make_repo("kissa", "/code/kissa");
+        let id = idx.upsert_repo(&repo).unwrap();
+        let path = Path::new("/code/kissa");
+        assert!(
+            idx.get_repo_by_path(path)
+                .unwrap()
+                .unwrap()
+                .upstream
+                .is_none()
+        );
+
+        let info = UpstreamInfo {
+            description: Some("Finally herd your repos.".into()),
+            archived: true,
+            stars: 42,
+            default_branch: Some("main".into()),
+            fetched_at: Utc::now(),
+        };
+        idx.set_upstream(id, &info).unwrap();
+        idx.upsert_repo(&repo).unwrap();
+        assert_eq!(
+            idx.get_repo_by_path(path).unwrap().unwrap().upstream,
+            Some(info)
+        );
+    }
+
+    #[test]
+    fn bulk_tags_count_changed_repos() {
+        let idx = Index::open_in_memory().unwrap();
+        let mut tagged = make_repo("a", "/code/a");
+        tagged.tags = vec!["review".into()];
+        let a = idx.upsert_repo(&tagged).unwrap();
+        let b = idx.upsert_repo(&make_repo("b", "/code/b")).unwrap();
+
+        assert_eq!(idx.add_tag(&[a, b], "review").unwrap(), 1);
+        let filter = RepoFilter::new().tags(["review"]);
+        assert_eq!(idx.count_repos(&filter).unwrap(), 2);
+
+        assert_eq!(idx.remove_tag(&[a, b], "review").unwrap(), 2);
+        assert_eq!(idx.remove_tag(&[a], "review").unwrap(), 0);
+        assert_eq!(idx.count_repos(&filter).unwrap(), 0);
+    }
+
     #[test]
     fn mark_lost_and_forget() {
         let idx = Index::open_in_memory().unwrap();
@@ -783,10 +2639,31 @@ mod tests {
         assert_eq!(r.state, RepoState::Lost);
 
         idx.forget_repo(id).unwrap();
-        assert!(idx
-            .get_repo_by_path(Path::new("/code/doomed"))
+        assert!(
+            idx.get_repo_by_path(Path::new("/code/doomed"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn lost_repos_hidden_consistently() {
+        let idx = Index::open_in_memory().unwrap();
+        let id = idx.upsert_repo(&make_repo("gone", "/code/gone")).unwrap();
+        idx.upsert_repo(&make_repo("here", "/code/here")).unwrap();
+        idx.mark_lost(id).unwrap();
+
+        assert!(idx.get_repo_by_name("gone").unwrap().is_none());
+        let found = idx
+            .get_repo_by_name_including_lost("gone")
             .unwrap()
-            .is_none());
+            .unwrap();
+        assert_eq!(found.state, RepoState::Lost);
+
+        let visible = idx.list_repos(&RepoFilter::new().show_lost(false)).unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0].name, "here");
+        assert_eq!(idx.list_repos(&RepoFilter::new()).unwrap().len(), 2);
     }
 
     #[test]
@@ -808,6 +2685,63 @@ mod tests {
         assert_eq!(summary.active, 2);
         assert_eq!(summary.stale, 1);
         assert_eq!(summary.recent, 0);
+
+        let tallied = FreshnessSummary::tally(&[r1, r2, r3]);
+        assert_eq!((tallied.active, tallied.stale, tallied.recent), (2, 1, 0));
+    }
+
+    #[test]
+    fn recompute_freshness_ages_stored_tiers() {
+        let idx = Index::open_in_memory().unwrap();
+
+        let mut aged = make_repo("aged", "/code/aged");
+        aged.last_commit = Some(Utc::now() - chrono::Duration::days(60));
+        aged.freshness = Freshness::Active;
+        let mut never = make_repo("never", "/code/never");
+        never.last_commit = None;
+        never.freshness = Freshness::Recent;
+        let current = make_repo("current", "/code/current");
+        idx.upsert_repo(&aged).unwrap();
+        idx.upsert_repo(&never).unwrap();
+        idx.upsert_repo(&current).unwrap();
+
+        assert_eq!(idx.recompute_freshness().unwrap(), 2);
+        let tier = |name: &str| idx.get_repo_by_name(name).unwrap().unwrap().freshness;
+        assert_eq!(tier("aged"), Freshness::Stale);
+        assert_eq!(tier("never"), Freshness::Ancient);
+        assert_eq!(tier("current"), Freshness::Active);
+        assert_eq!(idx.recompute_freshness().unwrap(), 0);
+    }
+
+    #[test]
+    fn distinct_values_for_filter_choices() {
+        let idx = Index::open_in_memory().unwrap();
+
+        let mut gateway = make_repo("api-gateway", "/code/api-gateway");
+        gateway.remotes.push(Remote {
+            name: "upstream".into(),
+            url: "ssh://git@git.example.com:2222/platform/infra/api-gateway.git".into(),
+            push_url: None,
+        });
+        let mut plugin = make_repo("plugin", "/code/plugin");
+        plugin.tags = vec!["vim".into(), "rust".into()];
+        plugin.project = None;
+        plugin.managed_by = Some("vim-plug".into());
+        plugin.remotes[0].url = "https://github.com/junegunn/plugin.git".into();
+        idx.upsert_repo(&gateway).unwrap();
+        idx.upsert_repo(&plugin).unwrap();
+
+        assert_eq!(idx.distinct_tags().unwrap(), vec!["backend", "rust", "vim"]);
+        assert_eq!(idx.distinct_projects().unwrap(), vec!["platform"]);
+        assert_eq!(idx.distinct_managed_by().unwrap(), vec!["vim-plug"]);
+        assert_eq!(
+            idx.distinct_orgs().unwrap(),
+            vec!["initech", "junegunn", "platform/infra"]
+        );
+
+        let scoped = idx.with_root(Some("/code/plugin".into()));
+        assert_eq!(scoped.distinct_tags().unwrap(), vec!["rust", "vim"]);
+        assert!(scoped.distinct_projects().unwrap().is_empty());
     }
 
     #[test]
@@ -839,7 +2773,8 @@ mod tests {
 
         assert!(idx.last_scan_time().unwrap().is_none());
 
-        idx.record_scan(&[PathBuf::from("/home/user")], 42).unwrap();
+        idx.record_scan(&[PathBuf::from("/home/user")], 42, &[])
+            .unwrap();
 
         let ts = idx.last_scan_time().unwrap().unwrap();
         assert!(ts <= Utc::now());
@@ -848,6 +2783,26 @@ mod tests {
         assert_eq!(roots, vec![PathBuf::from("/home/user")]);
     }
 
+    #[test]
+    fn scan_errors_keep_only_latest_scan() {
+        let idx = Index::open_in_memory().unwrap();
+        assert!(idx.last_scan_errors().unwrap().is_empty());
+
+        let first = vec![(
+            PathBuf::from("/root/secret"),
+            "permission denied".to_string(),
+        )];
+        idx.record_scan(&[PathBuf::from("/")], 0, &first).unwrap();
+        let errors = idx.last_scan_errors().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, PathBuf::from("/root/secret"));
+        assert_eq!(errors[0].message, "permission denied");
+
+        // A clean scan supersedes the previous errors
+        idx.record_scan(&[PathBuf::from("/")], 0, &[]).unwrap();
+        assert!(idx.last_scan_errors().unwrap().is_empty());
+    }
+
     #[test]
     fn ownership_roundtrips() {
         let idx = Index::open_in_memory().unwrap();
@@ -882,4 +2837,48 @@ mod tests {
             .unwrap();
         assert!(loaded.ownership.is_none());
     }
+
+    #[test]
+    fn search_fulltext_ranks_and_tracks_updates() {
+        let idx = Index::open_in_memory().unwrap();
+        let gateway = idx
+            .upsert_repo(&make_repo("api-gateway", "/code/api-gateway"))
+            .unwrap();
+        idx.upsert_repo(&make_repo("docs", "/code/gateway-notes/docs"))
+            .unwrap();
+        let tools = idx.upsert_repo(&make_repo("tools", "/code/tools")).unwrap();
+
+        // Name hits outrank path hits; prefixes match
+        let names: Vec<_> = idx
+            .search_fulltext("gate")
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["api-gateway", "docs"]);
+
+        // Tags and notes are searchable and follow targeted updates
+        idx.set_tags(tools, &["deploy".into()]).unwrap();
+        idx.set_note(tools, Some("terraform wrappers")).unwrap();
+        assert_eq!(idx.search_fulltext("deploy").unwrap()[0].name, "tools");
+        assert_eq!(idx.search_fulltext("terraform").unwrap()[0].name, "tools");
+        idx.set_tags(tools, &[]).unwrap();
+        assert!(idx.search_fulltext("deploy").unwrap().is_empty());
+
+        // All words must match; FTS syntax in input is inert
+        assert!(idx.search_fulltext("gateway tools").unwrap().is_empty());
+        assert!(idx.search_fulltext("\"OR* -").unwrap().is_empty());
+        assert!(idx.search_fulltext("   ").unwrap().is_empty());
+
+        idx.forget_repo(gateway).unwrap();
+        assert_eq!(idx.search_fulltext("gate").unwrap().len(), 1);
+
+        let scoped = Index::open_in_memory()
+            .unwrap()
+            .with_root(Some("/code/tools".into()));
+        scoped
+            .upsert_repo(&make_repo("api-gateway", "/code/api-gateway"))
+            .unwrap();
+        assert!(scoped.search_fulltext("gateway").unwrap().is_empty());
+    }
 }
@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use super::Index;
+use crate::core::filter::RepoFilter;
+use crate::core::repo::Repo;
+use crate::error::Result;
+
+/// A read-only merged view over several indexes.
+///
+/// The first index is the primary; when the same path appears in more than
+/// one index, the earliest index wins. There are deliberately no write
+/// methods here — upserts go through the primary `Index` directly.
+pub struct MultiIndex {
+    indexes: Vec<Index>,
+}
+
+impl MultiIndex {
+    /// Build a view from a primary index plus any number of extras.
+    pub fn new(primary: Index, extras: Vec<Index>) -> Self {
+        let mut indexes = vec![primary];
+        indexes.extend(extras);
+        Self { indexes }
+    }
+
+    /// The primary index, the only one writes may target.
+    pub fn primary(&self) -> &Index {
+        &self.indexes[0]
+    }
+
+    /// List repos matching the filter across all indexes, deduped by path.
+    /// A path belongs to the first index that has it, so a later index's
+    /// copy never shows up, even when only that copy matches the filter.
+    pub fn list_repos(&self, filter: &RepoFilter) -> Result<Vec<Repo>> {
+        let mut claimed = HashSet::new();
+        let mut repos = Vec::new();
+        for (i, index) in self.indexes.iter().enumerate() {
+            repos.extend(
+                index
+                    .list_repos(filter)?
+                    .into_iter()
+                    .filter(|repo| !claimed.contains(&repo.path)),
+            );
+            if i + 1 < self.indexes.len() {
+                claimed.extend(index.indexed_paths()?);
+            }
+        }
+        Ok(repos)
+    }
+
+    /// Count repos matching the filter across all indexes, deduped by path.
+    pub fn count_repos(&self, filter: &RepoFilter) -> Result<usize> {
+        if self.indexes.len() == 1 {
+            return self.indexes[0].count_repos(filter);
+        }
+        Ok(self.list_repos(filter)?.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn merges_and_dedupes_by_path() {
+        let mine = Index::open_in_memory().unwrap();
+        mine.upsert_repo(&Repo::test_default("shared", "/code/shared"))
+            .unwrap();
+        mine.upsert_repo(&Repo::test_default("mine", "/code/mine"))
+            .unwrap();
+
+        let theirs = Index::open_in_memory().unwrap();
+        let mut shared = Repo::test_default("shared-theirs", "/code/shared");
+        shared.dirty = true;
+        theirs.upsert_repo(&shared).unwrap();
+        theirs
+            .upsert_repo(&Repo::test_default("theirs", "/code/theirs"))
+            .unwrap();
+
+        let view = MultiIndex::new(mine, vec![theirs]);
+        let repos = view.list_repos(&RepoFilter::default()).unwrap();
+        assert_eq!(repos.len(), 3);
+
+        // Primary wins on conflicting paths
+        let shared = repos
+            .iter()
+            .find(|r| r.path == Path::new("/code/shared"))
+            .unwrap();
+        assert_eq!(shared.name, "shared");
+        assert!(!shared.dirty);
+
+        assert_eq!(view.count_repos(&RepoFilter::default()).unwrap(), 3);
+    }
+
+    #[test]
+    fn filters_apply_to_every_index() {
+        let mine = Index::open_in_memory().unwrap();
+        mine.upsert_repo(&Repo::test_default("clean", "/code/clean"))
+            .unwrap();
+
+        let theirs = Index::open_in_memory().unwrap();
+        let mut dirty = Repo::test_default("dirty", "/code/dirty");
+        dirty.dirty = true;
+        theirs.upsert_repo(&dirty).unwrap();
+
+        let view = MultiIndex::new(mine, vec![theirs]);
+        let repos = view.list_repos(&RepoFilter::new().dirty(true)).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].name, "dirty");
+    }
+
+    #[test]
+    fn extra_copies_never_outvote_the_primary() {
+        let mine = Index::open_in_memory().unwrap();
+        mine.upsert_repo(&Repo::test_default("shared", "/code/shared"))
+            .unwrap();
+
+        // A stale copy of the same path that happens to match the filter
+        let theirs = Index::open_in_memory().unwrap();
+        let mut stale = Repo::test_default("shared", "/code/shared");
+        stale.dirty = true;
+        theirs.upsert_repo(&stale).unwrap();
+
+        let view = MultiIndex::new(mine, vec![theirs]);
+        let dirty = RepoFilter::new().dirty(true);
+        assert!(view.list_repos(&dirty).unwrap().is_empty());
+        assert_eq!(view.count_repos(&dirty).unwrap(), 0);
+    }
+}
@@ -3,7 +3,9 @@ use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 
-use crate::core::repo::{Freshness, Ownership, Remote, Repo, RepoState};
+use crate::core::repo::{
+    Freshness, Ownership, Remote, Repo, RepoState, ScanSnapshot, UpstreamInfo,
+};
 
 /// Internal row struct for mapping SQL columns to Repo.
 pub(super) struct RepoRow {
@@ -13,24 +15,44 @@ pub(super) struct RepoRow {
     pub state: String,
     pub default_branch: Option<String>,
     pub current_branch: Option<String>,
+    pub default_branch_drift: bool,
+    pub head_signed: Option<bool>,
+    pub last_fetched: Option<String>,
+    pub commits_last_30d: Option<u32>,
+    pub snapshot_dirty: Option<bool>,
+    pub snapshot_ahead: Option<u32>,
+    pub snapshot_last_commit: Option<String>,
+    pub upstream_description: Option<String>,
+    pub upstream_archived: Option<bool>,
+    pub upstream_stars: Option<u32>,
+    pub upstream_default_branch: Option<String>,
+    pub upstream_fetched_at: Option<String>,
     pub branch_count: u32,
     pub stale_branch_count: u32,
+    pub is_empty: bool,
+    pub branches_truncated: bool,
+    pub nested_in: Option<String>,
+    pub contains_nested: bool,
     pub dirty: bool,
     pub staged: bool,
     pub untracked: bool,
+    pub status_checked: Option<String>,
     pub ahead: u32,
     pub behind: u32,
     pub last_commit: Option<String>,
+    pub last_commit_offset: Option<i32>,
     pub last_verified: Option<String>,
     pub first_seen: String,
     pub freshness: String,
     pub category: Option<String>,
+    pub origin_org: Option<String>,
     pub ownership_type: Option<String>,
     pub ownership_label: Option<String>,
     pub intention: Option<String>,
     pub project: Option<String>,
     pub role: Option<String>,
     pub managed_by: Option<String>,
+    pub note: Option<String>,
 }
 
 impl RepoRow {
@@ -68,35 +90,90 @@ impl RepoRow {
             remotes,
             default_branch: self.default_branch,
             current_branch: self.current_branch,
+            default_branch_drift: self.default_branch_drift,
+            head_signed: self.head_signed,
+            last_fetched: self.last_fetched.as_deref().and_then(parse_dt),
+            commits_last_30d: self.commits_last_30d,
+            scan_snapshot: match (self.snapshot_dirty, self.snapshot_ahead) {
+                (Some(dirty), Some(ahead)) => Some(ScanSnapshot {
+                    dirty,
+                    ahead,
+                    last_commit: self.snapshot_last_commit.as_deref().and_then(parse_dt),
+                }),
+                _ => None,
+            },
+            upstream: self
+                .upstream_fetched_at
+                .as_deref()
+                .and_then(parse_dt)
+                .map(|fetched_at| UpstreamInfo {
+                    description: self.upstream_description,
+                    archived: self.upstream_archived.unwrap_or(false),
+                    stars: self.upstream_stars.unwrap_or(0),
+                    default_branch: self.upstream_default_branch,
+                    fetched_at,
+                }),
             branch_count: self.branch_count,
             stale_branch_count: self.stale_branch_count,
+            is_empty: self.is_empty,
+            branches_truncated: self.branches_truncated,
+            nested_in: self.nested_in.map(PathBuf::from),
+            contains_nested: self.contains_nested,
             dirty: self.dirty,
             staged: self.staged,
             untracked: self.untracked,
+            status_checked: self.status_checked.as_deref().and_then(parse_dt),
             ahead: self.ahead,
             behind: self.behind,
             last_commit: self.last_commit.as_deref().and_then(parse_dt),
+            last_commit_offset: self.last_commit_offset,
             last_verified: self.last_verified.as_deref().and_then(parse_dt),
             first_seen: parse_dt(&self.first_seen).unwrap_or_else(Utc::now),
             freshness,
             category,
+            origin_org: self.origin_org,
             ownership,
             intention,
             managed_by: self.managed_by,
             tags,
             project: self.project,
             role: self.role,
+            note: self.note,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct FreshnessSummary {
     pub active: usize,
     pub recent: usize,
     pub stale: usize,
     pub dormant: usize,
     pub ancient: usize,
+    /// Repos with no commits yet.
+    pub empty: usize,
+}
+
+impl FreshnessSummary {
+    pub fn total(&self) -> usize {
+        self.active + self.recent + self.stale + self.dormant + self.ancient + self.empty
+    }
+
+    /// Count an in-memory set of repos by tier.
+    pub fn tally<'a>(repos: impl IntoIterator<Item = &'a Repo>) -> Self {
+        let mut summary = Self::default();
+        for repo in repos {
+            match repo.freshness {
+                Freshness::Active => summary.active += 1,
+                Freshness::Recent => summary.recent += 1,
+                Freshness::Stale => summary.stale += 1,
+                Freshness::Dormant => summary.dormant += 1,
+                Freshness::Ancient => summary.ancient += 1,
+                Freshness::Empty => summary.empty += 1,
+            }
+        }
+        summary
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -111,3 +188,19 @@ pub struct IndexSummary {
     pub last_scan: Option<DateTime<Utc>>,
     pub roots: Vec<PathBuf>,
 }
+
+/// One URL a remote has pointed at, from `remote_url_history`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteUrlRecord {
+    pub remote: String,
+    pub url: String,
+    /// When an upsert first saw this URL for the remote.
+    pub seen_at: DateTime<Utc>,
+}
+
+/// A path-level error recorded during a scan.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanError {
+    pub path: PathBuf,
+    pub message: String,
+}
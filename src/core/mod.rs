@@ -1,7 +1,13 @@
 pub mod classify;
+pub mod enrich;
 pub mod filter;
 pub mod git_ops;
+pub mod graph;
+pub mod hook;
 pub mod index;
+pub mod mounts;
 pub mod permissions;
 pub mod repo;
 pub mod scanner;
+pub mod sort;
+pub mod time;
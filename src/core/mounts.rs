@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+const MOUNTINFO: &str = "/proc/self/mountinfo";
+
+/// Filesystem types that live on the other end of a network connection.
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "ncpfs",
+    "afs",
+    "9p",
+    "ceph",
+    "glusterfs",
+    "lustre",
+    "gpfs",
+    "davfs",
+    "fuse.sshfs",
+    "sshfs",
+    "fuse.rclone",
+    "fuse.s3fs",
+    "fuse.glusterfs",
+    "fuse.cephfs",
+    "fuse.davfs2",
+];
+
+/// A single mount from `/proc/self/mountinfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    pub mount_point: PathBuf,
+    pub fstype: String,
+}
+
+/// Whether a filesystem type is network-backed.
+pub fn is_network_fs(fstype: &str) -> bool {
+    NETWORK_FS_TYPES.contains(&fstype)
+}
+
+/// Mount points of every network filesystem on this system. Empty when
+/// the mount table is unavailable (non-Linux, restricted /proc).
+pub fn network_mount_points() -> Vec<PathBuf> {
+    let Ok(text) = std::fs::read_to_string(MOUNTINFO) else {
+        return Vec::new();
+    };
+    parse_mountinfo(&text)
+        .into_iter()
+        .filter(|m| is_network_fs(&m.fstype))
+        .map(|m| m.mount_point)
+        .collect()
+}
+
+/// Identity of the filesystem volume holding `path`, for mount-boundary
+/// checks: the device number on Unix, the volume serial number on Windows.
+pub fn device_id(path: &Path) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        path.metadata().ok().map(|m| m.dev())
+    }
+    #[cfg(windows)]
+    {
+        volume_serial(path)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+#[cfg(windows)]
+fn volume_serial(path: &Path) -> Option<u64> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS, GetFileInformationByHandle,
+    };
+
+    // Directories only open with backup semantics; querying needs no access rights
+    let file = std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .ok()?;
+    // SAFETY: the handle stays open for the lifetime of `file`, and `info` is a
+    // plain-data out-parameter the call fully initializes on success.
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    let ok = unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) };
+    (ok != 0).then_some(u64::from(info.dwVolumeSerialNumber))
+}
+
+/// Whether the mount at `mount` is opted into by `boundaries.allow_mounts`:
+/// it is one of the `allowed` paths or sits below one. Allowed paths also
+/// match canonicalized, as mount points are reported that way.
+pub fn mount_allowed(mount: &Path, allowed: &[PathBuf]) -> bool {
+    allowed.iter().any(|allowed| {
+        mount.starts_with(allowed) || mount.starts_with(super::scanner::canonical_path(allowed))
+    })
+}
+
+/// The network mount containing `path`, if any.
+pub fn network_mount_for<'a>(path: &Path, network_mounts: &'a [PathBuf]) -> Option<&'a Path> {
+    network_mounts
+        .iter()
+        .filter(|m| path.starts_with(m))
+        .max_by_key(|m| m.components().count())
+        .map(PathBuf::as_path)
+}
+
+/// Parse the mountinfo format (proc(5)): the mount point is the fifth field,
+/// and the filesystem type is the first field after the ` - ` separator.
+pub fn parse_mountinfo(text: &str) -> Vec<MountEntry> {
+    text.lines()
+        .filter_map(|line| {
+            let (pre, post) = line.split_once(" - ")?;
+            let mount_point = pre.split(' ').nth(4)?;
+            let fstype = post.split(' ').next()?;
+            Some(MountEntry {
+                mount_point: PathBuf::from(unescape(mount_point)),
+                fstype: fstype.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Decode the octal escapes (`\040` for space, etc.) the kernel uses in mount paths.
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(digits) = bytes.get(i + 1..i + 4)
+            && digits.iter().all(|b| (b'0'..=b'7').contains(b))
+        {
+            let code = digits
+                .iter()
+                .fold(0u32, |acc, b| acc * 8 + u32::from(b - b'0'));
+            out.push(code as u8);
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+40 22 0:35 / /mnt/nas rw,relatime shared:20 - nfs4 nas:/export rw,vers=4.2
+41 22 0:36 / /home/me/remote\\040box rw,nosuid shared:21 - fuse.sshfs me@box:/ rw
+42 22 0:37 / /mnt/usb rw,relatime shared:22 - vfat /dev/sdb1 rw
+";
+
+    #[test]
+    fn device_id_is_stable_within_a_filesystem() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        let root = device_id(dir.path());
+        assert!(root.is_some());
+        assert_eq!(device_id(&dir.path().join("sub")), root);
+        assert!(device_id(&dir.path().join("missing")).is_none());
+    }
+
+    #[test]
+    fn parses_mount_points_and_types() {
+        let mounts = parse_mountinfo(SAMPLE);
+        assert_eq!(mounts.len(), 4);
+        assert_eq!(mounts[1].mount_point, PathBuf::from("/mnt/nas"));
+        assert_eq!(mounts[1].fstype, "nfs4");
+        assert_eq!(mounts[2].mount_point, PathBuf::from("/home/me/remote box"));
+        assert_eq!(mounts[2].fstype, "fuse.sshfs");
+    }
+
+    #[test]
+    fn finds_containing_network_mount() {
+        let network: Vec<PathBuf> = parse_mountinfo(SAMPLE)
+            .into_iter()
+            .filter(|m| is_network_fs(&m.fstype))
+            .map(|m| m.mount_point)
+            .collect();
+        assert_eq!(network.len(), 2);
+
+        assert_eq!(
+            network_mount_for(Path::new("/mnt/nas/code/repo"), &network),
+            Some(Path::new("/mnt/nas"))
+        );
+        assert!(network_mount_for(Path::new("/mnt/usb/code"), &network).is_none());
+        assert!(network_mount_for(Path::new("/mnt/nasty"), &network).is_none());
+    }
+
+    #[test]
+    fn allowed_mounts_cover_mounts_below_them() {
+        let allowed = vec![PathBuf::from("/mnt/nas")];
+        assert!(mount_allowed(Path::new("/mnt/nas"), &allowed));
+        assert!(mount_allowed(Path::new("/mnt/nas/share"), &allowed));
+        assert!(!mount_allowed(Path::new("/mnt/nasty"), &allowed));
+        // Allowing a directory inside a mount doesn't open the whole mount
+        let inner = vec![PathBuf::from("/mnt/nas/share")];
+        assert!(!mount_allowed(Path::new("/mnt/nas"), &inner));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn allowed_mounts_match_through_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mount = dir.path().canonicalize().unwrap().join("mnt");
+        std::fs::create_dir(&mount).unwrap();
+        let link = dir.path().join("nas");
+        std::os::unix::fs::symlink(&mount, &link).unwrap();
+        assert!(mount_allowed(&mount, &[link]));
+    }
+}
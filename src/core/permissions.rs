@@ -6,7 +6,9 @@ use crate::config::types::KissaConfig;
 use crate::error::KissaError;
 
 /// Difficulty levels control what operations kissa will perform (ADR-500).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, schemars::JsonSchema,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum DifficultyLevel {
     Readonly,
@@ -63,6 +65,9 @@ impl OperationClass {
 
 /// Resolve the effective difficulty level for a repo path.
 /// Checks per-path overrides first, then interface default (CLI vs MCP).
+/// When several overrides match, the most specific pattern wins (see
+/// `specificity`), and equally specific ones resolve to the stricter level,
+/// so a broad glob can never shadow a narrower one.
 pub fn effective_difficulty(
     repo_path: &Path,
     config: &KissaConfig,
@@ -71,12 +76,13 @@ pub fn effective_difficulty(
     let path_str = repo_path.to_string_lossy();
 
     // Check per-path overrides (glob patterns)
-    for (pattern, level) in &config.overrides {
-        if let Ok(glob) = glob::Pattern::new(pattern) {
-            if glob.matches(&path_str) {
-                return *level;
-            }
-        }
+    let matched = config
+        .overrides
+        .iter()
+        .filter(|(pattern, _)| glob::Pattern::new(pattern).is_ok_and(|g| g.matches(&path_str)))
+        .max_by_key(|(pattern, level)| (specificity(pattern), std::cmp::Reverse(**level)));
+    if let Some((_, level)) = matched {
+        return *level;
     }
 
     // Fall back to interface default
@@ -87,6 +93,22 @@ pub fn effective_difficulty(
     }
 }
 
+/// Whether an override key is a glob rather than a single path.
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// How narrowly a pattern selects paths: the length of its literal prefix
+/// (a plain path is literal throughout), then its count of literal chars.
+fn specificity(pattern: &str) -> (usize, usize) {
+    let prefix = pattern.find(['*', '?', '[']).unwrap_or(pattern.len());
+    let literal = pattern
+        .chars()
+        .filter(|c| !matches!(c, '*' | '?' | '[' | ']'))
+        .count();
+    (prefix, literal)
+}
+
 /// Check whether an operation is permitted for a given repo.
 pub fn check_permission(
     operation: OperationClass,
@@ -124,15 +146,27 @@ mod tests {
 
     #[test]
     fn operation_class_levels() {
-        assert_eq!(OperationClass::Read.required_level(), DifficultyLevel::Readonly);
-        assert_eq!(OperationClass::Write.required_level(), DifficultyLevel::Commit);
-        assert_eq!(OperationClass::Destructive.required_level(), DifficultyLevel::Unsafe);
+        assert_eq!(
+            OperationClass::Read.required_level(),
+            DifficultyLevel::Readonly
+        );
+        assert_eq!(
+            OperationClass::Write.required_level(),
+            DifficultyLevel::Commit
+        );
+        assert_eq!(
+            OperationClass::Destructive.required_level(),
+            DifficultyLevel::Unsafe
+        );
     }
 
     #[test]
     fn cat_mode_names() {
         assert_eq!(DifficultyLevel::Readonly.display_name(true), "napping");
-        assert_eq!(DifficultyLevel::Unsafe.display_name(true), "knocking-things-off-the-counter");
+        assert_eq!(
+            DifficultyLevel::Unsafe.display_name(true),
+            "knocking-things-off-the-counter"
+        );
         assert_eq!(DifficultyLevel::Commit.display_name(false), "commit");
     }
 
@@ -157,11 +191,8 @@ mod tests {
             .overrides
             .insert("/home/user/experiments/*".into(), DifficultyLevel::Force);
 
-        let level = effective_difficulty(
-            Path::new("/home/user/experiments/scratch"),
-            &config,
-            false,
-        );
+        let level =
+            effective_difficulty(Path::new("/home/user/experiments/scratch"), &config, false);
         assert_eq!(level, DifficultyLevel::Force);
 
         // Non-matching path falls back to default
@@ -169,6 +200,29 @@ mod tests {
         assert_eq!(level, DifficultyLevel::Commit);
     }
 
+    #[test]
+    fn broad_override_cannot_shadow_a_narrower_one() {
+        let mut config = default_config();
+        // "/code/*" sorts first, so map order alone would let it win
+        config
+            .overrides
+            .insert("/code/*".into(), DifficultyLevel::Force);
+        config
+            .overrides
+            .insert("/code/prod-*".into(), DifficultyLevel::Readonly);
+        config
+            .overrides
+            .insert("/code/prod-api".into(), DifficultyLevel::Fetch);
+
+        let level = |path: &str| effective_difficulty(Path::new(path), &config, true);
+        assert_eq!(level("/code/prod-web"), DifficultyLevel::Readonly);
+        assert_eq!(level("/code/prod-api"), DifficultyLevel::Fetch);
+        assert_eq!(level("/code/scratch"), DifficultyLevel::Force);
+
+        assert!(is_glob("/code/prod-*"));
+        assert!(!is_glob("/code/prod-api"));
+    }
+
     #[test]
     fn permission_check_allows_read_at_readonly() {
         let config = default_config();
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Offset, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -17,8 +17,42 @@ pub struct Repo {
     pub remotes: Vec<Remote>,
     pub default_branch: Option<String>,
     pub current_branch: Option<String>,
+    /// The primary remote's HEAD names a branch with no local counterpart (e.g. it moved master → main).
+    #[serde(default)]
+    pub default_branch_drift: bool,
+    /// Whether the HEAD commit carries a GPG/SSH signature (not verified).
+    /// None when there is no HEAD commit.
+    #[serde(default)]
+    pub head_signed: Option<bool>,
+    /// When `.git/FETCH_HEAD` was last written, i.e. the last fetch or pull.
+    #[serde(default)]
+    pub last_fetched: Option<DateTime<Utc>>,
+    /// Commits reachable from HEAD made in the last 30 days; None without a HEAD.
+    #[serde(default)]
+    pub commits_last_30d: Option<u32>,
+    /// Vitals as recorded by the last full scan, for "what moved since" checks.
+    #[serde(default)]
+    pub scan_snapshot: Option<ScanSnapshot>,
+    /// Platform API metadata, only present once `kissa enrich` has run.
+    #[serde(default)]
+    pub upstream: Option<UpstreamInfo>,
     pub branch_count: u32,
     pub stale_branch_count: u32,
+    /// Branch counting stopped at `scan.max_branches`, so both counts are
+    /// lower bounds.
+    #[serde(default)]
+    pub branches_truncated: bool,
+    /// HEAD is unborn: the repo has no commits yet.
+    #[serde(default)]
+    pub is_empty: bool,
+    /// The repo whose working tree this one sits inside, when it isn't a
+    /// registered submodule there (usually a stray `git init`).
+    #[serde(default)]
+    pub nested_in: Option<PathBuf>,
+    /// Other indexed repos sit in this one's working tree (the other side of
+    /// `nested_in`).
+    #[serde(default)]
+    pub contains_nested: bool,
 
     // Working tree state
     pub dirty: bool,
@@ -26,15 +60,25 @@ pub struct Repo {
     pub untracked: bool,
     pub ahead: u32,
     pub behind: u32,
+    /// When dirty/staged/untracked were last read from the working tree;
+    /// None if never (every scan so far skipped it).
+    #[serde(default)]
+    pub status_checked: Option<DateTime<Utc>>,
 
     // Timestamps
     pub last_commit: Option<DateTime<Utc>>,
+    /// The committer's UTC offset at `last_commit`, in minutes east of UTC.
+    #[serde(default)]
+    pub last_commit_offset: Option<i32>,
     pub last_verified: Option<DateTime<Utc>>,
     pub first_seen: DateTime<Utc>,
 
     // Classification (ADR-104)
     pub freshness: Freshness,
     pub category: Option<Category>,
+    /// For forks, the org of the upstream repo they were forked from.
+    #[serde(default)]
+    pub origin_org: Option<String>,
     pub ownership: Option<Ownership>,
     pub intention: Option<Intention>,
 
@@ -45,6 +89,8 @@ pub struct Repo {
     pub tags: Vec<String>,
     pub project: Option<String>,
     pub role: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 /// Lifecycle state of a repo in the index.
@@ -72,6 +118,8 @@ pub enum Freshness {
     Stale,
     Dormant,
     Ancient,
+    /// No commits yet: new, not old. Sorts after every dated tier.
+    Empty,
 }
 
 impl Freshness {
@@ -90,6 +138,22 @@ impl Freshness {
         }
     }
 
+    /// Freshness of a repo: `Empty` when it has no commits, otherwise the
+    /// tier for its last commit.
+    pub fn of(last_commit: Option<DateTime<Utc>>, is_empty: bool) -> Self {
+        if is_empty {
+            Freshness::Empty
+        } else {
+            Freshness::from_commit_time(last_commit)
+        }
+    }
+
+    /// Tier as a number for metrics: 0 (active) through 4 (ancient), 5 for
+    /// repos with no commits.
+    pub fn tier(&self) -> u8 {
+        *self as u8
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             Freshness::Active => "active",
@@ -97,6 +161,7 @@ impl Freshness {
             Freshness::Stale => "stale",
             Freshness::Dormant => "dormant",
             Freshness::Ancient => "ancient",
+            Freshness::Empty => "empty",
         }
     }
 }
@@ -115,12 +180,28 @@ pub enum Category {
 pub enum Ownership {
     Personal,
     #[serde(rename = "work")]
-    Work { label: String },
+    Work {
+        label: String,
+    },
     Community,
     ThirdParty,
     Local,
 }
 
+impl Ownership {
+    /// The config spelling, e.g. `work:acme` or `third-party` (inverse of
+    /// `classify::parse_ownership`).
+    pub fn label(&self) -> String {
+        match self {
+            Ownership::Personal => "personal".into(),
+            Ownership::Work { label } => format!("work:{}", label),
+            Ownership::Community => "community".into(),
+            Ownership::ThirdParty => "third-party".into(),
+            Ownership::Local => "local".into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Intention {
@@ -137,6 +218,7 @@ pub enum Intention {
 impl Repo {
     /// Create a new Repo from extracted vitals and discovery path.
     pub fn from_vitals(vitals: RepoVitals, path: std::path::PathBuf) -> Self {
+        let now = chrono::Utc::now();
         Self {
             id: 0,
             name: vitals.name,
@@ -145,28 +227,351 @@ impl Repo {
             remotes: vitals.remotes,
             default_branch: vitals.default_branch,
             current_branch: vitals.current_branch,
+            default_branch_drift: vitals.default_branch_drift,
+            head_signed: vitals.head_signed,
+            last_fetched: vitals.last_fetched,
+            commits_last_30d: vitals.commits_last_30d,
+            scan_snapshot: None,
+            upstream: None,
             branch_count: vitals.branch_count,
             stale_branch_count: vitals.stale_branch_count,
+            branches_truncated: vitals.branches_truncated,
+            is_empty: vitals.is_empty,
+            nested_in: None,
+            contains_nested: false,
             dirty: vitals.dirty,
             staged: vitals.staged,
             untracked: vitals.untracked,
             ahead: vitals.ahead,
             behind: vitals.behind,
+            status_checked: vitals.status_checked.then_some(now),
             last_commit: vitals.last_commit,
-            last_verified: Some(chrono::Utc::now()),
-            first_seen: chrono::Utc::now(),
-            freshness: Freshness::from_commit_time(vitals.last_commit),
+            last_commit_offset: vitals.last_commit_offset,
+            last_verified: Some(now),
+            first_seen: now,
+            freshness: Freshness::of(vitals.last_commit, vitals.is_empty),
             category: None,
+            origin_org: None,
             ownership: None,
             intention: None,
             managed_by: None,
             tags: vec![],
             project: None,
             role: None,
+            note: None,
+        }
+    }
+
+    /// Overwrite the git-derived fields with freshly extracted vitals,
+    /// leaving identity and classification untouched. Vitals that skipped
+    /// the working-tree status keep the cached dirty/staged/untracked.
+    pub fn apply_vitals(&mut self, vitals: RepoVitals) {
+        let now = chrono::Utc::now();
+        self.remotes = vitals.remotes;
+        self.default_branch = vitals.default_branch;
+        self.current_branch = vitals.current_branch;
+        self.default_branch_drift = vitals.default_branch_drift;
+        self.head_signed = vitals.head_signed;
+        self.last_fetched = vitals.last_fetched;
+        self.commits_last_30d = vitals.commits_last_30d;
+        self.branch_count = vitals.branch_count;
+        self.stale_branch_count = vitals.stale_branch_count;
+        self.branches_truncated = vitals.branches_truncated;
+        self.is_empty = vitals.is_empty;
+        if vitals.status_checked {
+            self.dirty = vitals.dirty;
+            self.staged = vitals.staged;
+            self.untracked = vitals.untracked;
+            self.status_checked = Some(now);
+        }
+        self.ahead = vitals.ahead;
+        self.behind = vitals.behind;
+        self.last_commit = vitals.last_commit;
+        self.last_commit_offset = vitals.last_commit_offset;
+        self.freshness = Freshness::of(vitals.last_commit, vitals.is_empty);
+        self.last_verified = Some(now);
+    }
+
+    /// Describe how the current vitals differ from the last scan's snapshot.
+    /// Empty when unchanged or when the repo has never been snapshotted.
+    pub fn scan_changes(&self) -> Vec<String> {
+        let Some(ref snap) = self.scan_snapshot else {
+            return Vec::new();
+        };
+        let mut changes = Vec::new();
+        if snap.last_commit != self.last_commit {
+            changes.push("new commits".to_string());
+        }
+        if snap.dirty != self.dirty {
+            changes.push(
+                if self.dirty {
+                    "became dirty"
+                } else {
+                    "became clean"
+                }
+                .to_string(),
+            );
+        }
+        if snap.ahead != self.ahead {
+            changes.push(format!("ahead {} → {}", snap.ahead, self.ahead));
+        }
+        changes
+    }
+
+    /// Fields that differ between `self` (old) and `other` (new), in
+    /// declaration order. Index bookkeeping (`id`, `first_seen`,
+    /// `last_verified`, `scan_snapshot`) is ignored, and tags compare as a
+    /// set so reordering alone is not a change.
+    pub fn diff(&self, other: &Repo) -> Vec<FieldChange> {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map_or_else(|| "none".into(), T::to_string)
+        }
+        fn plain<T: Serialize>(value: &T) -> String {
+            serde_plain::to_string(value).unwrap_or_default()
+        }
+        fn remotes(remotes: &[Remote]) -> String {
+            remotes
+                .iter()
+                .map(|r| match r.push_url {
+                    Some(ref push) => format!("{}={} (push {})", r.name, r.url, push),
+                    None => format!("{}={}", r.name, r.url),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        fn tags(tags: &[String]) -> String {
+            let mut sorted = tags.to_vec();
+            sorted.sort();
+            sorted.join(", ")
+        }
+
+        fn time(value: Option<DateTime<Utc>>) -> String {
+            opt(&value.map(|t| t.to_rfc3339()))
+        }
+
+        let (a, b) = (self, other);
+        let fields = [
+            ("name", a.name.clone(), b.name.clone()),
+            (
+                "path",
+                a.path.display().to_string(),
+                b.path.display().to_string(),
+            ),
+            ("state", plain(&a.state), plain(&b.state)),
+            ("remotes", remotes(&a.remotes), remotes(&b.remotes)),
+            (
+                "default_branch",
+                opt(&a.default_branch),
+                opt(&b.default_branch),
+            ),
+            (
+                "current_branch",
+                opt(&a.current_branch),
+                opt(&b.current_branch),
+            ),
+            (
+                "default_branch_drift",
+                a.default_branch_drift.to_string(),
+                b.default_branch_drift.to_string(),
+            ),
+            ("head_signed", opt(&a.head_signed), opt(&b.head_signed)),
+            ("last_fetched", time(a.last_fetched), time(b.last_fetched)),
+            (
+                "commits_last_30d",
+                opt(&a.commits_last_30d),
+                opt(&b.commits_last_30d),
+            ),
+            (
+                "branch_count",
+                a.branch_count.to_string(),
+                b.branch_count.to_string(),
+            ),
+            (
+                "stale_branch_count",
+                a.stale_branch_count.to_string(),
+                b.stale_branch_count.to_string(),
+            ),
+            (
+                "branches_truncated",
+                a.branches_truncated.to_string(),
+                b.branches_truncated.to_string(),
+            ),
+            ("is_empty", a.is_empty.to_string(), b.is_empty.to_string()),
+            (
+                "nested_in",
+                opt(&a.nested_in.as_ref().map(|p| p.display())),
+                opt(&b.nested_in.as_ref().map(|p| p.display())),
+            ),
+            ("dirty", a.dirty.to_string(), b.dirty.to_string()),
+            ("staged", a.staged.to_string(), b.staged.to_string()),
+            (
+                "untracked",
+                a.untracked.to_string(),
+                b.untracked.to_string(),
+            ),
+            ("ahead", a.ahead.to_string(), b.ahead.to_string()),
+            ("behind", a.behind.to_string(), b.behind.to_string()),
+            ("last_commit", time(a.last_commit), time(b.last_commit)),
+            (
+                "last_commit_offset",
+                opt(&a.last_commit_offset),
+                opt(&b.last_commit_offset),
+            ),
+            (
+                "freshness",
+                a.freshness.label().into(),
+                b.freshness.label().into(),
+            ),
+            (
+                "category",
+                opt(&a.category.as_ref().map(plain)),
+                opt(&b.category.as_ref().map(plain)),
+            ),
+            ("origin_org", opt(&a.origin_org), opt(&b.origin_org)),
+            (
+                "ownership",
+                opt(&a.ownership.as_ref().map(Ownership::label)),
+                opt(&b.ownership.as_ref().map(Ownership::label)),
+            ),
+            (
+                "intention",
+                opt(&a.intention.as_ref().map(plain)),
+                opt(&b.intention.as_ref().map(plain)),
+            ),
+            ("managed_by", opt(&a.managed_by), opt(&b.managed_by)),
+            ("tags", tags(&a.tags), tags(&b.tags)),
+            ("project", opt(&a.project), opt(&b.project)),
+            ("role", opt(&a.role), opt(&b.role)),
+            ("note", opt(&a.note), opt(&b.note)),
+        ];
+        fields
+            .into_iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(field, old, new)| FieldChange { field, old, new })
+            .collect()
+    }
+
+    /// `last_commit` on the committer's own clock, so a 09:00 commit made in
+    /// New York reads 09:00 -05:00 rather than 14:00 UTC. Falls back to UTC
+    /// for rows indexed before the offset was recorded.
+    pub fn last_commit_local(&self) -> Option<DateTime<FixedOffset>> {
+        let offset = self
+            .last_commit_offset
+            .and_then(|minutes| FixedOffset::east_opt(minutes * 60))
+            .unwrap_or_else(|| Utc.fix());
+        Some(self.last_commit?.with_timezone(&offset))
+    }
+
+    /// Whether stale branches have piled up to `threshold` (0 = never warn),
+    /// per `display.stale_branch_warn`.
+    pub fn warn_stale_branches(&self, threshold: u32) -> bool {
+        threshold > 0 && self.stale_branch_count >= threshold
+    }
+
+    /// Checked out on a branch other than the default one. False when
+    /// either branch is unknown, which includes a detached HEAD.
+    pub fn off_default_branch(&self) -> bool {
+        matches!(
+            (&self.current_branch, &self.default_branch),
+            (Some(current), Some(default)) if current != default
+        )
+    }
+
+    /// Whether dirty/staged/untracked predate the last verify, because later
+    /// scans skipped the working-tree status (or none ever read it).
+    pub fn status_is_stale(&self) -> bool {
+        match self.status_checked {
+            Some(checked) => self
+                .last_verified
+                .is_some_and(|verified| verified > checked),
+            None => true,
+        }
+    }
+
+    /// Whether the cached vitals are older than `max_age_secs` (or were never verified).
+    pub fn verify_is_stale(&self, max_age_secs: u64, now: DateTime<Utc>) -> bool {
+        match self.last_verified {
+            Some(verified) => (now - verified).num_seconds() > max_age_secs as i64,
+            None => true,
         }
     }
 }
 
+/// One field that differs between two versions of a repo, as found by
+/// `Repo::diff`. Values are rendered for display; `none` stands for unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// Flat, numeric-first health record for one repo, as fed to metrics
+/// systems by `kissa metrics`. Field names and meanings are a stable
+/// interface: add fields, never rename or repurpose them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepoMetrics {
+    /// Seconds since the Unix epoch when the record was produced.
+    pub ts: i64,
+    pub name: String,
+    pub path: PathBuf,
+    pub lost: bool,
+    pub dirty: bool,
+    pub staged: bool,
+    pub untracked: bool,
+    pub ahead: u32,
+    pub behind: u32,
+    pub branch_count: u32,
+    pub stale_branch_count: u32,
+    /// `Freshness::tier`: 0 (active) through 4 (ancient), 5 (no commits).
+    pub freshness: u8,
+    /// Whole days since the last commit; null without commits.
+    pub days_since_commit: Option<i64>,
+    pub commits_last_30d: Option<u32>,
+    pub remote_count: usize,
+}
+
+impl RepoMetrics {
+    pub fn from_repo(repo: &Repo, now: DateTime<Utc>) -> Self {
+        Self {
+            ts: now.timestamp(),
+            name: repo.name.clone(),
+            path: repo.path.clone(),
+            lost: repo.state == RepoState::Lost,
+            dirty: repo.dirty,
+            staged: repo.staged,
+            untracked: repo.untracked,
+            ahead: repo.ahead,
+            behind: repo.behind,
+            branch_count: repo.branch_count,
+            stale_branch_count: repo.stale_branch_count,
+            freshness: repo.freshness.tier(),
+            days_since_commit: repo.last_commit.map(|ts| (now - ts).num_days()),
+            commits_last_30d: repo.commits_last_30d,
+            remote_count: repo.remotes.len(),
+        }
+    }
+}
+
+/// The subset of vitals a full scan records, compared against later refreshes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScanSnapshot {
+    pub dirty: bool,
+    pub ahead: u32,
+    pub last_commit: Option<DateTime<Utc>>,
+}
+
+/// What the hosting platform's API said about a repo when `kissa enrich`
+/// last asked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpstreamInfo {
+    pub description: Option<String>,
+    pub archived: bool,
+    pub stars: u32,
+    pub default_branch: Option<String>,
+    pub fetched_at: DateTime<Utc>,
+}
+
 /// Lightweight struct of git-extracted data before index enrichment.
 #[derive(Debug, Clone)]
 pub struct RepoVitals {
@@ -174,25 +579,179 @@ pub struct RepoVitals {
     pub remotes: Vec<Remote>,
     pub default_branch: Option<String>,
     pub current_branch: Option<String>,
+    pub default_branch_drift: bool,
+    pub head_signed: Option<bool>,
+    pub last_fetched: Option<DateTime<Utc>>,
+    pub commits_last_30d: Option<u32>,
     pub branch_count: u32,
     pub stale_branch_count: u32,
+    pub branches_truncated: bool,
     pub dirty: bool,
     pub staged: bool,
     pub untracked: bool,
+    /// False when `scan.compute_status` skipped the working-tree status, so
+    /// dirty/staged/untracked above are placeholders.
+    pub status_checked: bool,
     pub ahead: u32,
     pub behind: u32,
     pub last_commit: Option<DateTime<Utc>>,
+    pub last_commit_offset: Option<i32>,
     pub is_bare: bool,
+    pub is_empty: bool,
+}
+
+#[cfg(test)]
+impl Repo {
+    /// A clean, active, freshly verified repo with no remotes or
+    /// classification, for tests to adjust with struct-update syntax.
+    pub fn test_default(name: &str, path: impl Into<PathBuf>) -> Repo {
+        let now = Utc::now();
+        Repo {
+            id: 0,
+            name: name.to_string(),
+            path: path.into(),
+            state: RepoState::Active,
+            remotes: vec![],
+            default_branch: Some("main".into()),
+            current_branch: Some("main".into()),
+            default_branch_drift: false,
+            head_signed: None,
+            last_fetched: None,
+            commits_last_30d: None,
+            scan_snapshot: None,
+            upstream: None,
+            branch_count: 1,
+            stale_branch_count: 0,
+            branches_truncated: false,
+            is_empty: false,
+            nested_in: None,
+            contains_nested: false,
+            dirty: false,
+            staged: false,
+            untracked: false,
+            ahead: 0,
+            behind: 0,
+            status_checked: None,
+            last_commit: Some(now),
+            last_commit_offset: None,
+            last_verified: Some(now),
+            first_seen: now,
+            freshness: Freshness::Active,
+            category: None,
+            origin_org: None,
+            ownership: None,
+            intention: None,
+            managed_by: None,
+            tags: vec![],
+            project: None,
+            role: None,
+            note: None,
+        }
+    }
+}
+
+#[cfg(test)]
+impl RepoVitals {
+    /// Vitals of an empty-handed, clean work tree whose status was read.
+    pub fn test_default(name: &str) -> RepoVitals {
+        RepoVitals {
+            name: name.to_string(),
+            remotes: vec![],
+            default_branch: None,
+            current_branch: None,
+            default_branch_drift: false,
+            head_signed: None,
+            last_fetched: None,
+            commits_last_30d: None,
+            branch_count: 0,
+            stale_branch_count: 0,
+            branches_truncated: false,
+            dirty: false,
+            staged: false,
+            untracked: false,
+            status_checked: true,
+            ahead: 0,
+            behind: 0,
+            last_commit: None,
+            last_commit_offset: None,
+            is_bare: false,
+            is_empty: false,
+        }
+    }
+}
+
+/// How a file differs between two sides of a diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChange,
+}
+
+impl ChangeKind {
+    /// The one-letter code `git status --short` uses.
+    pub fn letter(&self) -> char {
+        match self {
+            ChangeKind::Added => 'A',
+            ChangeKind::Modified => 'M',
+            ChangeKind::Deleted => 'D',
+            ChangeKind::Renamed => 'R',
+            ChangeKind::TypeChange => 'T',
+        }
+    }
+}
+
+/// One changed file, relative to the repo root.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: PathBuf,
+}
+
+/// A commit whose summary matched a `grep-log` pattern.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogMatch {
+    pub sha: String,
+    pub summary: String,
+    pub time: DateTime<Utc>,
 }
 
 /// Parsed remote URL information.
 #[derive(Debug, Clone)]
 pub struct RemoteInfo {
     pub platform: String,
+    /// Full namespace path, e.g. `org` or `group/subgroup` on GitLab.
     pub org: String,
     pub repo_name: String,
 }
 
+impl RemoteInfo {
+    /// Top-level owner segment of the namespace (`group` for `group/subgroup`).
+    pub fn owner(&self) -> &str {
+        self.org.split('/').next().unwrap_or(&self.org)
+    }
+
+    /// Match an org filter against the namespace, case-insensitively.
+    /// Accepts the full namespace, any ancestor group (`group` matches
+    /// `group/subgroup`), or a trailing suffix (`subgroup` matches `group/subgroup`).
+    pub fn org_matches(&self, filter: &str) -> bool {
+        let ns = self.org.to_lowercase();
+        let filter = filter.trim_matches('/').to_lowercase();
+        ns == filter
+            || ns.starts_with(&format!("{}/", filter))
+            || ns.ends_with(&format!("/{}", filter))
+    }
+
+    /// Case-insensitive `platform/org/repo` key; two remotes with the same
+    /// identity point at the same hosted repository regardless of URL scheme.
+    pub fn identity(&self) -> String {
+        format!("{}/{}/{}", self.platform, self.org, self.repo_name).to_lowercase()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,6 +767,199 @@ mod tests {
         assert_eq!(Freshness::from_commit_time(None), Freshness::Ancient);
     }
 
+    #[test]
+    fn empty_repos_are_not_ancient() {
+        assert_eq!(Freshness::of(None, true), Freshness::Empty);
+        assert_eq!(Freshness::of(None, false), Freshness::Ancient);
+        assert_eq!(Freshness::of(Some(Utc::now()), false), Freshness::Active);
+        assert_eq!(Freshness::Empty.tier(), 5);
+    }
+
+    #[test]
+    fn org_matches_namespace_segments() {
+        let info = RemoteInfo {
+            platform: "gitlab.com".into(),
+            org: "Group/subgroup".into(),
+            repo_name: "project".into(),
+        };
+        assert_eq!(info.owner(), "Group");
+        assert!(info.org_matches("group/subgroup"));
+        assert!(info.org_matches("group"));
+        assert!(info.org_matches("subgroup"));
+        assert!(!info.org_matches("sub"));
+        assert!(!info.org_matches("other"));
+    }
+
+    #[test]
+    fn verify_staleness_threshold() {
+        let now = Utc::now();
+        let mut repo = Repo::from_vitals(RepoVitals::test_default("r"), "/code/r".into());
+        repo.last_verified = Some(now - chrono::Duration::seconds(100));
+        assert!(!repo.verify_is_stale(300, now));
+        assert!(repo.verify_is_stale(60, now));
+        repo.last_verified = None;
+        assert!(repo.verify_is_stale(300, now));
+    }
+
+    #[test]
+    fn metrics_are_flat_and_numeric() {
+        let now = Utc::now();
+        let mut repo = Repo::from_vitals(
+            RepoVitals {
+                commits_last_30d: Some(3),
+                branch_count: 2,
+                stale_branch_count: 1,
+                dirty: true,
+                ahead: 4,
+                last_commit: Some(now - chrono::Duration::days(40)),
+                ..RepoVitals::test_default("r")
+            },
+            "/code/r".into(),
+        );
+        repo.state = RepoState::Lost;
+
+        let json = serde_json::to_value(RepoMetrics::from_repo(&repo, now)).unwrap();
+        assert_eq!(json["freshness"], 2); // stale
+        assert_eq!(json["days_since_commit"], 40);
+        assert_eq!(json["lost"], true);
+        assert_eq!(json["ahead"], 4);
+        assert_eq!(json["ts"], now.timestamp());
+        assert!(
+            json.as_object()
+                .unwrap()
+                .values()
+                .all(|v| !v.is_object() && !v.is_array())
+        );
+    }
+
+    fn diff_repo() -> Repo {
+        Repo::from_vitals(
+            RepoVitals {
+                remotes: vec![Remote {
+                    name: "origin".into(),
+                    url: "git@github.com:me/r.git".into(),
+                    push_url: None,
+                }],
+                default_branch: Some("main".into()),
+                current_branch: Some("main".into()),
+                commits_last_30d: Some(1),
+                branch_count: 1,
+                last_commit: Some(Utc::now()),
+                ..RepoVitals::test_default("r")
+            },
+            "/code/r".into(),
+        )
+    }
+
+    #[test]
+    fn stale_branch_warning_threshold() {
+        let mut repo = diff_repo();
+        repo.stale_branch_count = 3;
+        assert!(repo.warn_stale_branches(3));
+        assert!(!repo.warn_stale_branches(4));
+        assert!(!repo.warn_stale_branches(0), "0 disables the warning");
+    }
+
+    #[test]
+    fn last_commit_local_applies_offset() {
+        let mut repo = diff_repo();
+        repo.last_commit = Some("2026-03-02T14:05:00Z".parse().unwrap());
+        assert_eq!(
+            repo.last_commit_local().unwrap().to_rfc3339(),
+            "2026-03-02T14:05:00+00:00",
+            "unrecorded offset falls back to UTC"
+        );
+        repo.last_commit_offset = Some(-300);
+        assert_eq!(
+            repo.last_commit_local().unwrap().to_rfc3339(),
+            "2026-03-02T09:05:00-05:00"
+        );
+    }
+
+    #[test]
+    fn diff_of_unchanged_repo_is_empty() {
+        let old = diff_repo();
+        let mut new = old.clone();
+        // Bookkeeping moves on every refresh without the repo changing
+        new.id = 7;
+        new.last_verified = Some(Utc::now() + chrono::Duration::hours(1));
+        assert!(old.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_changed_vitals() {
+        let old = diff_repo();
+        let mut new = old.clone();
+        new.dirty = true;
+        new.ahead = 3;
+        new.current_branch = None;
+        new.ownership = Some(Ownership::Work {
+            label: "acme".into(),
+        });
+
+        let change = |field: &'static str, old: &str, new: &str| FieldChange {
+            field,
+            old: old.into(),
+            new: new.into(),
+        };
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                change("current_branch", "main", "none"),
+                change("dirty", "false", "true"),
+                change("ahead", "0", "3"),
+                change("ownership", "none", "work:acme"),
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_compares_tags_as_a_set() {
+        let mut old = diff_repo();
+        old.tags = vec!["rust".into(), "cli".into()];
+        let mut new = old.clone();
+        new.tags = vec!["cli".into(), "rust".into()];
+        assert!(old.diff(&new).is_empty());
+
+        new.tags.push("ci".into());
+        assert_eq!(
+            old.diff(&new),
+            vec![FieldChange {
+                field: "tags",
+                old: "cli, rust".into(),
+                new: "ci, cli, rust".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn scan_changes_compare_against_snapshot() {
+        let now = Utc::now();
+        let mut repo = Repo::from_vitals(
+            RepoVitals {
+                last_commit: Some(now),
+                ..RepoVitals::test_default("r")
+            },
+            "/code/r".into(),
+        );
+        assert!(repo.scan_changes().is_empty());
+
+        repo.scan_snapshot = Some(ScanSnapshot {
+            dirty: false,
+            ahead: 0,
+            last_commit: Some(now),
+        });
+        assert!(repo.scan_changes().is_empty());
+
+        repo.dirty = true;
+        repo.ahead = 2;
+        repo.last_commit = Some(now + chrono::Duration::minutes(5));
+        assert_eq!(
+            repo.scan_changes(),
+            vec!["new commits", "became dirty", "ahead 0 → 2"]
+        );
+    }
+
     #[test]
     fn freshness_ordering() {
         assert!(Freshness::Active < Freshness::Ancient);
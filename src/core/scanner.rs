@@ -1,11 +1,16 @@
-use std::os::unix::fs::MetadataExt;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
 
 use walkdir::WalkDir;
 
 use crate::config::types::ScanConfig;
-use crate::error::Result;
+use crate::error::{KissaError, Result};
+
+use super::git_ops;
+use super::repo::{Repo, RepoVitals};
 
 /// Result of scanning a single discovered .git directory.
 #[derive(Debug, Clone)]
@@ -18,8 +23,19 @@ pub struct DiscoveredRepo {
 pub enum ScanEvent {
     DirectoryEntered(PathBuf),
     RepoFound(PathBuf),
-    Skipped { path: PathBuf, reason: SkipReason },
-    Error { path: PathBuf, error: String },
+    Skipped {
+        path: PathBuf,
+        reason: SkipReason,
+    },
+    Error {
+        path: PathBuf,
+        error: String,
+    },
+    /// Emitted before walking a root that is likely to be slow.
+    Warning {
+        path: PathBuf,
+        message: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +44,16 @@ pub enum SkipReason {
     MountBoundary,
     MaxDepth,
     BlockedMount,
+    NetworkFs,
+}
+
+/// Why a repo read during a scan is kept out of the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSkip {
+    /// Tool-managed, and the scan was asked to prune managed repos.
+    Managed,
+    /// Its primary remote belongs to an org in `scan.exclude_orgs`.
+    ExcludedOrg,
 }
 
 /// Scan result after a full filesystem walk.
@@ -48,30 +74,88 @@ pub struct QuickVerifyResult {
     pub lost: Vec<PathBuf>,
 }
 
+/// Kernel pseudo-filesystems, never walked even when `/` is an allowed root.
+const SYSTEM_DIRS: &[&str] = &["/proc", "/sys", "/dev", "/run"];
+
+/// Directory entries within two levels of a home-directory root past which
+/// a scan warns that it will be slow.
+const HUGE_HOME_ENTRIES: usize = 10_000;
+
 /// Walk configured roots and discover .git directories.
+///
+/// The filesystem root is refused as a scan root unless `allow_huge_root`
+/// is set, and a home directory with a very large tree gets a
+/// `ScanEvent::Warning` before it is walked.
 pub fn full_scan(
     roots: &[PathBuf],
     config: &ScanConfig,
+    allow_huge_root: bool,
     progress: Option<Box<dyn Fn(ScanEvent) + Send>>,
 ) -> Result<ScanResult> {
+    // Check every root up front, so a bad entry fails before any walking
+    let roots = resolve_roots(roots);
+    if !allow_huge_root && let Some(root) = roots.iter().find(|r| r.parent().is_none()) {
+        return Err(KissaError::HugeRoot(root.clone()));
+    }
+
     let start = Instant::now();
     let mut discovered = Vec::new();
     let mut skipped_mounts = 0;
     let mut skipped_excluded = 0;
     let mut errors = Vec::new();
 
-    for root in roots {
+    // Network mounts the user hasn't explicitly opted into
+    let network_mounts: Vec<PathBuf> = if config.skip_network_fs {
+        super::mounts::network_mount_points()
+            .into_iter()
+            .filter(|mount| !super::mounts::mount_allowed(mount, &config.boundaries.allow_mounts))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let exclusions = parse_exclusions(&config.exclude);
+    let home = dirs::home_dir().map(|h| canonical_path(&h));
+
+    // Roots are canonical: walk the real location, so a symlinked or
+    // `./`-style root finds the same paths as the plain one
+    for root in &roots {
+        if home.as_ref() == Some(root)
+            && let Some(ref cb) = progress
+            && exceeds_entries(root, &exclusions, HUGE_HOME_ENTRIES)
+        {
+            cb(ScanEvent::Warning {
+                path: root.clone(),
+                message: format!(
+                    "over {} entries near the top of your home directory; this scan may be \
+                     slow, consider pointing scan.roots at where your repos live",
+                    HUGE_HOME_ENTRIES
+                ),
+            });
+        }
+
+        // A root that is itself a repo counts whatever max_depth says; the
+        // walk below then only looks for repos nested in its working tree
+        let root_repo = repo_at(root);
+        if let Some(ref repo) = root_repo {
+            if let Some(ref cb) = progress {
+                cb(ScanEvent::RepoFound(repo.path.clone()));
+            }
+            discovered.push(repo.clone());
+            if repo.is_bare || repo.path != *root || !config.walk_repo_roots {
+                continue;
+            }
+        }
+
         // Get the device ID of the root to detect mount boundaries
-        let root_dev = root
-            .metadata()
-            .ok()
-            .map(|m| m.dev());
+        let root_dev = super::mounts::device_id(root);
 
         let walker = WalkDir::new(root)
             .max_depth(config.max_depth)
             .follow_links(false);
 
-        for entry in walker {
+        let mut entries = walker.into_iter();
+        while let Some(entry) = entries.next() {
             let entry = match entry {
                 Ok(e) => e,
                 Err(e) => {
@@ -94,8 +178,11 @@ pub fn full_scan(
                 continue;
             }
 
-            // Check exclusion list
-            if is_excluded(path, root, &config.exclude) {
+            // Check exclusion list; prune so a glob like `*/target` covers
+            // everything beneath the match, not just the directory itself
+            if is_excluded(path, root, &exclusions)
+                || SYSTEM_DIRS.iter().any(|d| path == Path::new(d))
+            {
                 skipped_excluded += 1;
                 if let Some(ref cb) = progress {
                     cb(ScanEvent::Skipped {
@@ -103,27 +190,40 @@ pub fn full_scan(
                         reason: SkipReason::Excluded,
                     });
                 }
+                entries.skip_current_dir();
+                continue;
+            }
+
+            // Check network filesystems; prune the whole subtree so we never
+            // stat our way across a slow or hung mount
+            if super::mounts::network_mount_for(path, &network_mounts).is_some() {
+                skipped_mounts += 1;
+                if let Some(ref cb) = progress {
+                    cb(ScanEvent::Skipped {
+                        path: path.to_path_buf(),
+                        reason: SkipReason::NetworkFs,
+                    });
+                }
+                entries.skip_current_dir();
                 continue;
             }
 
             // Check mount boundaries
-            if !config.boundaries.cross_mounts {
-                if let Some(root_dev) = root_dev {
-                    if let Ok(meta) = path.metadata() {
-                        if meta.dev() != root_dev {
-                            // Check allow list
-                            if !config.boundaries.allow_mounts.iter().any(|m| path.starts_with(m)) {
-                                skipped_mounts += 1;
-                                if let Some(ref cb) = progress {
-                                    cb(ScanEvent::Skipped {
-                                        path: path.to_path_buf(),
-                                        reason: SkipReason::MountBoundary,
-                                    });
-                                }
-                                continue;
-                            }
-                        }
+            if !config.boundaries.cross_mounts
+                && let Some(root_dev) = root_dev
+                && let Some(dev) = super::mounts::device_id(path)
+                && dev != root_dev
+            {
+                // Check allow list
+                if !super::mounts::mount_allowed(path, &config.boundaries.allow_mounts) {
+                    skipped_mounts += 1;
+                    if let Some(ref cb) = progress {
+                        cb(ScanEvent::Skipped {
+                            path: path.to_path_buf(),
+                            reason: SkipReason::MountBoundary,
+                        });
                     }
+                    continue;
                 }
             }
 
@@ -147,6 +247,9 @@ pub fn full_scan(
             // Check if this is a .git directory → parent is a work tree repo
             if path.file_name().is_some_and(|n| n == ".git") {
                 let repo_path = path.parent().unwrap_or(path);
+                if root_repo.as_ref().is_some_and(|r| r.path == repo_path) {
+                    continue;
+                }
                 if let Some(ref cb) = progress {
                     cb(ScanEvent::RepoFound(repo_path.to_path_buf()));
                 }
@@ -185,6 +288,76 @@ pub fn full_scan(
     })
 }
 
+/// Whether `root` has more than `limit` non-excluded entries within two
+/// levels, counting no further than the limit.
+fn exceeds_entries(root: &Path, exclusions: &[Exclusion], limit: usize) -> bool {
+    WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(2)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !is_excluded(e.path(), root, exclusions))
+        .take(limit + 1)
+        .count()
+        > limit
+}
+
+/// Resolve a configured worker count: 0 means one per available core.
+pub fn effective_jobs(jobs: usize) -> usize {
+    if jobs > 0 {
+        jobs
+    } else {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    }
+}
+
+/// Extract vitals for every discovered repo, as `config` directs, using up
+/// to `jobs` worker threads.
+/// Results come back in discovery order; `on_done` fires once per repo as it
+/// finishes (from worker threads), e.g. to advance a progress bar.
+pub fn extract_all(
+    discovered: &[DiscoveredRepo],
+    jobs: usize,
+    config: &ScanConfig,
+    on_done: impl Fn(&Path) + Sync,
+) -> Vec<Result<RepoVitals>> {
+    map_parallel(discovered, jobs, |repo| {
+        let vitals = git_ops::extract_vitals(&repo.path, config);
+        on_done(&repo.path);
+        vitals
+    })
+}
+
+/// Apply `f` to every item on up to `jobs` worker threads (0 = one per
+/// core), returning results in input order.
+pub fn map_parallel<T: Sync, R: Send>(
+    items: &[T],
+    jobs: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let slots: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+    let workers = effective_jobs(jobs).clamp(1, items.len().max(1));
+
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else { break };
+                    let result = f(item);
+                    *slots[i].lock().unwrap() = Some(result);
+                }
+            });
+        }
+    });
+
+    slots
+        .into_iter()
+        .map(|slot| slot.into_inner().unwrap().expect("every slot is filled"))
+        .collect()
+}
+
 /// Quick verify: stat known repo paths, return which changed/lost.
 pub fn quick_verify(known_paths: &[PathBuf]) -> Result<QuickVerifyResult> {
     let mut unchanged = Vec::new();
@@ -216,27 +389,253 @@ pub fn quick_verify(known_paths: &[PathBuf]) -> Result<QuickVerifyResult> {
     })
 }
 
-/// Check if a path should be excluded.
-fn is_excluded(path: &Path, root: &Path, exclusions: &[String]) -> bool {
-    // Get path relative to root for matching
-    let rel = path.strip_prefix(root).unwrap_or(path);
-    let rel_str = rel.to_string_lossy();
-
-    for pattern in exclusions {
-        // Match against the last component
-        if let Some(name) = path.file_name() {
-            let name_str = name.to_string_lossy();
-            let pattern_trimmed = pattern.trim_end_matches('/');
-            if name_str == pattern_trimmed {
-                return true;
-            }
+/// The canonical form of a repo path: absolute, symlinks and `.`/`..`
+/// resolved, so one physical repo always maps to one index row. Paths that
+/// can't be resolved (a lost repo) come back unchanged.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path)
+        .map(strip_verbatim)
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Scan roots as absolute paths: relative ones resolve against the current
+/// directory, existing ones are canonicalized, and duplicates that turn out
+/// to be the same directory collapse to the first.
+pub fn resolve_roots(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut resolved: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        let absolute = std::path::absolute(root).unwrap_or_else(|_| root.clone());
+        let root = canonical_path(&absolute);
+        if !resolved.contains(&root) {
+            resolved.push(root);
         }
-        // Match against the relative path
-        if rel_str.contains(pattern.trim_end_matches('/')) {
+    }
+    resolved
+}
+
+/// Drop the `\\?\` prefix Windows `canonicalize` adds to drive paths, so
+/// the result compares equal to the plain paths stored by scans.
+pub fn strip_verbatim(path: PathBuf) -> PathBuf {
+    if cfg!(windows)
+        && let Some(rest) = path.to_str().and_then(|s| s.strip_prefix(r"\\?\"))
+        && rest.as_bytes().get(1) == Some(&b':')
+    {
+        return PathBuf::from(rest);
+    }
+    path
+}
+
+/// Cheap existence check for a known repo: a work tree with `.git`, or a bare repo.
+/// Touches only the filesystem metadata, never opens the repository.
+pub fn repo_path_exists(path: &Path) -> bool {
+    path.join(".git").exists() || path.join("HEAD").is_file()
+}
+
+/// What `probe_repo_path` found at a known repo path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathProbe {
+    Present,
+    Missing,
+    /// The filesystem didn't answer in time, e.g. a hung network mount.
+    TimedOut,
+}
+
+/// `repo_path_exists` bounded by `timeout`. The check runs on its own
+/// thread; on a timeout that thread is left behind, since a stat stuck in
+/// the kernel can't be cancelled.
+pub fn probe_repo_path(path: &Path, timeout: Duration) -> PathProbe {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let path = path.to_path_buf();
+    std::thread::spawn(move || {
+        let _ = tx.send(repo_path_exists(&path));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(true) => PathProbe::Present,
+        Ok(false) => PathProbe::Missing,
+        Err(_) => PathProbe::TimedOut,
+    }
+}
+
+/// Whether git has written to the repo at `path` since `cutoff`, judged by
+/// the mtimes of `HEAD`, `index` and `logs/HEAD` in its git dir (checkouts,
+/// commits, resets). Working-tree edits don't show up here. Metadata that
+/// can't be read counts as touched, so the repo gets read in full.
+pub fn git_touched_since(path: &Path, cutoff: SystemTime) -> bool {
+    let dot_git = path.join(".git");
+    let git_dir = if dot_git.is_file() {
+        // A linked worktree or submodule: `.git` holds `gitdir: <path>`
+        let Some(dir) = std::fs::read_to_string(&dot_git)
+            .ok()
+            .and_then(|s| s.strip_prefix("gitdir:").map(|d| PathBuf::from(d.trim())))
+        else {
             return true;
+        };
+        path.join(dir)
+    } else if dot_git.is_dir() {
+        dot_git
+    } else {
+        path.to_path_buf()
+    };
+    let mtimes: Vec<SystemTime> = ["HEAD", "index", "logs/HEAD"]
+        .iter()
+        .filter_map(|name| {
+            std::fs::metadata(git_dir.join(name))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .collect();
+    mtimes.is_empty() || mtimes.iter().any(|&mtime| mtime > cutoff)
+}
+
+/// Decide whether a classified repo read by a scan belongs in the index.
+/// `None` means index it. Shared by every scan entry point, so the CLI and
+/// MCP server agree on what gets catalogued.
+pub fn index_skip(repo: &Repo, config: &ScanConfig, prune_managed: bool) -> Option<IndexSkip> {
+    if prune_managed && repo.managed_by.is_some() {
+        return Some(IndexSkip::Managed);
+    }
+    if !config.exclude_orgs.is_empty() {
+        let info = git_ops::primary_remote(&repo.remotes, &config.primary_remote)
+            .and_then(|r| git_ops::parse_remote_org(&r.url));
+        if info.is_some_and(|info| config.exclude_orgs.iter().any(|org| info.org_matches(org))) {
+            return Some(IndexSkip::ExcludedOrg);
         }
     }
-    false
+    None
+}
+
+/// One `scan.exclude` entry, compiled once per scan.
+enum Exclusion {
+    /// Plain name or path fragment (`node_modules`, `.cargo/registry`):
+    /// matches a run of whole path components anywhere below the root.
+    Components(Vec<String>),
+    /// Glob (`*/target`, `**/node_modules`) matched against the path
+    /// relative to the scan root.
+    Glob(glob::Pattern),
+}
+
+/// Compile exclude entries. Invalid globs are dropped rather than
+/// excluding everything.
+fn parse_exclusions(patterns: &[String]) -> Vec<Exclusion> {
+    patterns
+        .iter()
+        .filter_map(|pattern| {
+            let pattern = pattern.trim_end_matches('/');
+            if pattern.contains(['*', '?', '[']) {
+                glob::Pattern::new(pattern).ok().map(Exclusion::Glob)
+            } else {
+                let parts: Vec<String> = pattern
+                    .split('/')
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect();
+                (!parts.is_empty()).then_some(Exclusion::Components(parts))
+            }
+        })
+        .collect()
+}
+
+/// Check if a path should be excluded.
+fn is_excluded(path: &Path, root: &Path, exclusions: &[Exclusion]) -> bool {
+    // Get path relative to root for matching, with `/` separators on every
+    // platform so patterns like `.cargo/registry` work on Windows too
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let components: Vec<String> = rel
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    let rel_str = components.join("/");
+
+    exclusions.iter().any(|exclusion| match exclusion {
+        Exclusion::Components(parts) => components
+            .windows(parts.len())
+            .any(|window| window == parts.as_slice()),
+        Exclusion::Glob(pattern) => pattern.matches(&rel_str),
+    })
+}
+
+/// The repo at `path`: a work tree (with a `.git` directory, or a `.git`
+/// file as in linked worktrees), a work tree's `.git` directory, or a bare
+/// repo. None for anything else.
+fn repo_at(path: &Path) -> Option<DiscoveredRepo> {
+    if path.file_name().is_some_and(|n| n == ".git")
+        && path.is_dir()
+        && let Some(parent) = path.parent()
+    {
+        return Some(DiscoveredRepo {
+            path: parent.to_path_buf(),
+            is_bare: false,
+        });
+    }
+    if path.join(".git").exists() {
+        Some(DiscoveredRepo {
+            path: path.to_path_buf(),
+            is_bare: false,
+        })
+    } else if is_bare_repo(path) {
+        Some(DiscoveredRepo {
+            path: path.to_path_buf(),
+            is_bare: true,
+        })
+    } else {
+        None
+    }
+}
+
+/// Build a scan result from candidate paths found by something else (e.g.
+/// `fd -g .git`) instead of walking roots. A candidate may be a work tree,
+/// its `.git` directory, or a bare repo; anything else is recorded as an
+/// error and skipped. Exclusions and mount rules don't apply: the caller
+/// chose the paths. Duplicates collapse to the first occurrence.
+pub fn discover_paths(candidates: impl IntoIterator<Item = PathBuf>) -> ScanResult {
+    let start = Instant::now();
+    let mut discovered: Vec<DiscoveredRepo> = Vec::new();
+    let mut errors = Vec::new();
+
+    for candidate in candidates {
+        let path = canonical_path(&candidate);
+        let Some(repo) = repo_at(&path) else {
+            let reason = if path.exists() {
+                "not a git repository"
+            } else {
+                "no such path"
+            };
+            errors.push((path, reason.to_string()));
+            continue;
+        };
+        if !discovered.iter().any(|d| d.path == repo.path) {
+            discovered.push(repo);
+        }
+    }
+
+    ScanResult {
+        discovered,
+        skipped_mounts: 0,
+        skipped_excluded: 0,
+        errors,
+        duration: start.elapsed(),
+    }
+}
+
+/// The repo whose working tree `path` sits in, unless `path` is one of its
+/// registered submodules. Only directories below the innermost scan root
+/// holding `path` count, so a dotfiles repo at `~` doesn't swallow every repo
+/// under home. An outer work tree must be in `known` (the discovered and
+/// indexed repos), or carry a `.git` file as worktrees and submodules do.
+pub fn enclosing_repo(path: &Path, known: &HashSet<PathBuf>, roots: &[PathBuf]) -> Option<PathBuf> {
+    let root = roots
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())?;
+    let outer = path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(root))
+        .find(|dir| {
+            let git = dir.join(".git");
+            git.is_file() || (git.is_dir() && known.contains(*dir))
+        })?;
+    (!git_ops::is_submodule(outer, path)).then(|| outer.to_path_buf())
 }
 
 /// Check if a directory looks like a bare git repo.
@@ -250,14 +649,23 @@ fn is_bare_repo(path: &Path) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::UntrackedMode;
     use std::fs;
 
     fn default_scan_config() -> ScanConfig {
         ScanConfig {
             roots: vec![],
             exclude: vec!["node_modules".into(), ".cache".into()],
+            exclude_orgs: vec![],
             max_depth: 10,
+            walk_repo_roots: true,
             auto_verify_seconds: 300,
+            jobs: 0,
+            skip_network_fs: false,
+            untracked: UntrackedMode::Normal,
+            primary_remote: "origin".into(),
+            max_branches: 500,
+            compute_status: true,
             boundaries: crate::config::types::BoundaryConfig {
                 cross_mounts: true, // Disable mount checking in tests
                 allow_mounts: vec![],
@@ -279,7 +687,7 @@ mod tests {
         fs::create_dir_all(root.join("not-a-repo")).unwrap();
 
         let config = default_scan_config();
-        let result = full_scan(&[root.to_path_buf()], &config, None).unwrap();
+        let result = full_scan(&[root.to_path_buf()], &config, false, None).unwrap();
 
         assert_eq!(result.discovered.len(), 2);
         assert!(!result.discovered[0].is_bare);
@@ -297,12 +705,104 @@ mod tests {
         fs::write(bare.join("HEAD"), "ref: refs/heads/main\n").unwrap();
 
         let config = default_scan_config();
-        let result = full_scan(&[root.to_path_buf()], &config, None).unwrap();
+        let result = full_scan(&[root.to_path_buf()], &config, false, None).unwrap();
 
         assert_eq!(result.discovered.len(), 1);
         assert!(result.discovered[0].is_bare);
     }
 
+    #[test]
+    fn root_that_is_a_repo_is_always_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = canonical_path(dir.path());
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("vendor/lib/.git")).unwrap();
+        let paths = |result: ScanResult| -> Vec<PathBuf> {
+            result.discovered.into_iter().map(|d| d.path).collect()
+        };
+
+        let roots = [root.clone()];
+        let mut config = default_scan_config();
+        let found = paths(full_scan(&roots, &config, false, None).unwrap());
+        assert_eq!(found, vec![root.clone(), root.join("vendor/lib")]);
+
+        // Depth no longer decides whether the root itself counts
+        config.max_depth = 0;
+        let found = paths(full_scan(&roots, &config, false, None).unwrap());
+        assert_eq!(found, vec![root.clone()]);
+
+        // Pointing at the .git dir means its work tree, and nothing below
+        config.max_depth = 10;
+        let found = paths(full_scan(&[root.join(".git")], &config, false, None).unwrap());
+        assert_eq!(found, vec![root.clone()]);
+
+        config.walk_repo_roots = false;
+        let found = paths(full_scan(&roots, &config, false, None).unwrap());
+        assert_eq!(found, vec![root]);
+    }
+
+    #[test]
+    fn discover_paths_accepts_repos_and_reports_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = canonical_path(dir.path());
+        fs::create_dir_all(root.join("work/.git")).unwrap();
+        fs::create_dir_all(root.join("plain")).unwrap();
+        let bare = root.join("repo.git");
+        fs::create_dir_all(bare.join("objects")).unwrap();
+        fs::create_dir_all(bare.join("refs")).unwrap();
+        fs::write(bare.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+
+        let result = discover_paths([
+            root.join("work/.git"),
+            root.join("work"),
+            bare.clone(),
+            root.join("plain"),
+            root.join("gone"),
+        ]);
+
+        let found: Vec<_> = result
+            .discovered
+            .iter()
+            .map(|d| (d.path.clone(), d.is_bare))
+            .collect();
+        assert_eq!(found, vec![(root.join("work"), false), (bare, true)]);
+        let failed: Vec<_> = result.errors.iter().map(|(p, _)| p.clone()).collect();
+        assert_eq!(failed, vec![root.join("plain"), root.join("gone")]);
+    }
+
+    #[test]
+    fn relative_roots_resolve_against_cwd() {
+        let cwd = std::env::current_dir().unwrap();
+        let roots = resolve_roots(&[
+            PathBuf::from("."),
+            cwd.clone(),
+            PathBuf::from("no/such/root"),
+        ]);
+        assert_eq!(roots, vec![canonical_path(&cwd), cwd.join("no/such/root")]);
+        assert!(roots.iter().all(|r| r.is_absolute()));
+    }
+
+    #[test]
+    fn scan_refuses_filesystem_root() {
+        let config = default_scan_config();
+        let err = full_scan(&[PathBuf::from("/")], &config, false, None).unwrap_err();
+        assert!(matches!(err, KissaError::HugeRoot(ref p) if p == Path::new("/")));
+    }
+
+    #[test]
+    fn entry_estimate_stops_at_limit_and_skips_exclusions() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for i in 0..3 {
+            fs::create_dir_all(root.join(format!("node_modules/pkg{}", i))).unwrap();
+        }
+        fs::create_dir_all(root.join("code/a")).unwrap();
+
+        let exclusions = parse_exclusions(&["node_modules".into()]);
+        assert!(!exceeds_entries(root, &exclusions, 2)); // code, code/a
+        assert!(exceeds_entries(root, &[], 2));
+    }
+
     #[test]
     fn scan_excludes_patterns() {
         let dir = tempfile::tempdir().unwrap();
@@ -314,16 +814,61 @@ mod tests {
         fs::create_dir_all(root.join("real-project/.git")).unwrap();
 
         let config = default_scan_config();
-        let result = full_scan(&[root.to_path_buf()], &config, None).unwrap();
+        let result = full_scan(&[root.to_path_buf()], &config, false, None).unwrap();
 
         assert_eq!(result.discovered.len(), 1);
-        assert!(result.discovered[0]
-            .path
-            .to_string_lossy()
-            .contains("real-project"));
+        assert!(
+            result.discovered[0]
+                .path
+                .to_string_lossy()
+                .contains("real-project")
+        );
         assert!(result.skipped_excluded > 0);
     }
 
+    #[test]
+    fn exclusions_match_whole_components_and_globs() {
+        let root = Path::new("/home/me");
+        let exclusions = parse_exclusions(&[
+            "build".into(),
+            ".cargo/registry".into(),
+            "target/".into(),
+            "*/vendor".into(),
+            "**/node_modules".into(),
+            "[".into(),
+        ]);
+        let excluded = |rel: &str| is_excluded(&root.join(rel), root, &exclusions);
+
+        assert!(excluded("code/build"));
+        assert!(!excluded("code/webuild"));
+        assert!(!excluded("code/build-tools"));
+        assert!(excluded(".cargo/registry"));
+        assert!(excluded(".cargo/registry/src"));
+        assert!(!excluded(".cargo/registry-old"));
+        assert!(excluded("proj/target"));
+        assert!(excluded("code/proj/vendor"));
+        assert!(!excluded("vendor"));
+        assert!(excluded("node_modules"));
+        assert!(excluded("web/app/node_modules"));
+        assert!(!excluded("code/proj"));
+    }
+
+    #[test]
+    fn scan_prunes_glob_excluded_subtrees() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("app/vendor/lib/.git")).unwrap();
+        fs::create_dir_all(root.join("webuild/.git")).unwrap();
+
+        let mut config = default_scan_config();
+        config.exclude = vec!["*/vendor".into(), "build".into()];
+        let result = full_scan(&[root.to_path_buf()], &config, false, None).unwrap();
+
+        assert_eq!(result.discovered.len(), 1);
+        assert!(result.discovered[0].path.ends_with("webuild"));
+        assert_eq!(result.skipped_excluded, 1);
+    }
+
     #[test]
     fn scan_respects_max_depth() {
         let dir = tempfile::tempdir().unwrap();
@@ -334,7 +879,7 @@ mod tests {
 
         let mut config = default_scan_config();
         config.max_depth = 3;
-        let result = full_scan(&[root.to_path_buf()], &config, None).unwrap();
+        let result = full_scan(&[root.to_path_buf()], &config, false, None).unwrap();
 
         // Too deep, should not be found
         assert_eq!(result.discovered.len(), 0);
@@ -353,6 +898,7 @@ mod tests {
         let result = full_scan(
             &[root.to_path_buf()],
             &config,
+            false,
             Some(Box::new(move |event| {
                 if matches!(event, ScanEvent::RepoFound(_)) {
                     found_clone.store(true, std::sync::atomic::Ordering::Relaxed);
@@ -375,14 +921,99 @@ mod tests {
         fs::create_dir_all(root_b.join("repo-2/.git")).unwrap();
 
         let config = default_scan_config();
-        let result = full_scan(
-            &[root_a.clone(), root_b.clone()],
-            &config,
-            None,
+        let result = full_scan(&[root_a.clone(), root_b.clone()], &config, false, None).unwrap();
+
+        assert_eq!(result.discovered.len(), 2);
+    }
+
+    #[test]
+    fn enclosing_repo_stays_within_scan_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().canonicalize().unwrap();
+        let root = home.join("code");
+        let app = root.join("app");
+        let stray = app.join("vendor/lib");
+        let linked = root.join("linked");
+        let inside = linked.join("tools");
+        for repo in [&home, &app, &stray, &inside] {
+            git2::Repository::init(repo).unwrap();
+        }
+        fs::create_dir_all(&linked).unwrap();
+        fs::write(
+            linked.join(".git"),
+            "gitdir: /elsewhere/.git/worktrees/linked\n",
         )
         .unwrap();
 
-        assert_eq!(result.discovered.len(), 2);
+        let known: HashSet<PathBuf> = [&home, &app, &stray, &inside]
+            .into_iter()
+            .cloned()
+            .collect();
+        let roots = vec![root.clone()];
+
+        // The dotfiles repo above the root doesn't count
+        assert_eq!(enclosing_repo(&app, &known, &roots), None);
+        assert_eq!(enclosing_repo(&stray, &known, &roots), Some(app.clone()));
+        assert_eq!(enclosing_repo(&inside, &known, &roots), Some(linked));
+        // Outside every root there's nothing to compare against
+        assert_eq!(enclosing_repo(&stray, &known, &[]), None);
+    }
+
+    #[test]
+    fn enclosing_repo_skips_registered_submodules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let app = root.join("app");
+        let outer = git2::Repository::init(&app).unwrap();
+        let sub = outer
+            .submodule("https://example.com/lib.git", Path::new("lib"), true)
+            .unwrap();
+        sub.open().unwrap();
+        drop(sub);
+        let lib = app.join("lib");
+        let known: HashSet<PathBuf> = [app.clone(), lib.clone()].into_iter().collect();
+
+        assert!(git_ops::is_submodule(&app, &lib));
+        assert_eq!(enclosing_repo(&lib, &known, &[root]), None);
+    }
+
+    #[test]
+    fn repo_path_exists_checks_git_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        let plain = dir.path().join("plain");
+        fs::create_dir_all(&plain).unwrap();
+
+        assert!(repo_path_exists(&repo));
+        assert!(!repo_path_exists(&plain));
+        assert!(!repo_path_exists(&dir.path().join("missing")));
+    }
+
+    #[test]
+    fn probe_repo_path_reports_presence() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(probe_repo_path(dir.path(), timeout), PathProbe::Present);
+        assert_eq!(
+            probe_repo_path(&dir.path().join("gone"), timeout),
+            PathProbe::Missing
+        );
+    }
+
+    #[test]
+    fn git_touched_since_reads_git_dir_mtimes() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let before = SystemTime::now() - Duration::from_secs(60);
+        let after = SystemTime::now() + Duration::from_secs(60);
+
+        assert!(git_touched_since(dir.path(), before));
+        assert!(!git_touched_since(dir.path(), after));
+        // Nothing to judge by: read it anyway
+        assert!(git_touched_since(&dir.path().join("gone"), after));
     }
 
     #[test]
@@ -395,11 +1026,83 @@ mod tests {
 
         let missing = dir.path().join("missing");
 
-        let result =
-            quick_verify(&[existing.clone(), missing.clone()]).unwrap();
+        let result = quick_verify(&[existing.clone(), missing.clone()]).unwrap();
 
         assert_eq!(result.changed.len(), 1);
         assert_eq!(result.lost.len(), 1);
         assert_eq!(result.lost[0], missing);
     }
+
+    #[test]
+    fn extract_all_keeps_order_and_reports_each() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut discovered = Vec::new();
+        for name in ["a", "b", "c", "d"] {
+            let path = dir.path().join(name);
+            git2::Repository::init(&path).unwrap();
+            discovered.push(DiscoveredRepo {
+                path,
+                is_bare: false,
+            });
+        }
+        discovered.insert(
+            2,
+            DiscoveredRepo {
+                path: dir.path().join("missing"),
+                is_bare: false,
+            },
+        );
+
+        let done = AtomicUsize::new(0);
+        let results = extract_all(&discovered, 3, &ScanConfig::default(), |_| {
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+
+        assert_eq!(done.load(Ordering::Relaxed), 5);
+        assert_eq!(results.len(), 5);
+        assert!(results[2].is_err());
+        let names: Vec<_> = results
+            .iter()
+            .filter_map(|r| r.as_ref().ok().map(|v| v.name.clone()))
+            .collect();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn index_skip_by_org_and_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("throwaway");
+        let git = git2::Repository::init(&path).unwrap();
+        git.remote("origin", "git@github.com:ci-bot/throwaway.git")
+            .unwrap();
+        git.remote("upstream", "git@github.com:acme/throwaway.git")
+            .unwrap();
+        let vitals = git_ops::extract_vitals(&path, &ScanConfig::default()).unwrap();
+        let mut repo = Repo::from_vitals(vitals, path);
+
+        let mut config = default_scan_config();
+        assert_eq!(index_skip(&repo, &config, false), None);
+
+        config.exclude_orgs = vec!["acme".into()];
+        assert_eq!(
+            index_skip(&repo, &config, false),
+            None,
+            "only the primary remote counts"
+        );
+
+        config.exclude_orgs = vec!["CI-Bot".into()];
+        assert_eq!(
+            index_skip(&repo, &config, false),
+            Some(IndexSkip::ExcludedOrg)
+        );
+
+        repo.managed_by = Some("cargo".into());
+        assert_eq!(index_skip(&repo, &config, true), Some(IndexSkip::Managed));
+    }
+
+    #[test]
+    fn effective_jobs_defaults_to_cores() {
+        assert_eq!(effective_jobs(4), 4);
+        assert!(effective_jobs(0) >= 1);
+    }
 }
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use super::repo::Repo;
+
+/// Fields a repo listing can be ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortKey {
+    Name,
+    Path,
+    LastCommit,
+    Freshness,
+    Ahead,
+    /// Commits in the last 30 days; repos without a count sort first.
+    Activity,
+}
+
+/// Sort repos in place by the given key. Ascending unless `reverse` is set.
+/// Repos without a last commit sort before all dated ones.
+pub fn sort_repos(repos: &mut [Repo], key: SortKey, reverse: bool) {
+    match key {
+        SortKey::Name => repos.sort_by_key(|r| r.name.to_lowercase()),
+        SortKey::Path => repos.sort_by(|a, b| a.path.cmp(&b.path)),
+        SortKey::LastCommit => repos.sort_by_key(|r| r.last_commit),
+        SortKey::Freshness => repos.sort_by_key(|r| r.freshness),
+        SortKey::Ahead => repos.sort_by_key(|r| r.ahead),
+        SortKey::Activity => repos.sort_by_key(|r| r.commits_last_30d),
+    }
+    if reverse {
+        repos.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::repo::*;
+    use chrono::{Duration, Utc};
+
+    fn make_repo(name: &str, days_ago: Option<i64>) -> Repo {
+        let last_commit = days_ago.map(|d| Utc::now() - Duration::days(d));
+        Repo {
+            last_commit,
+            last_verified: None,
+            freshness: Freshness::from_commit_time(last_commit),
+            ..Repo::test_default(name, format!("/code/{}", name))
+        }
+    }
+
+    fn names(repos: &[Repo]) -> Vec<&str> {
+        repos.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_by_last_commit() {
+        let mut repos = vec![
+            make_repo("mid", Some(30)),
+            make_repo("never", None),
+            make_repo("new", Some(1)),
+            make_repo("old", Some(400)),
+        ];
+
+        sort_repos(&mut repos, SortKey::LastCommit, false);
+        assert_eq!(names(&repos), vec!["never", "old", "mid", "new"]);
+
+        sort_repos(&mut repos, SortKey::LastCommit, true);
+        assert_eq!(names(&repos), vec!["new", "mid", "old", "never"]);
+    }
+
+    #[test]
+    fn sort_by_name_case_insensitive() {
+        let mut repos = vec![
+            make_repo("beta", None),
+            make_repo("Alpha", None),
+            make_repo("gamma", None),
+        ];
+        sort_repos(&mut repos, SortKey::Name, false);
+        assert_eq!(names(&repos), vec!["Alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn sort_by_activity() {
+        let mut repos = vec![
+            make_repo("busy", None),
+            make_repo("unborn", None),
+            make_repo("quiet", None),
+        ];
+        repos[0].commits_last_30d = Some(42);
+        repos[2].commits_last_30d = Some(1);
+
+        sort_repos(&mut repos, SortKey::Activity, true);
+        assert_eq!(names(&repos), vec!["busy", "quiet", "unborn"]);
+    }
+
+    #[test]
+    fn sort_key_parses_kebab_case() {
+        assert_eq!(
+            serde_plain::from_str::<SortKey>("last-commit").unwrap(),
+            SortKey::LastCommit
+        );
+        assert!(serde_plain::from_str::<SortKey>("bogus").is_err());
+    }
+}
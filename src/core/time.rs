@@ -0,0 +1,111 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Parse a relative duration like `90s`, `15m`, `12h`, `7d`, `2w`, `3mo` or
+/// `1y`. A month is 30 days and a year 365, matching `humanize_age`.
+/// Returns None for anything else: missing or unknown units, negative or
+/// overflowing amounts, stray whitespace inside.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim();
+    let split = input.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = input.split_at(split);
+    if amount.is_empty() {
+        return None;
+    }
+    let amount: i64 = amount.parse().ok()?;
+    let seconds_per_unit: i64 = match unit.to_ascii_lowercase().as_str() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        "mo" => 30 * 86_400,
+        "y" => 365 * 86_400,
+        _ => return None,
+    };
+    Duration::try_seconds(amount.checked_mul(seconds_per_unit)?)
+}
+
+/// Compact relative age like "3d ago" or "5mo ago".
+pub fn humanize_age(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - then).num_seconds();
+    if secs < 60 {
+        return "just now".into();
+    }
+    let mins = secs / 60;
+    let hours = mins / 60;
+    let days = hours / 24;
+    if mins < 60 {
+        format!("{}m ago", mins)
+    } else if hours < 24 {
+        format!("{}h ago", hours)
+    } else if days < 30 {
+        format!("{}d ago", days)
+    } else if days < 365 {
+        format!("{}mo ago", days / 30)
+    } else {
+        format!("{}y ago", days / 365)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_unit() {
+        let cases = [
+            ("45s", Duration::seconds(45)),
+            ("15m", Duration::minutes(15)),
+            ("12h", Duration::hours(12)),
+            ("7d", Duration::days(7)),
+            ("2w", Duration::days(14)),
+            ("3mo", Duration::days(90)),
+            ("1y", Duration::days(365)),
+            ("0d", Duration::zero()),
+            ("10D", Duration::days(10)),
+            (" 7d ", Duration::days(7)),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_duration(input), Some(expected), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for input in [
+            "",
+            "d",
+            "7",
+            "7x",
+            "7 d",
+            "-7d",
+            "+7d",
+            "7.5d",
+            "7dd",
+            "mo3",
+            "1m1s",
+            "7months",
+            "99999999999999999999d",
+            "9223372036854775807y",
+        ] {
+            assert_eq!(parse_duration(input), None, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn humanize_age_buckets() {
+        let now = Utc::now();
+        assert_eq!(humanize_age(now - Duration::seconds(5), now), "just now");
+        assert_eq!(humanize_age(now - Duration::minutes(12), now), "12m ago");
+        assert_eq!(humanize_age(now - Duration::hours(5), now), "5h ago");
+        assert_eq!(humanize_age(now - Duration::days(3), now), "3d ago");
+        assert_eq!(humanize_age(now - Duration::days(150), now), "5mo ago");
+        assert_eq!(humanize_age(now - Duration::days(800), now), "2y ago");
+    }
+
+    #[test]
+    fn future_timestamps_read_as_just_now() {
+        let now = Utc::now();
+        assert_eq!(humanize_age(now + Duration::hours(2), now), "just now");
+    }
+}
@@ -6,10 +6,7 @@ use crate::core::permissions::DifficultyLevel;
 #[derive(Error, Debug)]
 pub enum KissaError {
     #[error("git error at {path}: {source}")]
-    Git {
-        path: PathBuf,
-        source: git2::Error,
-    },
+    Git { path: PathBuf, source: git2::Error },
 
     #[error("index error: {0}")]
     Index(#[from] rusqlite::Error),
@@ -26,15 +23,38 @@ pub enum KissaError {
     #[error("repo not found: {0}")]
     RepoNotFound(String),
 
-    #[error("operation blocked: {operation} requires difficulty '{required:?}', current is '{current:?}'")]
+    #[error("'{name}' matches several repos: {}", .candidates.join(", "))]
+    AmbiguousName {
+        name: String,
+        candidates: Vec<String>,
+    },
+
+    #[error(
+        "operation blocked: {operation} requires difficulty '{required:?}', current is '{current:?}'"
+    )]
     PermissionDenied {
         operation: String,
         required: DifficultyLevel,
         current: DifficultyLevel,
     },
 
+    #[error("hook {path} failed: {message}")]
+    Hook { path: PathBuf, message: String },
+
+    #[error("{host} API error: {message}")]
+    Api { host: String, message: String },
+
+    #[error("{host} API rate limit exhausted")]
+    RateLimited { host: String },
+
     #[error("path not in scan roots: {0}")]
     OutsideScanRoots(PathBuf),
+
+    #[error(
+        "refusing to scan {0}: it is the filesystem root; set scan.roots (or --roots) \
+         to the directories holding your repos, or pass --allow-huge-root"
+    )]
+    HugeRoot(PathBuf),
 }
 
 pub type Result<T> = std::result::Result<T, KissaError>;
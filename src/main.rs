@@ -3,14 +3,26 @@ use clap::Parser;
 mod cli;
 mod mcp;
 
-fn main() -> anyhow::Result<()> {
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+fn main() {
     let args = cli::Cli::parse();
 
-    if args.mcp {
-        mcp::serve_stdio()?;
-    } else {
-        cli::run(args)?;
-    }
+    let result = cli::select_index(&args).and_then(|()| {
+        if args.mcp {
+            mcp::serve_stdio().map(|()| cli::Outcome::Success)
+        } else {
+            cli::run(args)
+        }
+    });
 
-    Ok(())
+    match result {
+        Ok(outcome) => std::process::exit(outcome.exit_code()),
+        Err(e) => {
+            eprintln!("Error: {:?}", e);
+            std::process::exit(cli::EXIT_ERROR);
+        }
+    }
 }
@@ -1,14 +1,32 @@
 // Terse text formatter for MCP responses (ADR-300)
 //
-// State tags: [listing], [status], [scan_complete], [blocked], [error], [batch]
+// State tags: [listing], [status], [scan_complete], [blocked], [error], [batch],
+//             [override_set], [declined]
 // Next hints: → next: tool1 | tool2
 // Elicitation: ? ask user: question
 
+use std::path::Path;
+
+use chrono::Utc;
+
 use kissa::core::index::{FreshnessSummary, IndexSummary};
-use kissa::core::repo::Repo;
+use kissa::core::repo::{Repo, RepoState};
+use kissa::core::time::humanize_age;
+
+/// Freshness tier plus relative age when the last commit is known.
+fn freshness_with_age(repo: &Repo) -> String {
+    match repo.last_commit {
+        Some(ts) => format!(
+            "{}, {}",
+            repo.freshness.label(),
+            humanize_age(ts, Utc::now())
+        ),
+        None => repo.freshness.label().to_string(),
+    }
+}
 
 /// Format a repo list for MCP output.
-pub fn format_repo_list(repos: &[Repo]) -> String {
+pub fn format_repo_list(repos: &[Repo], stale_branch_warn: u32) -> String {
     let mut lines = Vec::new();
     lines.push(format!("[listing] {} repos", repos.len()));
 
@@ -26,6 +44,20 @@ pub fn format_repo_list(repos: &[Repo]) -> String {
         if let Some(ref mb) = repo.managed_by {
             flags.push(format!("managed:{}", mb));
         }
+        if repo.warn_stale_branches(stale_branch_warn) {
+            flags.push(format!("stale-branches:{}", repo.stale_branch_count));
+        }
+        if repo.off_default_branch()
+            && let Some(ref branch) = repo.current_branch
+        {
+            flags.push(format!("on:{}", branch));
+        }
+        if repo.nested_in.is_some() || repo.contains_nested {
+            flags.push("nested".into());
+        }
+        if repo.state == RepoState::Timeout {
+            flags.push("timeout".into());
+        }
         let flag_str = if flags.is_empty() {
             String::new()
         } else {
@@ -35,7 +67,7 @@ pub fn format_repo_list(repos: &[Repo]) -> String {
         lines.push(format!(
             "  {} ({}) {}{}",
             repo.name,
-            repo.freshness.label(),
+            freshness_with_age(repo),
             repo.path.display(),
             flag_str,
         ));
@@ -45,13 +77,21 @@ pub fn format_repo_list(repos: &[Repo]) -> String {
     lines.join("\n")
 }
 
+/// Format a bare match count for MCP output.
+pub fn format_count(count: usize) -> String {
+    format!(
+        "[count] {} repos\n→ next: search <query> | list_repos",
+        count
+    )
+}
+
 /// Format a single repo status for MCP output.
 pub fn format_repo_status(repo: &Repo) -> String {
     let mut lines = Vec::new();
     lines.push(format!(
         "[status] {} ({})",
         repo.name,
-        repo.freshness.label()
+        freshness_with_age(repo)
     ));
     lines.push(format!("  path: {}", repo.path.display()));
 
@@ -62,6 +102,9 @@ pub fn format_repo_status(repo: &Repo) -> String {
             repo.default_branch.as_deref().unwrap_or("?")
         ));
     }
+    if repo.default_branch_drift {
+        lines.push("  warning: remote default branch has no local branch".into());
+    }
 
     let mut tree = Vec::new();
     if repo.dirty {
@@ -78,27 +121,59 @@ pub fn format_repo_status(repo: &Repo) -> String {
     }
     lines.push(format!("  tree: {}", tree.join(", ")));
 
+    let changes = repo.scan_changes();
+    if !changes.is_empty() {
+        lines.push(format!("  since scan: {}", changes.join(", ")));
+    }
+
+    if let Some(signed) = repo.head_signed {
+        lines.push(format!(
+            "  head: {}",
+            if signed { "signed" } else { "unsigned" }
+        ));
+    }
+
     if repo.ahead > 0 || repo.behind > 0 {
         lines.push(format!("  tracking: ↑{} ↓{}", repo.ahead, repo.behind));
     }
 
+    if let Some(n) = repo.commits_last_30d {
+        lines.push(format!("  activity: {} commits/30d", n));
+    }
+
+    if !repo.remotes.is_empty() {
+        let fetched = repo
+            .last_fetched
+            .map(|ts| humanize_age(ts, Utc::now()))
+            .unwrap_or_else(|| "never".into());
+        lines.push(format!("  fetched: {}", fetched));
+    }
+
     if let Some(ref mb) = repo.managed_by {
         lines.push(format!("  managed_by: {}", mb));
     }
 
+    if let Some(ref org) = repo.origin_org {
+        lines.push(format!("  fork of: {}", org));
+    }
+
     if !repo.remotes.is_empty() {
         for remote in &repo.remotes {
             lines.push(format!("  remote: {} → {}", remote.name, remote.url));
         }
     }
 
+    if let Some(ref note) = repo.note {
+        lines.push(format!("  note: {}", note));
+    }
+
     lines.push("→ next: list_repos | freshness".into());
     lines.join("\n")
 }
 
 /// Format the freshness summary for MCP output.
 pub fn format_freshness(summary: &FreshnessSummary) -> String {
-    let total = summary.active + summary.recent + summary.stale + summary.dormant + summary.ancient;
+    let total = summary.total();
     let mut lines = Vec::new();
     lines.push(format!("[freshness] {} repos", total));
     lines.push(format!("  active:  {}", summary.active));
@@ -106,21 +181,75 @@ pub fn format_freshness(summary: &FreshnessSummary) -> String {
     lines.push(format!("  stale:   {}", summary.stale));
     lines.push(format!("  dormant: {}", summary.dormant));
     lines.push(format!("  ancient: {}", summary.ancient));
+    if summary.empty > 0 {
+        lines.push(format!("  empty:   {}", summary.empty));
+    }
     lines.push("→ next: list_repos --freshness stale | list_repos --dirty".into());
     lines.join("\n")
 }
 
+/// At most this many new repo names are listed after a scan.
+const MAX_NEW_LISTED: usize = 20;
+
 /// Format a scan result for MCP output.
-pub fn format_scan_complete(discovered: usize, indexed: usize, duration_secs: f64) -> String {
+pub fn format_scan_complete(
+    discovered: usize,
+    indexed: usize,
+    skipped: usize,
+    duration_secs: f64,
+    new_repos: &[String],
+) -> String {
     let mut lines = Vec::new();
+    let skipped = if skipped > 0 {
+        format!(", {} skipped (excluded orgs)", skipped)
+    } else {
+        String::new()
+    };
     lines.push(format!(
-        "[scan_complete] {} discovered, {} indexed in {:.1}s",
-        discovered, indexed, duration_secs
+        "[scan_complete] {} discovered, {} indexed{} in {:.1}s",
+        discovered, indexed, skipped, duration_secs
     ));
-    lines.push("→ next: list_repos | freshness".into());
+    if let Some(new_line) = new_repos_line(new_repos) {
+        lines.push(new_line);
+        lines.push("→ next: repo_status <name> | list_repos".into());
+    } else {
+        lines.push("→ next: list_repos | freshness".into());
+    }
     lines.join("\n")
 }
 
+/// Format one chunk of a batched scan. `done` of `total` discovered repos
+/// have been processed so far; `cursor` continues the scan.
+pub fn format_scan_batch(
+    done: usize,
+    total: usize,
+    indexed: usize,
+    new_repos: &[String],
+    cursor: &str,
+) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!(
+        "[batch] {}/{} repos processed, {} indexed this batch",
+        done, total, indexed
+    ));
+    lines.extend(new_repos_line(new_repos));
+    lines.push(format!("→ next: scan cursor={}", cursor));
+    lines.join("\n")
+}
+
+/// "  new (N): a, b, c +K more", capped at `MAX_NEW_LISTED` names.
+fn new_repos_line(new_repos: &[String]) -> Option<String> {
+    if new_repos.is_empty() {
+        return None;
+    }
+    let shown = &new_repos[..new_repos.len().min(MAX_NEW_LISTED)];
+    let mut line = format!("  new ({}): {}", new_repos.len(), shown.join(", "));
+    if new_repos.len() > shown.len() {
+        line.push_str(&format!(" +{} more", new_repos.len() - shown.len()));
+    }
+    Some(line)
+}
+
 /// Format an index summary for MCP output.
 pub fn format_summary(summary: &IndexSummary) -> String {
     let mut lines = Vec::new();
@@ -137,6 +266,66 @@ pub fn format_summary(summary: &IndexSummary) -> String {
     lines.join("\n")
 }
 
+/// Format the refusal of a name that matches several repos under
+/// `display.name_collision = "error"`.
+pub fn format_ambiguous(name: &str, candidates: &[String]) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!(
+        "[error] '{}' matches {} repos:",
+        name,
+        candidates.len()
+    ));
+    for path in candidates {
+        lines.push(format!("  {}", path));
+    }
+    lines.push("→ next: retry with one of these absolute paths".into());
+    lines.join("\n")
+}
+
+/// Format confirmation that a repo was marked lost.
+pub fn format_marked_lost(repo: &Repo) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!("[lost] {} marked lost", repo.name));
+    lines.push(format!("  path: {}", repo.path.display()));
+    lines.push("→ next: list_repos | forget_repo <path>".into());
+    lines.join("\n")
+}
+
+/// Format confirmation that a repo was removed from the index.
+pub fn format_forgotten(repo: &Repo) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!("[forgotten] {} removed from index", repo.name));
+    lines.push(format!("  path: {}", repo.path.display()));
+    lines.push("→ next: list_repos | summary".into());
+    lines.join("\n")
+}
+
+/// Format confirmation that a per-path override was saved.
+pub fn format_override_set(pattern: &str, level: &str, backup: Option<&Path>) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!("[override_set] {} → {}", pattern, level));
+    if let Some(backup) = backup {
+        lines.push(format!("  backup: {}", backup.display()));
+    }
+    lines.push("→ next: retry the blocked operation".into());
+    lines.join("\n")
+}
+
+/// Format the refusal when the client can't ask the user to approve an
+/// override. `level` is the config spelling, since the user pastes it.
+pub fn format_override_unconfirmable(pattern: &str, level: &str) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!(
+        "[blocked] raising {} to '{}' needs the user's approval, and this client can't ask",
+        pattern, level
+    ));
+    lines.push(format!(
+        "? ask user: add \"{}\" = \"{}\" under [overrides] in config.toml",
+        pattern, level
+    ));
+    lines.join("\n")
+}
+
 /// Format a permission denied error for MCP output.
 pub fn format_blocked(operation: &str, required: &str, current: &str) -> String {
     let mut lines = Vec::new();
@@ -145,5 +334,63 @@ pub fn format_blocked(operation: &str, required: &str, current: &str) -> String
         operation, required, current
     ));
     lines.push("? ask user: increase difficulty level or use per-path override".into());
+    lines.push("→ next: request_override path=<repo path> level=<required>".into());
     lines.join("\n")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_complete_lists_new_repos_with_cap() {
+        let none = format_scan_complete(3, 3, 0, 1.0, &[]);
+        assert!(!none.contains("new ("));
+        assert!(!none.contains("skipped"));
+
+        let skipped = format_scan_complete(5, 3, 2, 1.0, &[]);
+        assert!(skipped.contains("3 indexed, 2 skipped (excluded orgs) in 1.0s"));
+
+        let few = format_scan_complete(2, 2, 0, 0.5, &["a".into(), "b".into()]);
+        assert!(few.contains("new (2): a, b"));
+        assert!(!few.contains("more"));
+
+        let many: Vec<String> = (0..25).map(|i| format!("r{}", i)).collect();
+        let out = format_scan_complete(25, 25, 0, 2.0, &many);
+        assert!(out.contains("new (25): r0, r1"));
+        assert!(out.contains("r19 +5 more"));
+        assert!(!out.contains("r20"));
+    }
+
+    #[test]
+    fn scan_batch_carries_cursor() {
+        let text = format_scan_batch(200, 450, 198, &["fresh".into()], "abc:200");
+        assert_eq!(
+            text,
+            "[batch] 200/450 repos processed, 198 indexed this batch\n  new (1): fresh\n→ next: scan cursor=abc:200"
+        );
+        let quiet = format_scan_batch(400, 450, 200, &[], "abc:400");
+        assert!(!quiet.contains("new ("));
+    }
+
+    #[test]
+    fn override_responses() {
+        let set = format_override_set("/code/a", "force", Some(Path::new("/cfg/config.toml.bak")));
+        assert_eq!(
+            set,
+            "[override_set] /code/a → force\n  backup: /cfg/config.toml.bak\n→ next: retry the blocked operation"
+        );
+        let refused = format_override_unconfirmable("/code/*", "unsafe");
+        assert!(refused.starts_with("[blocked]"));
+        assert!(refused.contains("\"/code/*\" = \"unsafe\" under [overrides]"));
+    }
+
+    #[test]
+    fn ambiguous_names_list_candidates() {
+        let text = format_ambiguous("api", &["/code/api-a".into(), "/code/api-b".into()]);
+        assert_eq!(
+            text,
+            "[error] 'api' matches 2 repos:\n  /code/api-a\n  /code/api-b\n→ next: retry with one of these absolute paths"
+        );
+    }
+}
@@ -1,30 +1,61 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use rmcp::handler::server::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{CallToolResult, Content, ServerCapabilities, ServerInfo};
-use rmcp::{tool, tool_handler, tool_router, ErrorData as McpError};
 use rmcp::schemars::JsonSchema;
-use serde::Deserialize;
+use rmcp::service::ElicitationError;
+use rmcp::{ErrorData as McpError, Peer, RoleServer, tool, tool_handler, tool_router};
+use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use kissa::config;
 use kissa::core::classify;
 use kissa::core::filter::RepoFilter;
-use kissa::core::git_ops;
 use kissa::core::index::Index;
-use kissa::core::repo::{Freshness, Repo};
+use kissa::core::permissions::{self, DifficultyLevel, OperationClass};
+use kissa::core::repo::{Freshness, Repo, RepoState};
 use kissa::core::scanner;
+use kissa::error::KissaError;
 
 use super::format;
 
 #[derive(Clone)]
 pub struct KissaServer {
     index: Arc<Mutex<Index>>,
+    /// A batched scan waiting for its next `cursor` call. One at a time:
+    /// starting a new batched scan abandons the previous one.
+    pending_scan: Arc<Mutex<Option<PendingScan>>>,
     tool_router: ToolRouter<Self>,
 }
 
+/// Walk results and running totals carried between batched `scan` calls.
+struct PendingScan {
+    id: String,
+    roots: Vec<PathBuf>,
+    /// Found repos left to index, those ignored with `forget --permanent`
+    /// already dropped.
+    discovered: Vec<scanner::DiscoveredRepo>,
+    ignored: usize,
+    nesting: Arc<Nesting>,
+    next: usize,
+    batch_size: usize,
+    indexed: usize,
+    skipped: usize,
+    new_repos: Vec<String>,
+    errors: Vec<(PathBuf, String)>,
+    started: std::time::Instant,
+}
+
+impl PendingScan {
+    /// The cursor that resumes at `next`; a replayed older cursor won't match.
+    fn cursor(&self) -> String {
+        format!("{}:{}", self.id, self.next)
+    }
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct ListReposParams {
     /// Show only dirty repos
@@ -39,6 +70,27 @@ pub struct ListReposParams {
     /// Filter by remote org/owner
     #[serde(default)]
     pub org: Option<String>,
+    /// Only repos with a remote of this name (e.g. "upstream")
+    #[serde(default)]
+    pub has_remote_named: Option<String>,
+    /// Only repos without a remote of this name
+    #[serde(default)]
+    pub missing_remote: Option<String>,
+    /// Only repos with (true) or without (false) stale branches
+    #[serde(default)]
+    pub has_stale_branches: Option<bool>,
+    /// Only repos checked out on a branch other than their default
+    #[serde(default)]
+    pub off_default: Option<bool>,
+    /// Only repos with a detached HEAD (no branch checked out)
+    #[serde(default)]
+    pub detached: Option<bool>,
+    /// Only repos inside another repo's working tree (not submodules)
+    #[serde(default)]
+    pub nested: Option<bool>,
+    /// Only repos not fetched in this many days (including never fetched)
+    #[serde(default)]
+    pub not_fetched_since_days: Option<i64>,
     /// Filter by freshness tier (active, recent, stale, dormant, ancient)
     #[serde(default)]
     pub freshness: Option<String>,
@@ -48,7 +100,7 @@ pub struct ListReposParams {
     /// Filter by path prefix
     #[serde(default)]
     pub path_prefix: Option<String>,
-    /// Filter by ownership
+    /// Filter by ownership: personal, work, work:label (globs like work:acme-* allowed), community, third-party, local
     #[serde(default)]
     pub ownership: Option<String>,
     /// Filter by intention
@@ -60,18 +112,39 @@ pub struct ListReposParams {
     /// Filter by tags (all must match)
     #[serde(default)]
     pub tags: Option<Vec<String>>,
+    /// Filter by tags (any one may match)
+    #[serde(default)]
+    pub tags_any: Option<Vec<String>>,
     /// Show only managed repos (true), only unmanaged (false), or all (omit)
     #[serde(default)]
     pub managed: Option<bool>,
     /// Filter by managing tool name (e.g., "lazy.nvim")
     #[serde(default)]
     pub managed_by: Option<String>,
+    /// Show only archived repos (true), hide archived (false), or all (omit)
+    #[serde(default)]
+    pub archived: Option<bool>,
+    /// Include lost repos (path missing), which are hidden by default
+    #[serde(default)]
+    pub include_lost: Option<bool>,
+    /// Only repos whose path timed out when last checked (slow or unavailable mounts)
+    #[serde(default)]
+    pub timeout: Option<bool>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub struct RepoStatusParams {
     /// Repo name or absolute path
     pub repo: String,
+    /// Let the name match a lost repo, to see its last-known state
+    #[serde(default)]
+    pub include_lost: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RepoParams {
+    /// Repo name or absolute path
+    pub repo: String,
 }
 
 #[derive(Deserialize, JsonSchema)]
@@ -79,19 +152,50 @@ pub struct ScanParams {
     /// Override scan roots (paths)
     #[serde(default)]
     pub roots: Option<Vec<String>>,
+    /// List the names of repos that weren't in the index before (default true)
+    #[serde(default)]
+    pub list_new: Option<bool>,
+    /// Index at most this many repos per call and return a `[batch]` result
+    /// with a cursor; omit to scan everything in one call
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+    /// Cursor from a previous `[batch]` result; continues that scan
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub struct SearchParams {
-    /// Search query (matches name, path, tags)
+    /// Search words, matched as prefixes against name, path, tags and note (all must match)
     pub query: String,
+    /// Return only the number of matches instead of the repo list
+    #[serde(default)]
+    pub count_only: Option<bool>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct RequestOverrideParams {
+    /// Absolute repo path, or a glob such as /home/me/work/*
+    pub path: String,
+    /// Difficulty to allow there: readonly, fetch, commit, force or unsafe
+    pub level: DifficultyLevel,
+}
+
+/// The user's answer when asked to approve an override.
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct OverrideApproval {
+    /// Allow this override and save it to config.toml
+    approve: bool,
+}
+
+rmcp::elicit_safe!(OverrideApproval);
+
 #[tool_router]
 impl KissaServer {
     pub fn new(index: Arc<Mutex<Index>>) -> Self {
         Self {
             index,
+            pending_scan: Arc::new(Mutex::new(None)),
             tool_router: Self::tool_router(),
         }
     }
@@ -111,31 +215,59 @@ impl KissaServer {
             .as_deref()
             .and_then(|s| serde_plain::from_str::<Freshness>(s).ok());
 
-        let filter = RepoFilter {
-            dirty: p.dirty,
-            unpushed: p.unpushed,
-            orphan: p.orphan,
-            org: p.org,
-            freshness,
-            ownership: p.ownership,
-            intention: p.intention,
-            category: p.category,
-            tags: p.tags,
-            path_prefix: p.path_prefix,
-            has_remote: None,
-            name_contains: p.name,
-            state: None,
-            managed_by: p.managed_by,
-            show_managed: p.managed,
-        };
+        let not_fetched_since = p
+            .not_fetched_since_days
+            .map(|days| {
+                chrono::TimeDelta::try_days(days)
+                    .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+                    .ok_or_else(|| {
+                        McpError::invalid_params(
+                            format!("not_fetched_since_days out of range: {}", days),
+                            None,
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let filter = RepoFilter::new()
+            .maybe(p.dirty, RepoFilter::dirty)
+            .maybe(p.unpushed, RepoFilter::unpushed)
+            .maybe(p.orphan, RepoFilter::orphan)
+            .maybe(p.org, RepoFilter::org)
+            .maybe(p.has_remote_named, RepoFilter::has_remote_named)
+            .maybe(p.missing_remote, RepoFilter::missing_remote)
+            .maybe(p.has_stale_branches, RepoFilter::has_stale_branches)
+            .maybe(p.off_default, RepoFilter::on_non_default_branch)
+            .maybe(p.detached, RepoFilter::detached)
+            .maybe(p.nested, RepoFilter::nested)
+            .maybe(not_fetched_since, RepoFilter::not_fetched_since)
+            .maybe(freshness, RepoFilter::freshness)
+            .maybe(p.ownership, RepoFilter::ownership)
+            .maybe(p.intention, RepoFilter::intention)
+            .maybe(p.category, RepoFilter::category)
+            .maybe(p.tags, RepoFilter::tags)
+            .maybe(p.tags_any, RepoFilter::tags_any)
+            .maybe(p.path_prefix, RepoFilter::path_prefix)
+            .maybe(p.name, RepoFilter::name_contains)
+            .maybe(p.managed_by, RepoFilter::managed_by)
+            .maybe(p.managed, RepoFilter::show_managed)
+            .maybe(p.archived, RepoFilter::show_archived)
+            .maybe(
+                (p.include_lost != Some(true)).then_some(false),
+                RepoFilter::show_lost,
+            )
+            .maybe(
+                (p.timeout == Some(true)).then_some(RepoState::Timeout),
+                RepoFilter::state,
+            );
 
         let index = self.index.lock().await;
-        let repos = index.list_repos(&filter).map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
+        let repos = index
+            .list_repos(&filter)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
         Ok(CallToolResult::success(vec![Content::text(
-            format::format_repo_list(&repos),
+            format::format_repo_list(&repos, stale_branch_warn()),
         )]))
     }
 
@@ -149,21 +281,10 @@ impl KissaServer {
         params: Parameters<RepoStatusParams>,
     ) -> Result<CallToolResult, McpError> {
         let index = self.index.lock().await;
-        let repo = if Path::new(&params.0.repo).is_absolute() {
-            index
-                .get_repo_by_path(Path::new(&params.0.repo))
-                .map_err(|e| McpError::internal_error(e.to_string(), None))?
-        } else {
-            index
-                .get_repo_by_name(&params.0.repo)
-                .map_err(|e| McpError::internal_error(e.to_string(), None))?
-        };
-
-        let Some(repo) = repo else {
-            return Ok(CallToolResult::success(vec![Content::text(format!(
-                "[error] repo not found: {}\n→ next: list_repos | search",
-                params.0.repo
-            ))]));
+        let include_lost = params.0.include_lost.unwrap_or(false);
+        let repo = match resolve_repo(&index, &params.0.repo, include_lost)? {
+            Ok(repo) => repo,
+            Err(response) => return Ok(response),
         };
 
         Ok(CallToolResult::success(vec![Content::text(
@@ -189,73 +310,88 @@ impl KissaServer {
 
     #[tool(
         name = "scan",
-        description = "Scan filesystem for git repositories and update the index.",
+        description = "Scan filesystem for git repositories and update the index. Set batch_size to index in chunks; each [batch] result carries a cursor for the next call.",
         annotations(read_only_hint = false, destructive_hint = false)
     )]
-    async fn scan(
-        &self,
-        params: Parameters<ScanParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let cfg = config::load_config().map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
-
-        let roots: Vec<PathBuf> = if let Some(ref r) = params.0.roots {
-            r.iter().map(PathBuf::from).collect()
-        } else {
-            cfg.scan.roots.clone()
-        };
+    async fn scan(&self, params: Parameters<ScanParams>) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let cfg =
+            config::load_config().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        if p.cursor.is_some() || p.batch_size.is_some() {
+            return self.scan_batch(p, cfg).await;
+        }
 
-        let result = scanner::full_scan(&roots, &cfg.scan, None).map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
+        let roots = scanner::resolve_roots(&match p.roots {
+            Some(ref r) => r.iter().map(PathBuf::from).collect(),
+            None => config::default_scan_roots(&cfg.scan),
+        });
+
+        // Walk and read every repo before touching the index lock, so other
+        // tool calls keep being served for the bulk of a long scan
+        let (indexed, ignored) = {
+            let index = self.index.lock().await;
+            (
+                index.indexed_paths().unwrap_or_default(),
+                index.ignored_paths().unwrap_or_default(),
+            )
+        };
+        let (result, prepared) = {
+            let (roots, cfg) = (roots.clone(), cfg.clone());
+            off_runtime(move || {
+                let result = scanner::full_scan(&roots, &cfg.scan, false, None)?;
+                let nesting = Nesting::new(indexed, &result.discovered, &roots);
+                let (wanted, _) = drop_ignored(result.discovered.clone(), &ignored);
+                let prepared = prepare_discovered(&cfg, &wanted, &nesting);
+                Ok((result, prepared))
+            })
+            .await?
+        };
 
-        let mut upserted = 0;
+        let mut new_repos = Vec::new();
         let index = self.index.lock().await;
+        let upserted = upsert_prepared(&index, prepared.repos, &mut new_repos);
+        let skipped = prepared.skipped;
+        let mut errors = result.errors.clone();
+        errors.extend(prepared.errors);
 
-        for discovered in &result.discovered {
-            if let Ok(vitals) = git_ops::extract_vitals(&discovered.path) {
-                let mut repo = Repo::from_vitals(vitals, discovered.path.clone());
-                classify::classify_repo(&mut repo, &cfg);
-                if index.upsert_repo(&repo).is_ok() {
-                    upserted += 1;
-                }
-            }
-        }
+        let _ = index.record_scan(&roots, upserted, &errors);
 
-        let _ = index.record_scan(&roots, upserted);
+        if !p.list_new.unwrap_or(true) {
+            new_repos.clear();
+        }
 
         Ok(CallToolResult::success(vec![Content::text(
             format::format_scan_complete(
                 result.discovered.len(),
                 upserted,
+                skipped,
                 result.duration.as_secs_f64(),
+                &new_repos,
             ),
         )]))
     }
 
     #[tool(
         name = "search",
-        description = "Search repos by name (fuzzy substring match).",
+        description = "Full-text search over repo name, path, tags and note. Best matches first.",
         annotations(read_only_hint = true)
     )]
-    async fn search(
-        &self,
-        params: Parameters<SearchParams>,
-    ) -> Result<CallToolResult, McpError> {
+    async fn search(&self, params: Parameters<SearchParams>) -> Result<CallToolResult, McpError> {
         let index = self.index.lock().await;
 
-        let filter = RepoFilter {
-            name_contains: Some(params.0.query.clone()),
-            ..Default::default()
-        };
+        let repos = index
+            .search_fulltext(&params.0.query)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-        let repos = index.list_repos(&filter).map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
+        if params.0.count_only.unwrap_or(false) {
+            return Ok(CallToolResult::success(vec![Content::text(
+                format::format_count(repos.len()),
+            )]));
+        }
 
         Ok(CallToolResult::success(vec![Content::text(
-            format::format_repo_list(&repos),
+            format::format_repo_list(&repos, stale_branch_warn()),
         )]))
     }
 
@@ -265,13 +401,11 @@ impl KissaServer {
         annotations(read_only_hint = true)
     )]
     async fn get_config(&self) -> Result<CallToolResult, McpError> {
-        let cfg = config::load_config().map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
+        let cfg =
+            config::load_config().map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-        let json = serde_json::to_string_pretty(&cfg).map_err(|e| {
-            McpError::internal_error(e.to_string(), None)
-        })?;
+        let json = serde_json::to_string_pretty(&cfg)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
@@ -291,6 +425,424 @@ impl KissaServer {
             format::format_summary(&summary),
         )]))
     }
+
+    #[tool(
+        name = "mark_lost",
+        description = "Mark a repo as lost (path no longer exists) without removing it from the index. Requires commit difficulty.",
+        annotations(read_only_hint = false, destructive_hint = false)
+    )]
+    async fn mark_lost(&self, params: Parameters<RepoParams>) -> Result<CallToolResult, McpError> {
+        let cfg =
+            config::load_config().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        let index = self.index.lock().await;
+        let repo = match resolve_repo(&index, &params.0.repo, false)? {
+            Ok(repo) => repo,
+            Err(response) => return Ok(response),
+        };
+
+        if let Err(e) = permissions::check_permission(OperationClass::Write, &repo.path, &cfg, true)
+        {
+            return blocked(e, cfg.display.cat_mode);
+        }
+
+        index
+            .mark_lost(repo.id)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            format::format_marked_lost(&repo),
+        )]))
+    }
+
+    #[tool(
+        name = "forget_repo",
+        description = "Permanently remove a repo entry from the index. Does not touch files on disk. Requires unsafe difficulty.",
+        annotations(read_only_hint = false, destructive_hint = true)
+    )]
+    async fn forget_repo(
+        &self,
+        params: Parameters<RepoParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let cfg =
+            config::load_config().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        let index = self.index.lock().await;
+        let repo = match resolve_repo(&index, &params.0.repo, true)? {
+            Ok(repo) => repo,
+            Err(response) => return Ok(response),
+        };
+
+        if let Err(e) =
+            permissions::check_permission(OperationClass::Destructive, &repo.path, &cfg, true)
+        {
+            return blocked(e, cfg.display.cat_mode);
+        }
+
+        index
+            .forget_repo(repo.id)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            format::format_forgotten(&repo),
+        )]))
+    }
+
+    #[tool(
+        name = "request_override",
+        description = "Ask the user to allow a difficulty level for one repo path or glob, saved to config.toml overrides. Raising the level, or setting any glob, needs the user's confirmation.",
+        annotations(read_only_hint = false, destructive_hint = false)
+    )]
+    async fn request_override(
+        &self,
+        params: Parameters<RequestOverrideParams>,
+        peer: Peer<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let p = params.0;
+        let cfg =
+            config::load_config().map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let cat_mode = cfg.display.cat_mode;
+
+        if !Path::new(&p.path).is_absolute() || glob::Pattern::new(&p.path).is_err() {
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "[error] not an absolute path or valid glob: {}\n→ next: request_override path=/abs/path/to/repo",
+                p.path
+            ))]));
+        }
+
+        // Lowering (or restating) a single path's level grants nothing, so it
+        // needs no approval. A glob covers paths whose levels differ, so it
+        // always does.
+        let current = permissions::effective_difficulty(Path::new(&p.path), &cfg, true);
+        if permissions::is_glob(&p.path) || p.level > current {
+            let question = if permissions::is_glob(&p.path) {
+                format!(
+                    "Allow kissa to operate at '{}' difficulty for every path matching {}? Narrower overrides still apply. This is saved to config.toml.",
+                    p.level.display_name(cat_mode),
+                    p.path
+                )
+            } else {
+                format!(
+                    "Allow kissa to operate at '{}' difficulty (currently '{}') for {}? This is saved to config.toml.",
+                    p.level.display_name(cat_mode),
+                    current.display_name(cat_mode),
+                    p.path
+                )
+            };
+            let approved = match peer.elicit::<OverrideApproval>(question).await {
+                Ok(answer) => answer.is_some_and(|a| a.approve),
+                Err(ElicitationError::CapabilityNotSupported) => {
+                    return Ok(CallToolResult::success(vec![Content::text(
+                        format::format_override_unconfirmable(&p.path, p.level.display_name(false)),
+                    )]));
+                }
+                Err(ElicitationError::UserDeclined | ElicitationError::UserCancelled) => false,
+                Err(e) => return Err(McpError::internal_error(e.to_string(), None)),
+            };
+            if !approved {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "[declined] user did not approve '{}' for {}\n→ next: continue at current difficulty",
+                    p.level.display_name(cat_mode),
+                    p.path
+                ))]));
+            }
+        }
+
+        let backup = config::set_override_in_file(config::config_path(), &p.path, p.level)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        Ok(CallToolResult::success(vec![Content::text(
+            format::format_override_set(&p.path, p.level.display_name(cat_mode), backup.as_deref()),
+        )]))
+    }
+}
+
+/// `display.stale_branch_warn`, falling back to the default when the
+/// config can't be read; listings shouldn't fail over a display setting.
+fn stale_branch_warn() -> u32 {
+    config::load_config()
+        .map(|cfg| cfg.display)
+        .unwrap_or_default()
+        .stale_branch_warn
+}
+
+/// Resolve a repo by absolute path, falling back to fuzzy name lookup with
+/// ties settled per `display.name_collision`. Names only match lost repos
+/// when `include_lost` is set. A miss or an ambiguous name comes back as
+/// the tool response to return instead.
+fn resolve_repo(
+    index: &Index,
+    query: &str,
+    include_lost: bool,
+) -> Result<Result<Repo, CallToolResult>, McpError> {
+    let found = if Path::new(query).is_absolute() {
+        index.get_repo_by_path(Path::new(query))
+    } else {
+        config::load_config().and_then(|cfg| {
+            index.resolve_repo_name(query, include_lost, cfg.display.name_collision)
+        })
+    };
+    match found {
+        Ok(Some(repo)) => Ok(Ok(repo)),
+        Ok(None) => Ok(Err(not_found(query))),
+        Err(KissaError::AmbiguousName { name, candidates }) => Ok(Err(CallToolResult::success(
+            vec![Content::text(format::format_ambiguous(&name, &candidates))],
+        ))),
+        Err(e) => Err(McpError::internal_error(e.to_string(), None)),
+    }
+}
+
+impl KissaServer {
+    /// One chunk of a batched scan: start a new walk (no cursor) or resume the
+    /// pending one, index up to `batch_size` repos, and either hand back a
+    /// cursor or finish with `[scan_complete]`.
+    async fn scan_batch(
+        &self,
+        p: ScanParams,
+        cfg: config::types::KissaConfig,
+    ) -> Result<CallToolResult, McpError> {
+        let mut pending_slot = self.pending_scan.lock().await;
+
+        let mut pending = match p.cursor {
+            Some(ref cursor) => match pending_slot.take() {
+                Some(pending) if pending.cursor() == *cursor => pending,
+                other => {
+                    *pending_slot = other;
+                    return Ok(CallToolResult::success(vec![Content::text(
+                        "[error] unknown or expired scan cursor\n→ next: scan batch_size=<n>",
+                    )]));
+                }
+            },
+            None => {
+                let roots = scanner::resolve_roots(&match p.roots {
+                    Some(ref r) => r.iter().map(PathBuf::from).collect(),
+                    None => config::default_scan_roots(&cfg.scan),
+                });
+                let started = std::time::Instant::now();
+                let (indexed, ignored) = {
+                    let index = self.index.lock().await;
+                    (
+                        index.indexed_paths().unwrap_or_default(),
+                        index.ignored_paths().unwrap_or_default(),
+                    )
+                };
+                let result = {
+                    let (roots, scan) = (roots.clone(), cfg.scan.clone());
+                    off_runtime(move || scanner::full_scan(&roots, &scan, false, None)).await?
+                };
+                let nesting = Arc::new(Nesting::new(indexed, &result.discovered, &roots));
+                let (discovered, ignored) = drop_ignored(result.discovered, &ignored);
+                let id = format!(
+                    "{:x}",
+                    chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+                );
+                PendingScan {
+                    id,
+                    roots,
+                    discovered,
+                    ignored,
+                    nesting,
+                    next: 0,
+                    batch_size: 0,
+                    indexed: 0,
+                    skipped: 0,
+                    new_repos: Vec::new(),
+                    errors: result.errors,
+                    started,
+                }
+            }
+        };
+        if let Some(size) = p.batch_size {
+            pending.batch_size = size.max(1);
+        } else if pending.batch_size == 0 {
+            pending.batch_size = DEFAULT_SCAN_BATCH;
+        }
+
+        let end = (pending.next + pending.batch_size).min(pending.discovered.len());
+        let prepared = {
+            let chunk = pending.discovered[pending.next..end].to_vec();
+            let nesting = pending.nesting.clone();
+            off_runtime(move || Ok(prepare_discovered(&cfg, &chunk, &nesting))).await?
+        };
+        pending.skipped += prepared.skipped;
+        pending.errors.extend(prepared.errors);
+
+        let mut batch_new = Vec::new();
+        let index = self.index.lock().await;
+        let indexed = upsert_prepared(&index, prepared.repos, &mut batch_new);
+        pending.next = end;
+        pending.indexed += indexed;
+        pending.new_repos.extend(batch_new.iter().cloned());
+
+        let list_new = p.list_new.unwrap_or(true);
+        if !list_new {
+            batch_new.clear();
+        }
+
+        if pending.next < pending.discovered.len() {
+            let text = format::format_scan_batch(
+                pending.next,
+                pending.discovered.len(),
+                indexed,
+                &batch_new,
+                &pending.cursor(),
+            );
+            *pending_slot = Some(pending);
+            return Ok(CallToolResult::success(vec![Content::text(text)]));
+        }
+
+        let _ = index.record_scan(&pending.roots, pending.indexed, &pending.errors);
+        if !list_new {
+            pending.new_repos.clear();
+        }
+        Ok(CallToolResult::success(vec![Content::text(
+            format::format_scan_complete(
+                pending.discovered.len() + pending.ignored,
+                pending.indexed,
+                pending.skipped,
+                pending.started.elapsed().as_secs_f64(),
+                &pending.new_repos,
+            ),
+        )]))
+    }
+}
+
+/// Repos indexed per batched `scan` call when a cursor call omits `batch_size`.
+const DEFAULT_SCAN_BATCH: usize = 200;
+
+/// Repos read from disk and classified, ready to go into the index.
+struct PreparedRepos {
+    repos: Vec<Repo>,
+    /// Turned away by `scanner::index_skip`.
+    skipped: usize,
+    errors: Vec<(PathBuf, String)>,
+}
+
+/// What a scan judges nesting against: every repo it found or the index
+/// already knew, below the (resolved) scan roots.
+struct Nesting {
+    known: HashSet<PathBuf>,
+    roots: Vec<PathBuf>,
+}
+
+impl Nesting {
+    fn new(
+        mut known: HashSet<PathBuf>,
+        discovered: &[scanner::DiscoveredRepo],
+        roots: &[PathBuf],
+    ) -> Self {
+        known.extend(discovered.iter().map(|d| d.path.clone()));
+        Nesting {
+            known,
+            roots: roots.to_vec(),
+        }
+    }
+}
+
+/// Split off the repos whose paths were ignored with `forget --permanent`,
+/// before any of them is read. Returns the rest and how many were dropped.
+fn drop_ignored(
+    discovered: Vec<scanner::DiscoveredRepo>,
+    ignored: &HashSet<PathBuf>,
+) -> (Vec<scanner::DiscoveredRepo>, usize) {
+    let total = discovered.len();
+    let wanted: Vec<_> = discovered
+        .into_iter()
+        .filter(|d| !ignored.contains(&scanner::canonical_path(&d.path)))
+        .collect();
+    let dropped = total - wanted.len();
+    (wanted, dropped)
+}
+
+/// Extract and classify `discovered`, the slow part of a scan. Needs no
+/// index access, so it runs without holding the index lock.
+fn prepare_discovered(
+    cfg: &config::types::KissaConfig,
+    discovered: &[scanner::DiscoveredRepo],
+    nesting: &Nesting,
+) -> PreparedRepos {
+    let mut prepared = PreparedRepos {
+        repos: Vec::new(),
+        skipped: 0,
+        errors: Vec::new(),
+    };
+    let extracted = scanner::extract_all(discovered, cfg.scan.jobs, &cfg.scan, |_| {});
+    for (discovered, vitals) in discovered.iter().zip(extracted) {
+        match vitals {
+            Ok(vitals) => {
+                let mut repo = Repo::from_vitals(vitals, discovered.path.clone());
+                if !discovered.is_bare {
+                    repo.nested_in =
+                        scanner::enclosing_repo(&repo.path, &nesting.known, &nesting.roots);
+                }
+                classify::classify_repo(&mut repo, cfg);
+                if scanner::index_skip(&repo, &cfg.scan, false).is_some() {
+                    prepared.skipped += 1;
+                } else {
+                    prepared.repos.push(repo);
+                }
+            }
+            Err(e) => prepared
+                .errors
+                .push((discovered.path.clone(), e.to_string())),
+        }
+    }
+    prepared
+}
+
+/// Upsert prepared repos, snapshotting each for `--changed`. Names of repos
+/// not previously indexed go to `new_repos`. Returns how many were indexed.
+fn upsert_prepared(index: &Index, repos: Vec<Repo>, new_repos: &mut Vec<String>) -> usize {
+    let mut upserted = 0;
+    for repo in repos {
+        let is_new = matches!(index.get_repo_by_path(&repo.path), Ok(None));
+        if let Ok(id) = index.upsert_vitals(&repo)
+            && index.record_scan_snapshot(id).is_ok()
+        {
+            upserted += 1;
+            if is_new {
+                new_repos.push(repo.name);
+            }
+        }
+    }
+    upserted
+}
+
+/// Run blocking filesystem/git work on tokio's blocking pool, keeping the
+/// async workers free to answer other tool calls meanwhile.
+async fn off_runtime<T: Send + 'static>(
+    work: impl FnOnce() -> kissa::error::Result<T> + Send + 'static,
+) -> Result<T, McpError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        .map_err(|e| McpError::internal_error(e.to_string(), None))
+}
+
+/// Terse not-found response shared by single-repo tools.
+fn not_found(query: &str) -> CallToolResult {
+    CallToolResult::success(vec![Content::text(format!(
+        "[error] repo not found: {}\n→ next: list_repos | search",
+        query
+    ))])
+}
+
+/// Turn a permission error into a `[blocked]` response; other errors stay internal.
+fn blocked(err: KissaError, cat_mode: bool) -> Result<CallToolResult, McpError> {
+    match err {
+        KissaError::PermissionDenied {
+            operation,
+            required,
+            current,
+        } => Ok(CallToolResult::success(vec![Content::text(
+            format::format_blocked(
+                &operation,
+                required.display_name(cat_mode),
+                current.display_name(cat_mode),
+            ),
+        )])),
+        other => Err(McpError::internal_error(other.to_string(), None)),
+    }
 }
 
 #[tool_handler]
@@ -300,7 +852,9 @@ impl rmcp::ServerHandler for KissaServer {
             instructions: Some(
                 "kissa: git repo catalogue and topology manager. \
                  Use scan to discover repos, list_repos to query, \
-                 repo_status for details, freshness for overview."
+                 repo_status for details, freshness for overview. \
+                 mark_lost and forget_repo clean up stale entries when \
+                 difficulty allows."
                     .into(),
             ),
             capabilities: ServerCapabilities::builder().enable_tools().build(),
@@ -0,0 +1,81 @@
+//! Fixtures shared by the binary's unit tests, which can't reach the
+//! library's `#[cfg(test)]` ones.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use kissa::core::repo::{Freshness, Repo, RepoState, RepoVitals};
+
+/// A clean, active, freshly verified repo with no remotes or
+/// classification, for tests to adjust with struct-update syntax.
+pub fn repo(name: &str, path: impl Into<PathBuf>) -> Repo {
+    let now = Utc::now();
+    Repo {
+        id: 0,
+        name: name.to_string(),
+        path: path.into(),
+        state: RepoState::Active,
+        remotes: vec![],
+        default_branch: Some("main".into()),
+        current_branch: Some("main".into()),
+        default_branch_drift: false,
+        head_signed: None,
+        last_fetched: None,
+        commits_last_30d: None,
+        scan_snapshot: None,
+        upstream: None,
+        branch_count: 1,
+        stale_branch_count: 0,
+        branches_truncated: false,
+        is_empty: false,
+        nested_in: None,
+        contains_nested: false,
+        dirty: false,
+        staged: false,
+        untracked: false,
+        ahead: 0,
+        behind: 0,
+        status_checked: None,
+        last_commit: Some(now),
+        last_commit_offset: None,
+        last_verified: Some(now),
+        first_seen: now,
+        freshness: Freshness::Active,
+        category: None,
+        origin_org: None,
+        ownership: None,
+        intention: None,
+        managed_by: None,
+        tags: vec![],
+        project: None,
+        role: None,
+        note: None,
+    }
+}
+
+/// Vitals of an empty-handed, clean work tree whose status was read.
+pub fn vitals(name: &str) -> RepoVitals {
+    RepoVitals {
+        name: name.to_string(),
+        remotes: vec![],
+        default_branch: None,
+        current_branch: None,
+        default_branch_drift: false,
+        head_signed: None,
+        last_fetched: None,
+        commits_last_30d: None,
+        branch_count: 0,
+        stale_branch_count: 0,
+        branches_truncated: false,
+        dirty: false,
+        staged: false,
+        untracked: false,
+        status_checked: true,
+        ahead: 0,
+        behind: 0,
+        last_commit: None,
+        last_commit_offset: None,
+        is_bare: false,
+        is_empty: false,
+    }
+}